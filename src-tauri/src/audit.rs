@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::file_store::{sha256_hex, unix_millis, FileStore};
+use crate::journal::{FileChange, JournalEntry};
+use crate::overlay::write_path;
+
+pub const AUDIT_DIRECTORY: &str = "audit";
+
+/// One mutating command as it was run, appended to the audit log of the
+/// workspace it ran in. `changes` hold the content hashes before and after.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AuditRecord {
+    #[ts(type = "number")]
+    pub timestamp: u64,
+    pub command: String,
+    pub description: String,
+    pub workspace: Vec<String>,
+    pub changes: Vec<FileChange>,
+    pub created_directories: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Filters for the audit log, records have to match all that are given.
+/// `path` matches the record when it is one of the changed files or a
+/// directory above one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AuditQuery {
+    pub path: Option<String>,
+    #[ts(type = "number | null")]
+    pub since: Option<u64>,
+    #[ts(type = "number | null")]
+    pub until: Option<u64>,
+    pub command: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl AuditRecord {
+    pub fn new(command: &str, description: String) -> Self {
+        Self {
+            timestamp: unix_millis(),
+            command: command.into(),
+            description,
+            workspace: Vec::new(),
+            changes: Vec::new(),
+            created_directories: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn from_entry(entry: &JournalEntry) -> Self {
+        Self {
+            changes: entry.changes.clone(),
+            created_directories: entry.created_directories.clone(),
+            ..Self::new(&entry.command, entry.description.clone())
+        }
+    }
+
+    fn touches(&self, paths: &[PathBuf]) -> bool {
+        self.changes
+            .iter()
+            .map(|change| &change.path)
+            .chain(self.created_directories.iter())
+            .any(|changed_path| {
+                paths
+                    .iter()
+                    .any(|path| Path::new(changed_path).starts_with(path))
+            })
+    }
+
+    fn matches(&self, query: &AuditQuery, paths: &[PathBuf]) -> bool {
+        query.since.is_none_or(|since| self.timestamp >= since)
+            && query.until.is_none_or(|until| self.timestamp <= until)
+            && query
+                .command
+                .as_ref()
+                .is_none_or(|command| &self.command == command)
+            && (paths.is_empty() || self.touches(paths))
+    }
+}
+
+impl FileStore {
+    // one log per set of configured directories, switching between
+    // installations keeps their histories apart
+    fn audit_log_path(&self) -> PathBuf {
+        let key = sha256_hex(self.workspace.roots().join("\n").as_bytes());
+
+        self.audit_directory.join(format!("{}.jsonl", &key[..16]))
+    }
+
+    /// Appends `record` to the audit log of the current workspace. The log is
+    /// only ever appended to, one JSON record per line.
+    pub fn audit(&self, mut record: AuditRecord) -> Result<(), String> {
+        record.workspace = self.workspace.roots();
+
+        let mut line = serde_json::to_string(&record)
+            .map_err(|err| format!("Failed to serialize the audit record: {}", err))?;
+        line.push('\n');
+
+        // one writer at a time, so that lines never interleave
+        let _guard = self.audit_lock.lock().unwrap();
+
+        fs::create_dir_all(&self.audit_directory).map_err(|err| {
+            format!(
+                "Failed to create '{}': {}",
+                self.audit_directory.display(),
+                err
+            )
+        })?;
+
+        let log_path = self.audit_log_path();
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .and_then(|mut file| {
+                file.write_all(line.as_bytes())?;
+                file.sync_data()
+            })
+            .map_err(|err| {
+                format!(
+                    "Failed to write the audit log '{}': {}",
+                    log_path.display(),
+                    err
+                )
+            })
+    }
+
+    /// The records of the current workspace matching `query`, oldest first.
+    /// With a limit only the most recent ones are returned.
+    pub fn query_audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditRecord>, String> {
+        let log_path = self.audit_log_path();
+        if !log_path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let data = fs::read_to_string(&log_path).map_err(|err| {
+            format!(
+                "Failed to read the audit log '{}': {}",
+                log_path.display(),
+                err
+            )
+        })?;
+
+        // changes to a read-only installation are logged at their overlay path
+        let paths: Vec<PathBuf> = query
+            .path
+            .iter()
+            .flat_map(|path| {
+                let path = PathBuf::from(path);
+                let overlay_path = write_path(&path);
+                [path, overlay_path]
+            })
+            .collect();
+
+        // a line cut short by a crash is skipped, the records after it are kept
+        let mut records: Vec<AuditRecord> = data
+            .lines()
+            .filter_map(|line| serde_json::from_str::<AuditRecord>(line).ok())
+            .filter(|record| record.matches(query, &paths))
+            .collect();
+
+        if let Some(limit) = query.limit {
+            records.drain(..records.len().saturating_sub(limit));
+        }
+
+        Ok(records)
+    }
+}
@@ -0,0 +1,373 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::commons::{parse_numbers, push_analysis_message};
+use crate::configuration_loader::{AnalysisMessageDetails, ConfigDetails, ErrType};
+
+const EPSILON: f64 = 1e-9;
+
+// OrcaSlicer's defaults for bed_mesh_min/bed_mesh_max, meaning "no limit"
+const UNLIMITED_MESH_COORDINATE: f64 = 99999.0;
+
+// Upper bound of printable_height accepted by OrcaSlicer
+const MAX_PRINTABLE_HEIGHT: f64 = 214700.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Parses a point written as `XxY` (points lists) or `X,Y` (single points).
+pub fn parse_point(value: &str) -> Option<Point> {
+    let (x, y) = value.split_once('x').or_else(|| value.split_once(','))?;
+
+    let x: f64 = x.trim().parse().ok()?;
+    let y: f64 = y.trim().parse().ok()?;
+
+    if x.is_finite() && y.is_finite() {
+        Some(Point { x, y })
+    } else {
+        None
+    }
+}
+
+/// Parses a points option, either a JSON array of `XxY` strings or a single
+/// comma separated string of them.
+pub fn parse_points(value: &Value) -> Result<Vec<Point>, String> {
+    let raw_points: Vec<String> = match value {
+        Value::Array(points) => points
+            .iter()
+            .map(|point| match point {
+                Value::String(point) => Ok(point.clone()),
+                other => Err(format!("'{}' is not a point", other)),
+            })
+            .collect::<Result<_, _>>()?,
+        Value::String(points) if points.trim().is_empty() => Vec::new(),
+        Value::String(points) => points.split(',').map(str::to_string).collect(),
+        other => return Err(format!("'{}' is not a list of points", other)),
+    };
+
+    raw_points
+        .iter()
+        .map(|point| {
+            point
+                .split_once('x')
+                .and_then(|_| parse_point(point))
+                .ok_or(format!("'{}' is not a point in the format XxY", point))
+        })
+        .collect()
+}
+
+fn parse_single_point(value: &Value) -> Option<Point> {
+    match value {
+        Value::String(point) => parse_point(point),
+        Value::Array(values) if values.len() == 1 => parse_single_point(&values[0]),
+        _ => None,
+    }
+}
+
+/// Signed shoelace area, positive for counter clockwise polygons.
+pub fn signed_area(polygon: &[Point]) -> f64 {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum::<f64>()
+        / 2.0
+}
+
+fn orientation(a: Point, b: Point, c: Point) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn on_segment(a: Point, b: Point, p: Point) -> bool {
+    orientation(a, b, p).abs() <= EPSILON
+        && p.x >= a.x.min(b.x) - EPSILON
+        && p.x <= a.x.max(b.x) + EPSILON
+        && p.y >= a.y.min(b.y) - EPSILON
+        && p.y <= a.y.max(b.y) + EPSILON
+}
+
+fn segments_intersect(a: Point, b: Point, c: Point, d: Point) -> bool {
+    let o1 = orientation(a, b, c);
+    let o2 = orientation(a, b, d);
+    let o3 = orientation(c, d, a);
+    let o4 = orientation(c, d, b);
+
+    if ((o1 > EPSILON && o2 < -EPSILON) || (o1 < -EPSILON && o2 > EPSILON))
+        && ((o3 > EPSILON && o4 < -EPSILON) || (o3 < -EPSILON && o4 > EPSILON))
+    {
+        return true;
+    }
+
+    on_segment(a, b, c) || on_segment(a, b, d) || on_segment(c, d, a) || on_segment(c, d, b)
+}
+
+/// Whether any two non adjacent edges of the closed polygon touch or cross.
+pub fn is_self_intersecting(polygon: &[Point]) -> bool {
+    let n = polygon.len();
+    if n < 4 {
+        return false;
+    }
+
+    (0..n).any(|i| {
+        ((i + 2)..n).filter(|&j| !(i == 0 && j == n - 1)).any(|j| {
+            segments_intersect(
+                polygon[i],
+                polygon[(i + 1) % n],
+                polygon[j],
+                polygon[(j + 1) % n],
+            )
+        })
+    })
+}
+
+/// Point in polygon test that counts points on the boundary as inside.
+pub fn contains_point(polygon: &[Point], point: Point) -> bool {
+    let n = polygon.len();
+
+    if (0..n).any(|i| on_segment(polygon[i], polygon[(i + 1) % n], point)) {
+        return true;
+    }
+
+    let mut inside = false;
+    for i in 0..n {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % n];
+
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Whether `inner` lies completely within `outer`, touching the boundary allowed.
+pub fn contains_polygon(outer: &[Point], inner: &[Point]) -> bool {
+    if !inner.iter().all(|&point| contains_point(outer, point)) {
+        return false;
+    }
+
+    // with every vertex inside, a concave outer polygon can still be left through an edge
+    let n = inner.len();
+    (0..n).all(|i| {
+        let a = inner[i];
+        let b = inner[(i + 1) % n];
+        contains_point(
+            outer,
+            Point {
+                x: (a.x + b.x) / 2.0,
+                y: (a.y + b.y) / 2.0,
+            },
+        )
+    })
+}
+
+fn polygon_problem(polygon: &[Point]) -> Option<&'static str> {
+    if polygon.len() < 3 {
+        Some("needs at least 3 points")
+    } else if signed_area(polygon).abs() <= EPSILON {
+        Some("has zero area")
+    } else if is_self_intersecting(polygon) {
+        Some("intersects itself")
+    } else {
+        None
+    }
+}
+
+/// Checks the bed definition of a printer: `printable_area` and `bed_exclude_area`
+/// polygons, the `bed_mesh_min`/`bed_mesh_max` probing limits and `printable_height`.
+/// `values` should be the effective config, as bed keys are usually inherited.
+pub fn analyse_bed_geometry(
+    values: &Map<String, Value>,
+    config_details: &ConfigDetails,
+) -> HashMap<String, Vec<AnalysisMessageDetails>> {
+    let mut analysis_result: HashMap<String, Vec<AnalysisMessageDetails>> = HashMap::new();
+
+    let printable_area = match values.get("printable_area").map(parse_points) {
+        Some(Ok(printable_area)) => match polygon_problem(&printable_area) {
+            Some(problem) => {
+                push_analysis_message(
+                    &mut analysis_result,
+                    "printable_area",
+                    format!("The printable_area polygon {}", problem),
+                    ErrType::Error,
+                    config_details,
+                );
+                None
+            }
+            None => Some(printable_area),
+        },
+        Some(Err(err)) => {
+            push_analysis_message(
+                &mut analysis_result,
+                "printable_area",
+                format!("Could not parse printable_area: {}", err),
+                ErrType::Error,
+                config_details,
+            );
+            None
+        }
+        None => None,
+    };
+
+    match values.get("bed_exclude_area").map(parse_points) {
+        Some(Ok(exclude_area)) => {
+            // OrcaSlicer writes a single 0x0 point, or nothing, when there is no exclude area
+            let is_empty = exclude_area
+                .iter()
+                .all(|point| point.x.abs() <= EPSILON && point.y.abs() <= EPSILON);
+
+            if !is_empty {
+                if let Some(problem) = polygon_problem(&exclude_area) {
+                    push_analysis_message(
+                        &mut analysis_result,
+                        "bed_exclude_area",
+                        format!("The bed_exclude_area polygon {}", problem),
+                        ErrType::Error,
+                        config_details,
+                    );
+                } else if let Some(printable_area) = &printable_area {
+                    if !contains_polygon(printable_area, &exclude_area) {
+                        push_analysis_message(
+                            &mut analysis_result,
+                            "bed_exclude_area",
+                            "The bed_exclude_area does not lie within the printable_area".into(),
+                            ErrType::Error,
+                            config_details,
+                        );
+                    }
+                }
+            }
+        }
+        Some(Err(err)) => push_analysis_message(
+            &mut analysis_result,
+            "bed_exclude_area",
+            format!("Could not parse bed_exclude_area: {}", err),
+            ErrType::Error,
+            config_details,
+        ),
+        None => (),
+    }
+
+    let mut mesh_points: Vec<Option<Point>> = Vec::new();
+    for key in ["bed_mesh_min", "bed_mesh_max"] {
+        let point = values
+            .get(key)
+            .map(|value| (value, parse_single_point(value)));
+
+        if let Some((value, None)) = point {
+            push_analysis_message(
+                &mut analysis_result,
+                key,
+                format!(
+                    "Could not parse {} '{}' as a point in the format X,Y",
+                    key, value
+                ),
+                ErrType::Error,
+                config_details,
+            );
+        }
+
+        mesh_points.push(point.and_then(|(_, point)| point));
+    }
+
+    if let [Some(mesh_min), Some(mesh_max)] = mesh_points[..] {
+        if mesh_min.x >= mesh_max.x || mesh_min.y >= mesh_max.y {
+            push_analysis_message(
+                &mut analysis_result,
+                "bed_mesh_min",
+                format!(
+                    "bed_mesh_min ({},{}) must be smaller than bed_mesh_max ({},{}) on both axes",
+                    mesh_min.x, mesh_min.y, mesh_max.x, mesh_max.y
+                ),
+                ErrType::Error,
+                config_details,
+            );
+        } else if let Some(printable_area) = &printable_area {
+            let bed_min_x = printable_area
+                .iter()
+                .map(|p| p.x)
+                .fold(f64::INFINITY, f64::min);
+            let bed_min_y = printable_area
+                .iter()
+                .map(|p| p.y)
+                .fold(f64::INFINITY, f64::min);
+            let bed_max_x = printable_area
+                .iter()
+                .map(|p| p.x)
+                .fold(f64::NEG_INFINITY, f64::max);
+            let bed_max_y = printable_area
+                .iter()
+                .map(|p| p.y)
+                .fold(f64::NEG_INFINITY, f64::max);
+
+            let limited = |coordinate: f64| coordinate.abs() < UNLIMITED_MESH_COORDINATE;
+
+            if (limited(mesh_min.x) && mesh_min.x >= bed_max_x)
+                || (limited(mesh_min.y) && mesh_min.y >= bed_max_y)
+                || (limited(mesh_max.x) && mesh_max.x <= bed_min_x)
+                || (limited(mesh_max.y) && mesh_max.y <= bed_min_y)
+            {
+                push_analysis_message(
+                    &mut analysis_result,
+                    "bed_mesh_min",
+                    "The bed mesh area between bed_mesh_min and bed_mesh_max does not overlap the printable_area".into(),
+                    ErrType::Error,
+                    config_details,
+                );
+            } else {
+                for (key, outside) in [
+                    (
+                        "bed_mesh_min",
+                        (limited(mesh_min.x) && mesh_min.x < bed_min_x - EPSILON)
+                            || (limited(mesh_min.y) && mesh_min.y < bed_min_y - EPSILON),
+                    ),
+                    (
+                        "bed_mesh_max",
+                        (limited(mesh_max.x) && mesh_max.x > bed_max_x + EPSILON)
+                            || (limited(mesh_max.y) && mesh_max.y > bed_max_y + EPSILON),
+                    ),
+                ] {
+                    if outside {
+                        push_analysis_message(
+                            &mut analysis_result,
+                            key,
+                            format!("{} lies outside of the printable_area", key),
+                            ErrType::Warning,
+                            config_details,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(value) = values.get("printable_height") {
+        match parse_numbers(value).filter(|numbers| numbers.len() == 1) {
+            Some(height) if height[0] > 0.0 && height[0] <= MAX_PRINTABLE_HEIGHT => (),
+            Some(height) => push_analysis_message(
+                &mut analysis_result,
+                "printable_height",
+                format!(
+                    "printable_height must be between 0 and {}, found {}",
+                    MAX_PRINTABLE_HEIGHT, height[0]
+                ),
+                ErrType::Error,
+                config_details,
+            ),
+            None => push_analysis_message(
+                &mut analysis_result,
+                "printable_height",
+                format!("Could not parse printable_height '{}' as a number", value),
+                ErrType::Error,
+                config_details,
+            ),
+        }
+    }
+
+    analysis_result
+}
@@ -1,10 +1,7 @@
 #[cfg(target_os = "linux")]
 use fork::{daemon, Fork};
-use fs_extra::dir;
-use fs_extra::file;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
-use std::ffi::OsString;
 use std::fs;
 #[cfg(target_os = "linux")]
 use std::fs::metadata;
@@ -16,7 +13,15 @@ use std::sync::Arc;
 use std::{collections::HashSet, io::BufReader};
 use tauri::async_runtime::spawn_blocking;
 
+use crate::audit::{AuditQuery, AuditRecord};
+use crate::bed_geometry::analyse_bed_geometry;
+use crate::config_inheritance::{resolve_vendor_config, EffectiveConfig};
+use crate::config_keys::{analyse_config_keys, config_type_of, generic_config_values};
+use crate::config_overrides::{analyse_redundant_overrides, redundant_keys};
+use crate::configuration_loader::get_all_json_files;
 use crate::configuration_loader::load_filament_preset;
+use crate::configuration_loader::load_generic_preset;
+use crate::configuration_loader::load_preset;
 use crate::configuration_loader::load_printer_variant_preset;
 use crate::configuration_loader::load_process_preset;
 use crate::configuration_loader::load_vendor_preset;
@@ -26,10 +31,26 @@ use crate::configuration_loader::ConfigNameAndPath;
 use crate::configuration_loader::ErrType;
 use crate::configuration_loader::ErrWan;
 use crate::configuration_loader::FilamentJsonSchema;
-use crate::configuration_loader::PrinterModelJsonSchema;
 use crate::configuration_loader::PrinterVariantJsonSchema;
 use crate::configuration_loader::ProcessJsonSchema;
-use crate::configuration_loader::VendorJsonSchema;
+use crate::filament_rules::{analyse_filament_rules, FilamentRuleSettings};
+use crate::file_store::{
+    check_file_token, file_hash, sha256_hex, BackupEntry, FileStore, WriteError,
+};
+use crate::journal::{JournalEntry, JournalState};
+use crate::json_diagnostics::{analyse_json_file, diagnose_json, JsonDiagnostic};
+use crate::json_edit::{edit_json, edited_json};
+use crate::name_references::analyse_vendor_references;
+use crate::overlay::{materialize, read_path, set_overlay, OverlaySettings};
+use crate::preset_deletion::{self, DeleteImpact, DeleteStrategy};
+use crate::preset_rename::{self, PresetEdit, RenamedPreset};
+use crate::trash::TrashEntry;
+use crate::vendor_bundle::{self, BundleImport};
+use crate::vendor_copy;
+use crate::vendor_duplication;
+use crate::vendor_merge::{self, CollisionStrategy};
+use crate::vendor_plan::{execute_plan, PlannedAction, VendorPlan, VendorProblem};
+use crate::vendor_split::{self, SharedBaseStrategy};
 use regex::Regex;
 use std::sync::RwLock;
 
@@ -47,6 +68,21 @@ fn str_to_bool(s: &str) -> Option<bool> {
     }
 }
 
+/// Reads a numeric option, which is stored either as a number, a string or an
+/// array of those (one entry per extruder).
+pub fn parse_numbers(value: &Value) -> Option<Vec<f64>> {
+    match value {
+        Value::Number(number) => number.as_f64().map(|number| vec![number]),
+        Value::String(number) => number.trim().parse().ok().map(|number| vec![number]),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| parse_numbers(value).filter(|numbers| numbers.len() == 1))
+            .map(|numbers| numbers.map(|numbers| numbers[0]))
+            .collect(),
+        _ => None,
+    }
+}
+
 #[tauri::command]
 pub fn check_directory(path: &str) -> bool {
     let path = path::Path::new(path);
@@ -129,59 +165,224 @@ pub fn check_file_exists(path: String) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub fn write_to_file(path: String, content: String) -> Result<(), String> {
-    let write_res = fs::write(path, content);
-    match write_res {
-        Ok(()) => Ok(()),
-        Err(e) => Err(e.to_string() + "\nYou may need to relaunch the app as administrator"),
-    }
+/// Writes `content` and returns the new version token of the file. With an
+/// `expected_token` the write is refused when the file changed since it was read.
+/// JSON written over a JSON file keeps the key order and layout of the file.
+pub fn write_to_file(
+    path: String,
+    content: String,
+    expected_token: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<String, WriteError> {
+    // the token is compared within the operation, right before the write, so
+    // that the file merged into is the one the token was checked against
+    let content = store.record("write_to_file", format!("Write '{}'", path), |operation| {
+        if let Err(conflict) = check_file_token(Path::new(&path), &expected_token) {
+            return Ok(Err(conflict));
+        }
+
+        let content = match (
+            fs::read_to_string(read_path(Path::new(&path))),
+            serde_json::from_str::<Map<String, Value>>(&content),
+        ) {
+            (Ok(original), Ok(updated)) => edit_json(&original, &updated).unwrap_or(content),
+            _ => content,
+        };
+
+        operation.write(Path::new(&path), content.as_bytes())?;
+        Ok(Ok(content))
+    })??;
+
+    Ok(sha256_hex(content.as_bytes()))
 }
 
+/// The current version token of a file, `None` when it does not exist.
 #[tauri::command]
-pub fn rename_file(path: &str, new_path: &str) -> Result<(), String> {
-    let renamed_res = fs::rename(path, new_path);
-    match renamed_res {
-        Ok(()) => Ok(()),
-        Err(e) => Err(e.to_string() + "\nYou may need to relaunch the app as administrator"),
-    }
+pub fn get_file_token(path: &str) -> Result<Option<String>, String> {
+    file_hash(&read_path(Path::new(path)))
 }
 
 #[tauri::command]
-pub fn copy_file(path: &str, new_path: &str) -> Result<(), String> {
-    let renamed_res = fs::copy(path, new_path);
-    match renamed_res {
-        Ok(_) => Ok(()),
-        Err(e) => Err(e.to_string() + "\nYou may need to relaunch the app as administrator"),
-    }
+pub fn rename_file(
+    path: &str,
+    new_path: &str,
+    expected_token: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), WriteError> {
+    store.record(
+        "rename_file",
+        format!("Rename '{}' to '{}'", path, new_path),
+        |operation| {
+            if let Err(conflict) = check_file_token(Path::new(path), &expected_token) {
+                return Ok(Err(conflict));
+            }
+
+            operation.rename(Path::new(path), Path::new(new_path))?;
+            Ok(Ok(()))
+        },
+    )?
 }
 
 #[tauri::command]
-pub fn delete_file(path: &str) -> Result<(), String> {
-    let deleted_res = fs::remove_file(path);
-    match deleted_res {
-        Ok(()) => Ok(()),
-        Err(e) => Err(e.to_string() + "\nYou may need to relaunch the app as administrator"),
-    }
+pub fn copy_file(
+    path: &str,
+    new_path: &str,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), String> {
+    store.record(
+        "copy_file",
+        format!("Copy '{}' to '{}'", path, new_path),
+        |operation| operation.copy(Path::new(path), Path::new(new_path)),
+    )
+}
+
+/// Moves a file into the trash, along with the vendor list entries pointing at it.
+#[tauri::command]
+pub fn delete_file(
+    path: &str,
+    expected_token: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<TrashEntry, WriteError> {
+    store.record("delete_file", format!("Delete '{}'", path), |operation| {
+        if let Err(conflict) = check_file_token(Path::new(path), &expected_token) {
+            return Ok(Err(conflict));
+        }
+
+        store
+            .move_to_trash(operation, Path::new(path), "delete_file")
+            .map(Ok)
+    })?
+}
+
+#[tauri::command]
+pub fn list_trash(store: tauri::State<Arc<FileStore>>) -> Result<Vec<TrashEntry>, String> {
+    store.list_trash()
+}
+
+#[tauri::command]
+pub fn restore_from_trash(
+    id: &str,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<TrashEntry, String> {
+    store.restore_from_trash(id)
+}
+
+#[tauri::command]
+pub fn purge_trash(
+    ids: Option<Vec<String>>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), String> {
+    store.purge_trash(ids)
+}
+
+#[tauri::command]
+pub fn list_backups(
+    path: &str,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<Vec<BackupEntry>, String> {
+    store.list_backups(Path::new(path))
+}
+
+#[tauri::command]
+pub fn restore_backup(
+    path: &str,
+    backup_id: &str,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), String> {
+    store.restore_backup(Path::new(path), backup_id)
+}
+
+#[tauri::command]
+pub fn undo(store: tauri::State<Arc<FileStore>>) -> Result<JournalEntry, String> {
+    store.undo()
+}
+
+#[tauri::command]
+pub fn redo(store: tauri::State<Arc<FileStore>>) -> Result<JournalEntry, String> {
+    store.redo()
+}
+
+#[tauri::command]
+pub fn get_journal(store: tauri::State<Arc<FileStore>>) -> JournalState {
+    store.journal_state()
+}
+
+/// The audit log of the current workspace, filtered by `query`.
+#[tauri::command]
+pub fn query_audit_log(
+    query: AuditQuery,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<Vec<AuditRecord>, String> {
+    store.query_audit_log(&query)
 }
 
 #[tauri::command]
-pub fn create_directory(path: String) -> Result<(), String> {
-    let write_res = fs::create_dir(path);
+pub fn set_workspace_roots(
+    installation_directory: Option<String>,
+    data_directory: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), String> {
+    store
+        .workspace
+        .set_roots(installation_directory, data_directory)
+}
+
+/// Redirects the changes to a read-only installation into `overlay_directory`,
+/// or writes to the installation again without settings.
+#[tauri::command]
+pub fn set_overlay_directory(settings: Option<OverlaySettings>) -> Result<(), String> {
+    set_overlay(settings)
+}
+
+/// Exports the installation with the overlay applied as a complete profiles tree.
+#[tauri::command]
+pub async fn materialize_overlay(
+    target: String,
+    store: tauri::State<'_, Arc<FileStore>>,
+) -> Result<(), String> {
+    let target = store.workspace.check_path(Path::new(&target))?;
+    let materialized_target = target.clone();
+
+    spawn_blocking(move || materialize(&materialized_target))
+        .await
+        .unwrap_or_else(|e| Err(format!("Task error: {e}")))?;
+
+    store.audit(AuditRecord {
+        created_directories: vec![target.to_string_lossy().to_string()],
+        ..AuditRecord::new(
+            "materialize_overlay",
+            format!("Materialize the overlay into '{}'", target.display()),
+        )
+    })
+}
+
+#[tauri::command]
+pub fn create_directory(path: String, store: tauri::State<Arc<FileStore>>) -> Result<(), String> {
+    let path = store.workspace.check_path(Path::new(&path))?;
+    let write_res = fs::create_dir(&path);
     match write_res {
-        Ok(()) => Ok(()),
+        Ok(()) => store.audit(AuditRecord {
+            created_directories: vec![path.to_string_lossy().to_string()],
+            ..AuditRecord::new("create_directory", format!("Create '{}'", path.display()))
+        }),
         Err(e) => Err(e.to_string() + "\nYou may need to relaunch the app as administrator"),
     }
 }
 
 #[tauri::command]
-pub fn rename_config(path: String, new_name: String) -> Result<String, String> {
+pub fn rename_config(
+    path: String,
+    new_name: String,
+    expected_token: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<String, WriteError> {
     let path_obj = Path::new(&path);
     let parent_path = path_obj.parent().unwrap();
 
     let new_path = parent_path.join(new_name + ".json");
     let new_path_string = new_path.to_str().unwrap().to_string();
 
-    let renamed_res = rename_file(&path, &new_path_string);
+    let renamed_res = rename_file(&path, &new_path_string, expected_token, store);
     match renamed_res {
         Ok(()) => Ok(new_path_string),
         Err(e) => Err(e),
@@ -197,7 +398,7 @@ pub async fn find_possible_values(
         let mut values = HashSet::new();
 
         for path in files_to_check {
-            let file = File::open(&path).ok()?;
+            let file = File::open(read_path(Path::new(&path))).ok()?;
             let reader = BufReader::new(file);
             let json: Value = serde_json::from_reader(reader).ok()?;
 
@@ -223,43 +424,6 @@ pub async fn find_possible_values(
     result
 }
 
-pub fn duplicate_vendor_files(
-    old_directory_path: &PathBuf,
-    new_directory_path: &PathBuf,
-    old_file_path: &PathBuf,
-    new_file_path: &PathBuf,
-) -> Result<(), String> {
-    let dir_copy_options = dir::CopyOptions {
-        overwrite: false,
-        skip_exist: false,
-        buffer_size: 64_000,
-        copy_inside: true, // If true, copies contents *inside* src instead of src folder itself
-        ..Default::default()
-    };
-
-    let file_copy_options = file::CopyOptions {
-        overwrite: false,
-        skip_exist: false,
-        buffer_size: 64_000,
-        ..Default::default()
-    };
-
-    let dir_copy_res = dir::copy(old_directory_path, new_directory_path, &dir_copy_options);
-    let file_copy_res = file::copy(old_file_path, new_file_path, &file_copy_options);
-
-    if dir_copy_res.is_err() || file_copy_res.is_err() {
-        if dir_copy_res.is_err() {
-            Err(dir_copy_res.err().unwrap().to_string()
-                + "\nYou may need to relaunch the app as administrator")
-        } else {
-            Err(file_copy_res.err().unwrap().to_string()
-                + "\nYou may need to relaunch the app as administrator")
-        }
-    } else {
-        Ok(())
-    }
-}
-
 pub fn replace_name(
     name: &str,
     old_dir_name: &str,
@@ -298,476 +462,337 @@ pub fn remove_nulls(value: &mut Value) {
 }
 
 #[tauri::command]
-pub fn duplicate_vendor(
+pub fn plan_duplicate_vendor(
     path: &str,
     new_dir_name: &str,
     orca_filament_library_filaments: Vec<String>,
-) -> Result<(), String> {
+) -> Result<VendorPlan, Vec<VendorProblem>> {
     let orca_filament_library_filaments: HashSet<String> =
         orca_filament_library_filaments.into_iter().collect();
 
-    let path = path::Path::new(path);
-    let parent_directory = path.parent().unwrap();
-
-    let path_stem = path.file_stem().unwrap();
-    let path_extension = path.extension().unwrap();
+    vendor_duplication::plan_duplicate_vendor(
+        Path::new(path),
+        new_dir_name,
+        &orca_filament_library_filaments,
+    )
+}
 
-    let new_directory_path = parent_directory.join(Path::new(new_dir_name));
-    let old_directory_path = parent_directory.join(path_stem);
+#[tauri::command]
+pub fn execute_vendor_plan(
+    plan: VendorPlan,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), Vec<VendorProblem>> {
+    // the paths of the plan are checked against the workspace by execute_plan
+    store
+        .record(&plan.command, plan.description.clone(), |operation| {
+            Ok(execute_plan(&plan, operation))
+        })
+        .unwrap_or_else(|err| Err(vec![VendorProblem::new(Path::new(&plan.root), err)]))
+}
 
-    let mut path_extension_combined = OsString::from(new_dir_name);
-    path_extension_combined.push(".");
-    path_extension_combined.push(path_extension);
+#[tauri::command]
+pub fn duplicate_vendor(
+    path: &str,
+    new_dir_name: &str,
+    orca_filament_library_filaments: Vec<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), Vec<VendorProblem>> {
+    let plan = plan_duplicate_vendor(path, new_dir_name, orca_filament_library_filaments)?;
 
-    let new_file_path = parent_directory.join(path_extension_combined);
+    execute_vendor_plan(plan, store)
+}
 
-    duplicate_vendor_files(
-        &old_directory_path,
-        &new_directory_path,
-        &path.to_path_buf(),
-        &new_file_path,
-    )?;
+/// Plans renaming a vendor in place. With `data_directory` the stale system
+/// copy of the vendor is removed and, with `update_user_presets`, user presets
+/// inheriting from it follow the new names.
+#[tauri::command]
+pub fn plan_rename_vendor(
+    path: &str,
+    new_dir_name: &str,
+    orca_filament_library_filaments: Vec<String>,
+    data_directory: Option<String>,
+    update_user_presets: bool,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let orca_filament_library_filaments: HashSet<String> =
+        orca_filament_library_filaments.into_iter().collect();
 
-    let old_dir_name = path_stem.to_str().unwrap();
+    vendor_duplication::plan_rename_vendor(
+        Path::new(path),
+        new_dir_name,
+        &orca_filament_library_filaments,
+        data_directory.as_deref().map(Path::new),
+        update_user_presets,
+    )
+}
 
-    let new_vendor_config_data = fs::read_to_string(&new_file_path).unwrap();
+#[tauri::command]
+pub fn rename_vendor(
+    path: &str,
+    new_dir_name: &str,
+    orca_filament_library_filaments: Vec<String>,
+    data_directory: Option<String>,
+    update_user_presets: bool,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), Vec<VendorProblem>> {
+    let plan = plan_rename_vendor(
+        path,
+        new_dir_name,
+        orca_filament_library_filaments,
+        data_directory,
+        update_user_presets,
+    )?;
 
-    let mut parsed_vendor_config: VendorJsonSchema =
-        serde_json::from_str(&new_vendor_config_data).unwrap();
+    execute_vendor_plan(plan, store)
+}
 
-    parsed_vendor_config.name = new_dir_name.to_string();
+/// Plans moving every config of the vendor at `source_path` into the vendor at
+/// `target_path`, removing the source vendor afterwards.
+#[tauri::command]
+pub fn plan_merge_vendors(
+    source_path: &str,
+    target_path: &str,
+    strategy: CollisionStrategy,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    vendor_merge::plan_merge_vendors(Path::new(source_path), Path::new(target_path), strategy)
+}
 
-    if parsed_vendor_config.machine_list.is_some() {
-        parsed_vendor_config
-            .machine_list
-            .as_mut()
-            .unwrap()
-            .iter_mut()
-            .for_each(|el| {
-                let old_sub_path = el.sub_path.clone();
-                let sub_path_path = Path::new(&el.sub_path);
-                let sub_path_parent = sub_path_path.parent().unwrap();
-
-                let new_name = replace_name(
-                    &el.name,
-                    old_dir_name,
-                    new_dir_name,
-                    &orca_filament_library_filaments,
-                );
-                el.name = new_name.clone();
-
-                let new_sub_path =
-                    sub_path_parent.to_str().unwrap().to_string() + "/" + &el.name + ".json";
-                el.sub_path = new_sub_path;
-
-                let old_file_path = new_directory_path.join(old_sub_path);
-                let new_file_path = new_directory_path.join(el.sub_path.clone());
-
-                let file_move_options = file::CopyOptions {
-                    overwrite: false,
-                    skip_exist: false,
-                    buffer_size: 64_000,
-                    ..Default::default()
-                };
-
-                file::move_file(old_file_path, new_file_path.clone(), &file_move_options).ok();
-
-                let config_data = fs::read_to_string(&new_file_path).unwrap();
-                let mut parsed_config_file: PrinterVariantJsonSchema =
-                    serde_json::from_str(&config_data).unwrap();
-
-                parsed_config_file.name = new_name;
-
-                match parsed_config_file.inherits {
-                    Some(inh) => {
-                        parsed_config_file.inherits = Some(replace_name(
-                            &inh,
-                            old_dir_name,
-                            new_dir_name,
-                            &orca_filament_library_filaments,
-                        ));
-                    }
-                    None => (),
-                }
+#[tauri::command]
+pub fn merge_vendors(
+    source_path: &str,
+    target_path: &str,
+    strategy: CollisionStrategy,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), Vec<VendorProblem>> {
+    let plan = plan_merge_vendors(source_path, target_path, strategy)?;
+
+    execute_vendor_plan(plan, store)
+}
 
-                if let Some(pm) = parsed_config_file.printer_model {
-                    let replaced = replace_name(
-                        &pm,
-                        old_dir_name,
-                        new_dir_name,
-                        &orca_filament_library_filaments,
-                    );
-                    parsed_config_file.printer_model = Some(replaced);
-                }
+/// Plans moving the given printer models of the vendor at `path` into a new
+/// vendor `new_dir_name`, with every preset they need.
+#[tauri::command]
+pub fn plan_split_vendor(
+    path: &str,
+    machine_models: Vec<String>,
+    new_dir_name: &str,
+    shared_bases: SharedBaseStrategy,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    vendor_split::plan_split_vendor(Path::new(path), &machine_models, new_dir_name, shared_bases)
+}
 
-                let default_print_profile_res =
-                    parsed_config_file.extra.0.get("default_print_profile");
-
-                match default_print_profile_res {
-                    Some(default_print_profile) => {
-                        let new_default_print_profile = replace_name(
-                            default_print_profile.as_str().unwrap(),
-                            old_dir_name,
-                            new_dir_name,
-                            &orca_filament_library_filaments,
-                        );
-
-                        parsed_config_file.extra.0.insert(
-                            "default_print_profile".to_string(),
-                            Value::String(new_default_print_profile),
-                        );
-                    }
-                    None => (),
-                }
+#[tauri::command]
+pub fn split_vendor(
+    path: &str,
+    machine_models: Vec<String>,
+    new_dir_name: &str,
+    shared_bases: SharedBaseStrategy,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), Vec<VendorProblem>> {
+    let plan = plan_split_vendor(path, machine_models, new_dir_name, shared_bases)?;
 
-                let default_filament_profile_res =
-                    parsed_config_file.extra.0.get("default_filament_profile");
-
-                match default_filament_profile_res {
-                    Some(default_filament_profile) => {
-                        let new_material_names: Vec<_> = default_filament_profile
-                            .as_array()
-                            .unwrap()
-                            .iter()
-                            .map(|material_name| {
-                                let material_name_str = material_name.as_str().unwrap();
-
-                                Value::String(replace_name(
-                                    material_name_str,
-                                    old_dir_name,
-                                    new_dir_name,
-                                    &orca_filament_library_filaments,
-                                ))
-                            })
-                            .collect();
-
-                        parsed_config_file.extra.0.insert(
-                            "default_filament_profile".to_string(),
-                            Value::Array(new_material_names),
-                        );
-                    }
-                    None => (),
-                }
+    execute_vendor_plan(plan, store)
+}
 
-                let mut parsed_config_file_value =
-                    serde_json::to_value(&parsed_config_file).unwrap();
-                remove_nulls(&mut parsed_config_file_value);
+/// Plans copying the configs at `config_paths` of the vendor at `source_path`
+/// into the vendor at `target_path`, with the ancestors the target lacks.
+#[tauri::command]
+pub fn plan_copy_configs_to_vendor(
+    source_path: &str,
+    config_paths: Vec<String>,
+    target_path: &str,
+    orca_filament_library_filaments: Vec<String>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let orca_filament_library_filaments: HashSet<String> =
+        orca_filament_library_filaments.into_iter().collect();
+    let config_paths: Vec<PathBuf> = config_paths.into_iter().map(PathBuf::from).collect();
+
+    vendor_copy::plan_copy_configs_to_vendor(
+        Path::new(source_path),
+        &config_paths,
+        Path::new(target_path),
+        &orca_filament_library_filaments,
+    )
+}
 
-                let pretty_json = serde_json::to_string_pretty(&parsed_config_file_value).unwrap();
+#[tauri::command]
+pub fn copy_configs_to_vendor(
+    source_path: &str,
+    config_paths: Vec<String>,
+    target_path: &str,
+    orca_filament_library_filaments: Vec<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let plan = plan_copy_configs_to_vendor(
+        source_path,
+        config_paths,
+        target_path,
+        orca_filament_library_filaments,
+    )?;
 
-                write_to_file(new_file_path.to_str().unwrap().to_string(), pretty_json).ok();
-            });
-    }
+    execute_vendor_plan(plan.clone(), store)?;
 
-    if parsed_vendor_config.machine_model_list.is_some() {
-        parsed_vendor_config
-            .machine_model_list
-            .as_mut()
-            .unwrap()
-            .iter_mut()
-            .for_each(|el| {
-                let old_sub_path = el.sub_path.clone();
-                let sub_path_path = Path::new(&el.sub_path);
-                let sub_path_parent = sub_path_path.parent().unwrap();
-
-                let new_name = replace_name(
-                    &el.name,
-                    old_dir_name,
-                    new_dir_name,
-                    &orca_filament_library_filaments,
-                );
-                el.name = new_name.clone();
-
-                let new_sub_path =
-                    sub_path_parent.to_str().unwrap().to_string() + "/" + &el.name + ".json";
-                el.sub_path = new_sub_path;
-
-                let old_file_path = new_directory_path.join(old_sub_path);
-                let new_file_path = new_directory_path.join(el.sub_path.clone());
-
-                let file_move_options = file::CopyOptions {
-                    overwrite: false,
-                    skip_exist: false,
-                    buffer_size: 64_000,
-                    ..Default::default()
-                };
-
-                file::move_file(old_file_path, new_file_path.clone(), &file_move_options).ok();
-
-                let config_data = fs::read_to_string(&new_file_path).unwrap();
-                let mut parsed_config_file: PrinterModelJsonSchema =
-                    serde_json::from_str(&config_data).unwrap();
-
-                parsed_config_file.name = new_name;
-                parsed_config_file.family = new_dir_name.to_string();
-
-                let default_materials_res = parsed_config_file.extra.0.get("default_materials");
-
-                match default_materials_res {
-                    Some(default_materials) => {
-                        let new_material_names: Vec<_> = default_materials
-                            .as_str()
-                            .unwrap()
-                            .split(";")
-                            .map(|material_name| {
-                                let material_name_str = material_name;
-
-                                replace_name(
-                                    material_name_str,
-                                    old_dir_name,
-                                    new_dir_name,
-                                    &orca_filament_library_filaments,
-                                )
-                            })
-                            .collect();
-
-                        parsed_config_file.extra.0.insert(
-                            "default_materials".to_string(),
-                            Value::String(new_material_names.join(";")),
-                        );
-                    }
-                    None => (),
-                }
+    Ok(plan)
+}
 
-                let mut parsed_config_file_value =
-                    serde_json::to_value(&parsed_config_file).unwrap();
-                remove_nulls(&mut parsed_config_file_value);
+/// Plans renaming the preset at `path` with every reference to it in the
+/// installation and the data directory, saving it with `content` if given.
+#[tauri::command]
+pub fn plan_rename_preset(
+    path: &str,
+    config_type: &str,
+    new_name: &str,
+    content: Option<String>,
+    profiles_directory: Option<String>,
+    data_directory: Option<String>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    preset_rename::plan_rename_preset(
+        Path::new(path),
+        config_type,
+        new_name,
+        content.as_deref(),
+        profiles_directory.as_deref().map(Path::new),
+        data_directory.as_deref().map(Path::new),
+    )
+}
 
-                let pretty_json = serde_json::to_string_pretty(&parsed_config_file_value).unwrap();
+/// Renames the preset at `path` as one journaled operation. An edited preset
+/// is saved in the same operation, checked against the token it was read
+/// with.
+#[tauri::command]
+pub fn rename_preset(
+    path: &str,
+    config_type: &str,
+    new_name: &str,
+    edit: Option<PresetEdit>,
+    profiles_directory: Option<String>,
+    data_directory: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<RenamedPreset, Vec<VendorProblem>> {
+    let (content, expected_token) = edit
+        .map(|edit| (Some(edit.content), edit.expected_token))
+        .unwrap_or_default();
+    let plan = plan_rename_preset(
+        path,
+        config_type,
+        new_name,
+        content,
+        profiles_directory,
+        data_directory,
+    )?;
 
-                write_to_file(new_file_path.to_str().unwrap().to_string(), pretty_json).ok();
-            });
+    // the renamed preset is the file created from the old one
+    let renamed = plan
+        .files
+        .iter()
+        .find(|file| file.action == PlannedAction::Create && file.source.as_deref() == Some(path));
+
+    // the plan is refused if the file changes after it was made, so the
+    // token only has to match what the plan read
+    if let Some(expected_token) = &expected_token {
+        if renamed.and_then(|file| file.expected_hash.as_ref()) != Some(expected_token) {
+            return Err(vec![VendorProblem::new(
+                Path::new(path),
+                format!(
+                    "'{}' was changed by another program after it was loaded",
+                    path
+                ),
+            )]);
+        }
     }
 
-    if parsed_vendor_config.filament_list.is_some() {
-        parsed_vendor_config
-            .filament_list
-            .as_mut()
-            .unwrap()
-            .iter_mut()
-            .for_each(|el| {
-                let old_sub_path = el.sub_path.clone();
-                let sub_path_path = Path::new(&el.sub_path);
-                let sub_path_parent = sub_path_path.parent().unwrap();
-
-                let new_name = replace_name(
-                    &el.name,
-                    old_dir_name,
-                    new_dir_name,
-                    &orca_filament_library_filaments,
-                );
-                el.name = new_name.clone();
-
-                let new_sub_path =
-                    sub_path_parent.to_str().unwrap().to_string() + "/" + &el.name + ".json";
-                el.sub_path = new_sub_path;
-
-                let old_file_path = new_directory_path.join(old_sub_path);
-                let new_file_path = new_directory_path.join(el.sub_path.clone());
-
-                let file_move_options = file::CopyOptions {
-                    overwrite: false,
-                    skip_exist: false,
-                    buffer_size: 64_000,
-                    ..Default::default()
-                };
-
-                file::move_file(old_file_path, new_file_path.clone(), &file_move_options).ok();
-
-                let config_data = fs::read_to_string(&new_file_path).unwrap();
-                let mut parsed_config_file: FilamentJsonSchema =
-                    serde_json::from_str(&config_data).unwrap();
-
-                parsed_config_file.name = new_name;
-
-                match parsed_config_file.inherits {
-                    Some(inh) => {
-                        parsed_config_file.inherits = Some(replace_name(
-                            &inh,
-                            old_dir_name,
-                            new_dir_name,
-                            &orca_filament_library_filaments,
-                        ));
-                    }
-                    None => (),
-                }
-
-                let compatible_printers_res = parsed_config_file.extra.0.get("compatible_printers");
-
-                match compatible_printers_res {
-                    Some(compatible_printers) => {
-                        let new_material_names: Vec<_> = compatible_printers
-                            .as_array()
-                            .unwrap()
-                            .iter()
-                            .map(|material_name| {
-                                let material_name_str = material_name.as_str().unwrap();
-
-                                Value::String(replace_name(
-                                    material_name_str,
-                                    old_dir_name,
-                                    new_dir_name,
-                                    &orca_filament_library_filaments,
-                                ))
-                            })
-                            .collect();
-
-                        parsed_config_file.extra.0.insert(
-                            "compatible_printers".to_string(),
-                            Value::Array(new_material_names),
-                        );
-                    }
-                    None => (),
-                }
-
-                let mut parsed_config_file_value =
-                    serde_json::to_value(&parsed_config_file).unwrap();
-                remove_nulls(&mut parsed_config_file_value);
-
-                let pretty_json = serde_json::to_string_pretty(&parsed_config_file_value).unwrap();
+    let new_path = renamed.map(|file| file.path.clone()).unwrap_or(path.into());
 
-                write_to_file(new_file_path.to_str().unwrap().to_string(), pretty_json).ok();
-            });
-    }
+    execute_vendor_plan(plan.clone(), store)?;
 
-    if parsed_vendor_config.process_list.is_some() {
-        parsed_vendor_config
-            .process_list
-            .as_mut()
-            .unwrap()
-            .iter_mut()
-            .for_each(|el| {
-                let old_sub_path = el.sub_path.clone();
-                let sub_path_path = Path::new(&el.sub_path);
-                let sub_path_parent = sub_path_path.parent().unwrap();
-
-                let new_name = replace_name(
-                    &el.name,
-                    old_dir_name,
-                    new_dir_name,
-                    &orca_filament_library_filaments,
-                );
-                el.name = new_name.clone();
-
-                let new_sub_path =
-                    sub_path_parent.to_str().unwrap().to_string() + "/" + &el.name + ".json";
-                el.sub_path = new_sub_path;
-
-                let old_file_path = new_directory_path.join(old_sub_path);
-                let new_file_path = new_directory_path.join(el.sub_path.clone());
-
-                let file_move_options = file::CopyOptions {
-                    overwrite: false,
-                    skip_exist: false,
-                    buffer_size: 64_000,
-                    ..Default::default()
-                };
-
-                file::move_file(old_file_path, new_file_path.clone(), &file_move_options).ok();
-
-                let config_data = fs::read_to_string(&new_file_path).unwrap();
-                let mut parsed_config_file: ProcessJsonSchema =
-                    serde_json::from_str(&config_data).unwrap();
-
-                parsed_config_file.name = new_name;
-
-                match parsed_config_file.inherits {
-                    Some(inh) => {
-                        parsed_config_file.inherits = Some(replace_name(
-                            &inh,
-                            old_dir_name,
-                            new_dir_name,
-                            &orca_filament_library_filaments,
-                        ));
-                    }
-                    None => (),
-                }
+    Ok(RenamedPreset {
+        path: new_path,
+        plan,
+    })
+}
 
-                let compatible_printers_res = parsed_config_file.extra.0.get("compatible_printers");
-
-                match compatible_printers_res {
-                    Some(compatible_printers) => {
-                        let new_material_names: Vec<_> = compatible_printers
-                            .as_array()
-                            .unwrap()
-                            .iter()
-                            .map(|material_name| {
-                                let material_name_str = material_name.as_str().unwrap();
-
-                                Value::String(replace_name(
-                                    material_name_str,
-                                    old_dir_name,
-                                    new_dir_name,
-                                    &orca_filament_library_filaments,
-                                ))
-                            })
-                            .collect();
-
-                        parsed_config_file.extra.0.insert(
-                            "compatible_printers".to_string(),
-                            Value::Array(new_material_names),
-                        );
-                    }
-                    None => (),
-                }
+/// Lists what depends on the preset at `path` before it is deleted.
+#[tauri::command]
+pub fn preset_delete_impact(
+    path: &str,
+    config_type: &str,
+    profiles_directory: Option<String>,
+    data_directory: Option<String>,
+) -> Result<DeleteImpact, Vec<VendorProblem>> {
+    preset_deletion::preset_delete_impact(
+        Path::new(path),
+        config_type,
+        profiles_directory.as_deref().map(Path::new),
+        data_directory.as_deref().map(Path::new),
+    )
+}
 
-                let mut parsed_config_file_value =
-                    serde_json::to_value(&parsed_config_file).unwrap();
-                remove_nulls(&mut parsed_config_file_value);
+/// Plans deleting the preset at `path` from the installation and the data
+/// directory, with `strategy` deciding what happens to its dependents.
+#[tauri::command]
+pub fn plan_delete_preset(
+    path: &str,
+    config_type: &str,
+    strategy: DeleteStrategy,
+    profiles_directory: Option<String>,
+    data_directory: Option<String>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    preset_deletion::plan_delete_preset(
+        Path::new(path),
+        config_type,
+        strategy,
+        profiles_directory.as_deref().map(Path::new),
+        data_directory.as_deref().map(Path::new),
+    )
+}
 
-                let pretty_json = serde_json::to_string_pretty(&parsed_config_file_value).unwrap();
+#[tauri::command]
+pub fn delete_preset(
+    path: &str,
+    config_type: &str,
+    strategy: DeleteStrategy,
+    profiles_directory: Option<String>,
+    data_directory: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let plan = plan_delete_preset(
+        path,
+        config_type,
+        strategy,
+        profiles_directory,
+        data_directory,
+    )?;
 
-                write_to_file(new_file_path.to_str().unwrap().to_string(), pretty_json).ok();
-            });
-    }
+    execute_vendor_plan(plan.clone(), store)?;
 
-    match fs::read_dir(new_directory_path) {
-        Ok(entries) => entries
-            .filter_map(|entry_red| match entry_red {
-                Ok(entry) => {
-                    let path = entry.path();
-
-                    if entry.path().is_file()
-                        && path.extension().and_then(|ext| ext.to_str()) == Some("png")
-                    {
-                        Some(entry)
-                    } else {
-                        None
-                    }
-                }
-                Err(_) => None,
-            })
-            .for_each(|entry| {
-                let img_path = entry.path();
-                let old_name = img_path.file_stem().unwrap();
-                let new_name = replace_name(
-                    old_name.to_str().unwrap(),
-                    old_dir_name,
-                    new_dir_name,
-                    &orca_filament_library_filaments,
-                );
+    Ok(plan)
+}
 
-                let new_img_path = img_path.parent().unwrap().join(new_name + ".png");
-                let file_move_options = file::CopyOptions {
-                    overwrite: false,
-                    skip_exist: false,
-                    buffer_size: 64_000,
-                    ..Default::default()
-                };
-
-                file::move_file(img_path, new_img_path, &file_move_options).ok();
-            }),
-        Err(_err) => (),
+/// Installs the vendor bundle at `zip_path` into `profiles_directory`, or its
+/// overlay. The bundle is unpacked and analysed before anything is written,
+/// errors and preset name collisions with installed vendors stop the import.
+#[tauri::command]
+pub fn import_vendor_bundle(
+    zip_path: &str,
+    profiles_directory: &str,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<BundleImport, Vec<VendorProblem>> {
+    let profiles_directory = Path::new(profiles_directory);
+
+    // the staged files are outside of the workspace, only the target is checked
+    if let Err(err) = store.workspace.check_path(profiles_directory) {
+        return Err(vec![VendorProblem::new(profiles_directory, err)]);
     }
 
-    let mut parsed_config_file_value = serde_json::to_value(&parsed_vendor_config).unwrap();
-    remove_nulls(&mut parsed_config_file_value);
+    let staged = vendor_bundle::stage_bundle(Path::new(zip_path))?;
+    let (plan, warnings) = vendor_bundle::plan_import_bundle(&staged, profiles_directory)?;
 
-    let pretty_json = serde_json::to_string_pretty(&parsed_config_file_value).unwrap();
+    execute_vendor_plan(plan.clone(), store)?;
 
-    write_to_file(new_file_path.to_str().unwrap().to_string(), pretty_json).ok();
-
-    Ok(())
+    Ok(BundleImport {
+        vendor: staged.vendor.clone(),
+        plan,
+        warnings,
+    })
 }
 
 fn check_if_configs_exist(
@@ -789,7 +814,15 @@ fn check_if_configs_exist(
 
                     match file_exists_res {
                         Ok(file_exists) => {
-                            if !file_exists {
+                            if file_exists {
+                                let json_checks = analyse_json_file(
+                                    &complete_path,
+                                    &format!("Config '{}',", name),
+                                    &list_type,
+                                    vendor_config_details,
+                                );
+                                extend_combine_map(&mut config_analysis_messages, json_checks);
+                            } else {
                                 insert_or_push_into_map(
                                     &mut config_analysis_messages,
                                     list_type.clone(),
@@ -859,6 +892,71 @@ pub fn extend_combine_map<T>(
     }
 }
 
+pub fn push_analysis_message(
+    analysis_result: &mut HashMap<String, Vec<AnalysisMessageDetails>>,
+    key: &str,
+    text: String,
+    err_type: ErrType,
+    config_details: &ConfigDetails,
+) {
+    insert_or_push_into_map(
+        analysis_result,
+        key.into(),
+        AnalysisMessageDetails {
+            config_details: config_details.clone(),
+            message: ErrWan {
+                text,
+                r#type: err_type,
+            },
+        },
+    );
+}
+
+// An inheritance that cannot be resolved is reported, only the checks that
+// need the resolved config are skipped
+fn resolve_for_analysis(
+    analysis_result: &mut HashMap<String, Vec<AnalysisMessageDetails>>,
+    path: &str,
+    family: &str,
+    config_type: &str,
+    config_details: &ConfigDetails,
+) -> Option<EffectiveConfig> {
+    match resolve_vendor_config(path, family, config_type) {
+        Ok(effective_config) => {
+            push_inheritance_messages(analysis_result, &effective_config, config_details);
+            Some(effective_config)
+        }
+        Err(err) => {
+            push_analysis_message(
+                analysis_result,
+                "inherits",
+                format!("The inherited values could not be resolved: {}", err),
+                ErrType::Error,
+                config_details,
+            );
+            None
+        }
+    }
+}
+
+/// Reports the problems met while resolving the `inherits` chain of a config.
+pub fn push_inheritance_messages(
+    analysis_result: &mut HashMap<String, Vec<AnalysisMessageDetails>>,
+    effective_config: &EffectiveConfig,
+    config_details: &ConfigDetails,
+) {
+    effective_config.messages.iter().for_each(|message| {
+        insert_or_push_into_map(
+            analysis_result,
+            "inherits".into(),
+            AnalysisMessageDetails {
+                config_details: config_details.clone(),
+                message: message.clone(),
+            },
+        );
+    });
+}
+
 fn filter_analysis_results_into_errors_and_warning(
     analysis_result: HashMap<String, Vec<AnalysisMessageDetails>>,
 ) -> (
@@ -916,77 +1014,126 @@ pub async fn analyse_vendor_config(
     ),
     String,
 > {
-    spawn_blocking(move || {
-        let mut analysis_result: HashMap<String, Vec<AnalysisMessageDetails>> = HashMap::new();
-
-        let path_obj = Path::new(&path);
-        let parent_path = path_obj.parent().unwrap();
-        let config_dir_path = parent_path.join(name.clone());
+    spawn_blocking(move || vendor_config_analysis(path, config_location, name))
+        .await
+        .unwrap_or_else(|e| Err(format!("Task error: {e}")))
+}
 
-        let vendor_config_details = ConfigDetails::new(
-            name.clone(),
-            path.clone(),
-            None,
-            config_location.clone(),
-            "vendor".into(),
-        );
+/// Analysis messages by key, split into errors and warnings.
+pub type ErrorsAndWarnings = (
+    HashMap<String, Vec<AnalysisMessageDetails>>,
+    HashMap<String, Vec<AnalysisMessageDetails>>,
+);
 
-        let parsed_vendor_config = load_vendor_preset(&path)?;
+/// The errors and warnings of the vendor config at `path` and its lists.
+pub fn vendor_config_analysis(
+    path: String,
+    config_location: String,
+    name: String,
+) -> Result<ErrorsAndWarnings, String> {
+    let mut analysis_result: HashMap<String, Vec<AnalysisMessageDetails>> = HashMap::new();
 
-        let vendor_version_rule = !parsed_vendor_config.version.is_none();
+    let path_obj = Path::new(&path);
+    let parent_path = path_obj.parent().unwrap();
+    let config_dir_path = parent_path.join(name.clone());
+
+    let vendor_config_details = ConfigDetails::new(
+        name.clone(),
+        path.clone(),
+        None,
+        config_location.clone(),
+        "vendor".into(),
+    );
+
+    let json_checks = analyse_json_file(&path, "Vendor config", FILE_KEY, &vendor_config_details);
+    let has_json_errors = json_checks
+        .values()
+        .flatten()
+        .any(|details| matches!(details.message.r#type, ErrType::Error));
+    extend_combine_map(&mut analysis_result, json_checks);
+
+    let parsed_vendor_config = match load_vendor_preset(&path) {
+        Ok(parsed_vendor_config) => parsed_vendor_config,
+        Err(err) => {
+            // a file that is not valid JSON has been reported above
+            if !has_json_errors {
+                push_analysis_message(
+                    &mut analysis_result,
+                    FILE_KEY,
+                    err,
+                    ErrType::Critical,
+                    &vendor_config_details,
+                );
+            }
 
-        if !vendor_version_rule {
-            insert_or_push_into_map(
-                &mut analysis_result,
-                "version".into(),
-                AnalysisMessageDetails {
-                    config_details: vendor_config_details.clone(),
-                    message: ErrWan {
-                        text: "Config must contain the key 'config'".into(),
-                        r#type: ErrType::Error,
-                    },
-                },
-            );
+            return Ok(filter_analysis_results_into_errors_and_warning(
+                analysis_result,
+            ));
         }
+    };
 
-        let model_configs_checks = check_if_configs_exist(
-            &parsed_vendor_config.machine_model_list,
-            &config_dir_path,
-            &vendor_config_details,
-            "machine_model_list".into(),
-        );
-        extend_combine_map(&mut analysis_result, model_configs_checks);
-
-        let printer_configs_checks = check_if_configs_exist(
-            &parsed_vendor_config.machine_list,
-            &config_dir_path,
-            &vendor_config_details,
-            "machine_list".into(),
-        );
-        extend_combine_map(&mut analysis_result, printer_configs_checks);
-
-        let filament_configs_checks = check_if_configs_exist(
-            &parsed_vendor_config.filament_list,
-            &config_dir_path,
-            &vendor_config_details,
-            "filament_list".into(),
-        );
-        extend_combine_map(&mut analysis_result, filament_configs_checks);
+    let vendor_version_rule = !parsed_vendor_config.version.is_none();
 
-        let process_configs_checks = check_if_configs_exist(
-            &parsed_vendor_config.process_list,
-            &config_dir_path,
-            &vendor_config_details,
-            "process_list".into(),
+    if !vendor_version_rule {
+        insert_or_push_into_map(
+            &mut analysis_result,
+            "version".into(),
+            AnalysisMessageDetails {
+                config_details: vendor_config_details.clone(),
+                message: ErrWan {
+                    text: "Config must contain the key 'config'".into(),
+                    r#type: ErrType::Error,
+                },
+            },
         );
-        extend_combine_map(&mut analysis_result, process_configs_checks);
+    }
 
-        Ok(filter_analysis_results_into_errors_and_warning(
-            analysis_result,
-        ))
-    })
-    .await
-    .unwrap_or_else(|e| Err(format!("Task error: {e}")))
+    let model_configs_checks = check_if_configs_exist(
+        &parsed_vendor_config.machine_model_list,
+        &config_dir_path,
+        &vendor_config_details,
+        "machine_model_list".into(),
+    );
+    extend_combine_map(&mut analysis_result, model_configs_checks);
+
+    let printer_configs_checks = check_if_configs_exist(
+        &parsed_vendor_config.machine_list,
+        &config_dir_path,
+        &vendor_config_details,
+        "machine_list".into(),
+    );
+    extend_combine_map(&mut analysis_result, printer_configs_checks);
+
+    let filament_configs_checks = check_if_configs_exist(
+        &parsed_vendor_config.filament_list,
+        &config_dir_path,
+        &vendor_config_details,
+        "filament_list".into(),
+    );
+    extend_combine_map(&mut analysis_result, filament_configs_checks);
+
+    let process_configs_checks = check_if_configs_exist(
+        &parsed_vendor_config.process_list,
+        &config_dir_path,
+        &vendor_config_details,
+        "process_list".into(),
+    );
+    extend_combine_map(&mut analysis_result, process_configs_checks);
+
+    let reference_checks = analyse_vendor_references(
+        &parsed_vendor_config,
+        &config_dir_path,
+        &vendor_config_details,
+    );
+    extend_combine_map(&mut analysis_result, reference_checks);
+
+    let raw_vendor_config: Map<String, Value> = load_preset(&path)?;
+    let key_checks = analyse_config_keys(&raw_vendor_config, "vendor", &vendor_config_details);
+    extend_combine_map(&mut analysis_result, key_checks);
+
+    Ok(filter_analysis_results_into_errors_and_warning(
+        analysis_result,
+    ))
 }
 
 fn _check_file_name_and_config_name(path: &str, name: &str) -> bool {
@@ -1131,6 +1278,7 @@ pub async fn analyse_installed_filament_config(
     config_location: String,
     name: String,
     family: String,
+    rule_settings: Option<FilamentRuleSettings>,
 ) -> Result<
     (
         HashMap<String, Vec<AnalysisMessageDetails>>,
@@ -1144,21 +1292,22 @@ pub async fn analyse_installed_filament_config(
         let filament_config_details = ConfigDetails::new(
             name.clone(),
             path.clone(),
-            Some(family),
+            Some(family.clone()),
             config_location.clone(),
             "filament".into(),
         );
 
         let parsed_filament_config = load_filament_preset(&path)?;
 
-        if str_to_bool(
+        let is_instantiated = str_to_bool(
             &parsed_filament_config
                 .instantiation
                 .clone()
                 .unwrap_or("false".into()),
         )
-        .unwrap_or(false)
-        {
+        .unwrap_or(false);
+
+        if is_instantiated {
             check_filament_setting_id(
                 &mut analysis_result,
                 &parsed_filament_config,
@@ -1176,6 +1325,41 @@ pub async fn analyse_installed_filament_config(
             filament_config_details.clone(),
         );
 
+        let raw_filament_config: Map<String, Value> = load_preset(&path)?;
+        let key_checks =
+            analyse_config_keys(&raw_filament_config, "filament", &filament_config_details);
+        extend_combine_map(&mut analysis_result, key_checks);
+
+        let Some(effective_config) = resolve_for_analysis(
+            &mut analysis_result,
+            &path,
+            &family,
+            "filament",
+            &filament_config_details,
+        ) else {
+            return Ok(filter_analysis_results_into_errors_and_warning(
+                analysis_result,
+            ));
+        };
+
+        let override_checks = analyse_redundant_overrides(
+            &raw_filament_config,
+            &effective_config.inherited_values,
+            "filament",
+            &filament_config_details,
+        );
+        extend_combine_map(&mut analysis_result, override_checks);
+
+        // base presets are not selectable and may leave values to their children
+        if is_instantiated {
+            let filament_rule_checks = analyse_filament_rules(
+                &effective_config.values,
+                &rule_settings.unwrap_or_default(),
+                &filament_config_details,
+            );
+            extend_combine_map(&mut analysis_result, filament_rule_checks);
+        }
+
         Ok(filter_analysis_results_into_errors_and_warning(
             analysis_result,
         ))
@@ -1227,6 +1411,38 @@ pub async fn analyse_installed_printer_config(
             );
         }
 
+        let raw_printer_config: Map<String, Value> = load_preset(&path)?;
+        let key_checks =
+            analyse_config_keys(&raw_printer_config, "printer", &filament_config_details);
+        extend_combine_map(&mut analysis_result, key_checks);
+
+        let Some(effective_config) = resolve_for_analysis(
+            &mut analysis_result,
+            &path,
+            filament_config_details
+                .family
+                .as_deref()
+                .unwrap_or_default(),
+            "printer",
+            &filament_config_details,
+        ) else {
+            return Ok(filter_analysis_results_into_errors_and_warning(
+                analysis_result,
+            ));
+        };
+
+        let override_checks = analyse_redundant_overrides(
+            &raw_printer_config,
+            &effective_config.inherited_values,
+            "printer",
+            &filament_config_details,
+        );
+        extend_combine_map(&mut analysis_result, override_checks);
+
+        let bed_geometry_checks =
+            analyse_bed_geometry(&effective_config.values, &filament_config_details);
+        extend_combine_map(&mut analysis_result, bed_geometry_checks);
+
         Ok(filter_analysis_results_into_errors_and_warning(
             analysis_result,
         ))
@@ -1278,6 +1494,34 @@ pub async fn analyse_installed_process_config(
             );
         }
 
+        let raw_process_config: Map<String, Value> = load_preset(&path)?;
+        let key_checks =
+            analyse_config_keys(&raw_process_config, "process", &filament_config_details);
+        extend_combine_map(&mut analysis_result, key_checks);
+
+        let Some(effective_config) = resolve_for_analysis(
+            &mut analysis_result,
+            &path,
+            filament_config_details
+                .family
+                .as_deref()
+                .unwrap_or_default(),
+            "process",
+            &filament_config_details,
+        ) else {
+            return Ok(filter_analysis_results_into_errors_and_warning(
+                analysis_result,
+            ));
+        };
+
+        let override_checks = analyse_redundant_overrides(
+            &raw_process_config,
+            &effective_config.inherited_values,
+            "process",
+            &filament_config_details,
+        );
+        extend_combine_map(&mut analysis_result, override_checks);
+
         Ok(filter_analysis_results_into_errors_and_warning(
             analysis_result,
         ))
@@ -1286,40 +1530,284 @@ pub async fn analyse_installed_process_config(
     .unwrap_or_else(|e| Err(format!("Task error: {e}")))
 }
 
+/// Reports the vendor files of a profiles directory that cannot be loaded, which
+/// are left out of the vendor lists, keyed by file.
 #[tauri::command]
-pub fn add_new_prop_to_file(path: &str, prop_name: &str, prop_value: &str) -> Result<(), String> {
-    // Step 1: Read the JSON file
-    let contents = fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read file '{}': {}", path, e.to_string()))?;
-
-    let mut json_value: Value = serde_json::from_str(&contents)
-        .map_err(|e| format!("Malformed JSON file '{}': {}", path, e.to_string()))?;
-
-    let parsed_value_to_write: Value = serde_json::from_str(&prop_value).map_err(|e| {
-        format!(
-            "Cannot locate property value '{}': {}",
-            prop_name,
-            e.to_string()
-        )
-    })?;
+pub async fn analyse_vendor_directory(
+    path: String,
+    config_location: String,
+) -> Result<
+    Vec<(
+        String,
+        (
+            HashMap<String, Vec<AnalysisMessageDetails>>,
+            HashMap<String, Vec<AnalysisMessageDetails>>,
+        ),
+    )>,
+    String,
+> {
+    spawn_blocking(move || {
+        let vendor_files = get_all_json_files(&path)?;
+
+        Ok(vendor_files
+            .into_iter()
+            .filter_map(|vendor_file| {
+                let err = load_vendor_preset(&vendor_file).err()?;
+
+                let name = Path::new(&vendor_file)
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or_default()
+                    .to_string();
+
+                let vendor_config_details = ConfigDetails::new(
+                    name,
+                    vendor_file.clone(),
+                    None,
+                    config_location.clone(),
+                    "vendor".into(),
+                );
 
-    // Step 2: Add or overwrite a property
-    if let Value::Object(ref mut obj) = json_value {
-        obj.insert(prop_name.to_string(), parsed_value_to_write);
-    }
+                let mut analysis_result = analyse_json_file(
+                    &vendor_file,
+                    "Vendor config",
+                    FILE_KEY,
+                    &vendor_config_details,
+                );
 
-    // Step 3: Write it back to the file (pretty-printed)
-    let string_property = serde_json::to_string_pretty(&json_value).map_err(|e| {
-        format!(
-            "Failed to serialize JSON to string for file '{}': {}",
-            path,
-            e.to_string()
-        )
-    })?;
+                if analysis_result.is_empty() {
+                    push_analysis_message(
+                        &mut analysis_result,
+                        FILE_KEY,
+                        err,
+                        ErrType::Critical,
+                        &vendor_config_details,
+                    );
+                }
+
+                Some((
+                    vendor_file,
+                    filter_analysis_results_into_errors_and_warning(analysis_result),
+                ))
+            })
+            .collect())
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("Task error: {e}")))
+}
+
+/// Lists the JSON problems of a file with their positions.
+#[tauri::command]
+pub fn diagnose_json_file(path: &str) -> Result<Vec<JsonDiagnostic>, String> {
+    let text = fs::read_to_string(read_path(Path::new(path)))
+        .map_err(|e| format!("Failed to read file '{}': {}", path, e))?;
+
+    Ok(diagnose_json(&text).diagnostics)
+}
+
+/// Removes trailing commas, comments and a byte order mark from a file and rewrites
+/// it. Returns the problems that remain, such as duplicate keys.
+#[tauri::command]
+pub fn repair_json_file(
+    path: &str,
+    expected_token: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<Vec<JsonDiagnostic>, WriteError> {
+    // the file is read through the overlay and checked right before it is
+    // rewritten, within the operation
+    let diagnosis = store.record(
+        "repair_json_file",
+        format!("Repair '{}'", path),
+        |operation| {
+            if let Err(conflict) = check_file_token(Path::new(path), &expected_token) {
+                return Ok(Err(conflict));
+            }
+
+            let text = fs::read_to_string(read_path(Path::new(path)))
+                .map_err(|e| format!("Failed to read file '{}': {}", path, e))?;
+            let diagnosis = diagnose_json(&text);
+
+            let Some(repaired) = &diagnosis.repaired else {
+                let problems: Vec<String> = diagnosis
+                    .diagnostics
+                    .iter()
+                    .filter(|diagnostic| !diagnostic.fixable)
+                    .map(JsonDiagnostic::describe)
+                    .collect();
+
+                return Ok(Err(format!(
+                    "Cannot repair '{}' automatically:\n{}",
+                    path,
+                    problems.join("\n")
+                )
+                .into()));
+            };
+
+            if *repaired != text {
+                operation.write(Path::new(path), repaired.as_bytes())?;
+            }
+            Ok(Ok(diagnosis))
+        },
+    )??;
+
+    Ok(diagnosis
+        .diagnostics
+        .into_iter()
+        .filter(|diagnostic| !diagnostic.fixable)
+        .collect())
+}
+
+/// Key analysis for any config, read through `GenericJsonSchema`. The config type
+/// is taken from the config itself when not given.
+#[tauri::command]
+pub async fn analyse_generic_config(
+    path: String,
+    config_location: String,
+    name: String,
+    family: Option<String>,
+    config_type: Option<String>,
+) -> Result<
+    (
+        HashMap<String, Vec<AnalysisMessageDetails>>,
+        HashMap<String, Vec<AnalysisMessageDetails>>,
+    ),
+    String,
+> {
+    spawn_blocking(move || {
+        let parsed_config = load_generic_preset(&path)?;
+        let values = generic_config_values(&parsed_config);
+
+        let config_type = config_type
+            .or(config_type_of(&values).map(str::to_string))
+            .ok_or(format!("Could not determine the config type of '{}'", path))?;
+
+        let config_details = ConfigDetails::new(
+            name,
+            path.clone(),
+            family,
+            config_location,
+            config_type.clone(),
+        );
+
+        let analysis_result = analyse_config_keys(&values, &config_type, &config_details);
+
+        Ok(filter_analysis_results_into_errors_and_warning(
+            analysis_result,
+        ))
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("Task error: {e}")))
+}
+
+/// Removes the keys of a vendor preset that re-declare inherited values and
+/// rewrites the file. Returns the removed keys.
+#[tauri::command]
+pub async fn minimize_config(
+    path: String,
+    family: String,
+    config_type: String,
+    expected_token: Option<String>,
+    store: tauri::State<'_, Arc<FileStore>>,
+) -> Result<Vec<String>, WriteError> {
+    let store = Arc::clone(&store);
+
+    spawn_blocking(move || {
+        store.record(
+            "minimize_config",
+            format!("Minimize '{}'", path),
+            |operation| {
+                if let Err(conflict) = check_file_token(Path::new(&path), &expected_token) {
+                    return Ok(Err(conflict));
+                }
+
+                let effective_config = resolve_vendor_config(&path, &family, &config_type)
+                    .map_err(|err| {
+                        format!(
+                            "Cannot minimize '{}' while its inherited values cannot be resolved: {}",
+                            path, err
+                        )
+                    })?;
+
+                // with a broken chain the inherited values are incomplete
+                if let Some(message) = effective_config.messages.first() {
+                    return Err(format!(
+                        "Cannot minimize '{}' while its inherits chain is broken: {}",
+                        path, message.text
+                    ));
+                }
+
+                let contents = fs::read_to_string(read_path(Path::new(&path)))
+                    .map_err(|e| format!("Failed to read file '{}': {}", path, e))?;
+                let mut raw_config: Map<String, Value> = serde_json::from_str(&contents)
+                    .map_err(|e| format!("Malformed JSON file '{}': {}", path, e))?;
+                let removed_keys = redundant_keys(
+                    &raw_config,
+                    &effective_config.inherited_values,
+                    &config_type,
+                );
+
+                if removed_keys.is_empty() {
+                    return Ok(Ok(removed_keys));
+                }
+
+                for key in &removed_keys {
+                    raw_config.shift_remove(key);
+                }
+
+                let pretty_json = edited_json(Some(&contents), &Value::Object(raw_config))?;
+                operation.write(Path::new(&path), pretty_json.as_bytes())?;
+
+                Ok(Ok(removed_keys))
+            },
+        )?
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("Task error: {e}").into()))
+}
+
+#[tauri::command]
+pub fn add_new_prop_to_file(
+    path: &str,
+    prop_name: &str,
+    prop_value: &str,
+    expected_token: Option<String>,
+    store: tauri::State<Arc<FileStore>>,
+) -> Result<(), WriteError> {
+    store.record(
+        "add_new_prop_to_file",
+        format!("Add '{}' to '{}'", prop_name, path),
+        |operation| {
+            if let Err(conflict) = check_file_token(Path::new(path), &expected_token) {
+                return Ok(Err(conflict));
+            }
+
+            // Step 1: Read the JSON file
+            let contents = fs::read_to_string(read_path(Path::new(path)))
+                .map_err(|e| format!("Failed to read file '{}': {}", path, e.to_string()))?;
+
+            let mut json_value: Value = serde_json::from_str(&contents)
+                .map_err(|e| format!("Malformed JSON file '{}': {}", path, e.to_string()))?;
+
+            let parsed_value_to_write: Value = serde_json::from_str(&prop_value).map_err(|e| {
+                format!(
+                    "Cannot locate property value '{}': {}",
+                    prop_name,
+                    e.to_string()
+                )
+            })?;
+
+            // Step 2: Add or overwrite a property
+            if let Value::Object(ref mut obj) = json_value {
+                obj.insert(prop_name.to_string(), parsed_value_to_write);
+            }
 
-    write_to_file(path.to_string(), string_property)?;
+            // Step 3: Write it back to the file, keeping its layout
+            let string_property = edited_json(Some(&contents), &json_value)?;
+            operation.write(Path::new(path), string_property.as_bytes())?;
 
-    Ok(())
+            Ok(Ok(()))
+        },
+    )?
 }
 
 #[tauri::command]
@@ -1363,7 +1851,7 @@ pub async fn check_collision_in_config_file(
             config_type.into(),
         );
 
-        let contents = fs::read_to_string(&path)
+        let contents = fs::read_to_string(read_path(Path::new(&path)))
             .map_err(|e| format!("Failed to read file '{}': {}", path, e))?;
 
         let json_value: Value = serde_json::from_str(&contents)
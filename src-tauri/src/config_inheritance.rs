@@ -0,0 +1,204 @@
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::configuration_loader::{load_preset, ErrType, ErrWan};
+
+pub const ORCA_FILAMENT_LIBRARY: &str = "OrcaFilamentLibrary";
+
+/// A config merged with all of its ancestors, with the problems found on the way.
+#[derive(Debug, Clone)]
+pub struct EffectiveConfig {
+    pub values: Map<String, Value>,
+    /// What the config would resolve to without its own values.
+    pub inherited_values: Map<String, Value>,
+    pub messages: Vec<ErrWan>,
+}
+
+pub fn vendor_list_key(config_type: &str) -> Option<&'static str> {
+    match config_type {
+        "printer" => Some("machine_list"),
+        "filament" => Some("filament_list"),
+        "process" => Some("process_list"),
+        "printer-model" => Some("machine_model_list"),
+        _ => None,
+    }
+}
+
+/// The directory of vendor `family` that contains `path`, recognised by the
+/// `<family>.json` lying next to it.
+pub fn find_vendor_directory(path: &Path, family: &str) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|ancestor| {
+            ancestor.file_name().and_then(|name| name.to_str()) == Some(family)
+                && ancestor
+                    .parent()
+                    .map(|parent| parent.join(format!("{}.json", family)).is_file())
+                    .unwrap_or(false)
+        })
+        .map(Path::to_path_buf)
+}
+
+// Keys that belong to one preset and are never taken over from its parent
+const NON_INHERITED_KEYS: [&str; 4] = ["name", "inherits", "instantiation", "setting_id"];
+
+/// Takes `parent` out of the inheritance chain of `config`: the values
+/// `config` inherited from it become its own and it inherits from the parent
+/// of `parent` instead, so that its effective values stay the same.
+pub fn detach_from_parent(config: &mut Map<String, Value>, parent: &Map<String, Value>) {
+    for (key, value) in parent {
+        if !NON_INHERITED_KEYS.contains(&key.as_str())
+            && !value.is_null()
+            && !config.contains_key(key)
+        {
+            config.insert(key.clone(), value.clone());
+        }
+    }
+
+    match parent.get("inherits") {
+        Some(grandparent) => config.insert("inherits".into(), grandparent.clone()),
+        None => config.shift_remove("inherits"),
+    };
+}
+
+/// Maps every config name of the given vendor list to its file.
+pub fn vendor_config_index(
+    profiles_dir: &Path,
+    vendor: &str,
+    list_key: &str,
+) -> Result<HashMap<String, PathBuf>, String> {
+    let vendor_file = profiles_dir.join(format!("{}.json", vendor));
+    let vendor_config: Value = load_preset(vendor_file.to_str().unwrap_or_default())?;
+    let vendor_dir = profiles_dir.join(vendor);
+
+    Ok(vendor_config
+        .get(list_key)
+        .and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter_map(|entry| {
+                    let name = entry.get("name")?.as_str()?;
+                    let sub_path = entry.get("sub_path")?.as_str()?;
+                    Some((name.to_string(), vendor_dir.join(sub_path)))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Resolves a config of an installed or loaded system vendor. Parents are looked
+/// up in the same vendor and, for filaments, in the Orca filament library.
+pub fn resolve_vendor_config(
+    path: &str,
+    family: &str,
+    config_type: &str,
+) -> Result<EffectiveConfig, String> {
+    let list_key = vendor_list_key(config_type)
+        .ok_or(format!("Configs of type '{}' cannot inherit", config_type))?;
+
+    let vendor_dir = find_vendor_directory(Path::new(path), family).ok_or(format!(
+        "Could not find the directory of vendor '{}' for '{}'",
+        family, path
+    ))?;
+    let profiles_dir = vendor_dir.parent().unwrap();
+
+    let mut indexes = vec![vendor_config_index(profiles_dir, family, list_key)?];
+
+    if config_type == "filament" && family != ORCA_FILAMENT_LIBRARY {
+        if let Ok(library_index) =
+            vendor_config_index(profiles_dir, ORCA_FILAMENT_LIBRARY, list_key)
+        {
+            indexes.push(library_index);
+        }
+    }
+
+    resolve_config_chain(Path::new(path), &indexes)
+}
+
+/// Follows `inherits` from `path` through the given name indexes, searched in order.
+pub fn resolve_config_chain(
+    path: &Path,
+    indexes: &[HashMap<String, PathBuf>],
+) -> Result<EffectiveConfig, String> {
+    let mut layers: Vec<Map<String, Value>> = Vec::new();
+    let mut messages: Vec<ErrWan> = Vec::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut current = path.to_path_buf();
+
+    loop {
+        let layer_res: Result<Map<String, Value>, String> =
+            load_preset(current.to_str().unwrap_or_default());
+
+        let layer = match layer_res {
+            Ok(layer) => layer,
+            Err(err) if layers.is_empty() => return Err(err),
+            Err(err) => {
+                messages.push(ErrWan {
+                    text: err,
+                    r#type: ErrType::Error,
+                });
+                break;
+            }
+        };
+
+        let name = layer
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        if !visited.insert(name) {
+            messages.push(ErrWan {
+                text: "Circular dependency found, cannot be deinherited".into(),
+                r#type: ErrType::Error,
+            });
+            break;
+        }
+
+        let parent_name = layer
+            .get("inherits")
+            .and_then(Value::as_str)
+            .filter(|parent_name| !parent_name.is_empty())
+            .map(str::to_string);
+
+        layers.push(layer);
+
+        match parent_name {
+            None => break,
+            Some(parent_name) => match indexes.iter().find_map(|index| index.get(&parent_name)) {
+                Some(parent_path) => current = parent_path.clone(),
+                None => {
+                    messages.push(ErrWan {
+                        text: format!("Could not find parent config {}", parent_name),
+                        r#type: ErrType::Error,
+                    });
+                    break;
+                }
+            },
+        }
+    }
+
+    Ok(EffectiveConfig {
+        values: merge_layers(layers.iter()),
+        inherited_values: merge_layers(layers.iter().skip(1)),
+        messages,
+    })
+}
+
+/// Applies layers given leaf first, so that earlier layers win. Nulls are skipped.
+fn merge_layers<'a>(
+    layers: impl DoubleEndedIterator<Item = &'a Map<String, Value>>,
+) -> Map<String, Value> {
+    let mut values = Map::new();
+
+    for layer in layers.rev() {
+        for (key, value) in layer {
+            if !value.is_null() {
+                values.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    values
+}
@@ -0,0 +1,124 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::commons::push_analysis_message;
+use crate::configuration_loader::{
+    AnalysisMessageDetails, ConfigDetails, ErrType, GenericJsonSchema,
+};
+use crate::option_registry::{
+    config_types_of_key, find_option, section_options, ALL_OPTIONS, PRESET_METADATA_KEYS,
+};
+
+/// The config type of a preset, as named by the frontend, from its `type` key.
+/// Vendor files carry no type and are recognised by their config lists.
+pub fn config_type_of(values: &Map<String, Value>) -> Option<&'static str> {
+    match values.get("type").and_then(Value::as_str) {
+        Some("machine") => Some("printer"),
+        Some("machine_model") => Some("printer-model"),
+        Some("filament") => Some("filament"),
+        Some("process") => Some("process"),
+        Some(_) => None,
+        None if values.contains_key("machine_model_list") => Some("vendor"),
+        None => None,
+    }
+}
+
+/// Puts the fields `GenericJsonSchema` pulls out of a preset back next to the rest.
+pub fn generic_config_values(config: &GenericJsonSchema) -> Map<String, Value> {
+    let mut values: Map<String, Value> = config
+        .extra
+        .0
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    values.insert("name".into(), Value::String(config.name.clone()));
+    if let Some(inherits) = &config.inherits {
+        values.insert("inherits".into(), Value::String(inherits.clone()));
+    }
+    if let Some(preset_type) = &config.preset_type {
+        values.insert("type".into(), Value::String(preset_type.clone()));
+    }
+
+    values
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// The candidate closest to `key`, if it is close enough to be a plausible typo
+/// or rename.
+pub fn closest_key<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (key.chars().count() / 3).max(2);
+
+    candidates
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Flags keys of a config that are unknown to the option registry or that belong
+/// to another config type, suggesting the closest valid key for unknown ones.
+pub fn analyse_config_keys(
+    values: &Map<String, Value>,
+    config_type: &str,
+    config_details: &ConfigDetails,
+) -> HashMap<String, Vec<AnalysisMessageDetails>> {
+    let mut analysis_result: HashMap<String, Vec<AnalysisMessageDetails>> = HashMap::new();
+
+    let Some(options) = section_options(config_type) else {
+        return analysis_result;
+    };
+
+    for key in values.keys() {
+        if find_option(options, key).is_some()
+            || (config_type != "vendor" && PRESET_METADATA_KEYS.contains(&key.as_str()))
+        {
+            continue;
+        }
+
+        let owners = config_types_of_key(key);
+
+        let text = if !owners.is_empty() {
+            format!(
+                "'{}' is a {} option and has no effect in a {} config",
+                key,
+                owners.join("/"),
+                config_type
+            )
+        } else if find_option(ALL_OPTIONS, key).is_some() {
+            format!("'{}' is not a {} option", key, config_type)
+        } else {
+            match closest_key(key, options.iter().map(|(id, _)| *id)) {
+                Some(suggestion) => {
+                    format!("Unknown key '{}', did you mean '{}'?", key, suggestion)
+                }
+                None => format!("Unknown key '{}'", key),
+            }
+        };
+
+        push_analysis_message(
+            &mut analysis_result,
+            key,
+            text,
+            ErrType::Warning,
+            config_details,
+        );
+    }
+
+    analysis_result
+}
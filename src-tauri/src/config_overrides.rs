@@ -0,0 +1,155 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::bed_geometry::parse_point;
+use crate::commons::push_analysis_message;
+use crate::configuration_loader::{AnalysisMessageDetails, ConfigDetails, ErrType};
+use crate::option_registry::{option_type_of, OptionType, PRESET_METADATA_KEYS};
+
+const RELATIVE_TOLERANCE: f64 = 1e-9;
+
+// Keys that identify a preset rather than configure it, so they are never redundant
+const IDENTITY_KEYS: [&str; 11] = [
+    "name",
+    "inherits",
+    "type",
+    "from",
+    "instantiation",
+    "version",
+    "setting_id",
+    "filament_id",
+    "filament_settings_id",
+    "print_settings_id",
+    "printer_settings_id",
+];
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.trim().to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(if *flag { "1" } else { "0" }.into()),
+        _ => None,
+    }
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.to_lowercase().as_str() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn numbers_equal(a: &str, b: &str) -> bool {
+    // a percentage never equals an absolute value, whatever the numbers
+    let (a, a_percent) = a.strip_suffix('%').map_or((a, false), |a| (a, true));
+    let (b, b_percent) = b.strip_suffix('%').map_or((b, false), |b| (b, true));
+
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) if a_percent == b_percent => {
+            (a - b).abs() <= RELATIVE_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+        }
+        _ => false,
+    }
+}
+
+fn scalars_equal(a: &str, b: &str, option_type: Option<OptionType>) -> bool {
+    if a == b {
+        return true;
+    }
+
+    match option_type {
+        Some(OptionType::Bool | OptionType::Bools) => match (parse_bool(a), parse_bool(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        },
+        Some(OptionType::Point | OptionType::Points) => match (parse_point(a), parse_point(b)) {
+            (Some(a), Some(b)) => {
+                numbers_equal(&a.x.to_string(), &b.x.to_string())
+                    && numbers_equal(&a.y.to_string(), &b.y.to_string())
+            }
+            _ => false,
+        },
+        Some(
+            OptionType::Float
+            | OptionType::Floats
+            | OptionType::Int
+            | OptionType::Ints
+            | OptionType::Percent
+            | OptionType::Percents
+            | OptionType::FloatOrPercent,
+        )
+        | None => numbers_equal(a, b),
+        _ => false,
+    }
+}
+
+/// Compares two option values the way OrcaSlicer reads them, so that "0.20"
+/// equals "0.2" for numeric options. Single values equal one element lists.
+pub fn option_values_equal(a: &Value, b: &Value, option_type: Option<OptionType>) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let elements = |value: &Value| -> Option<Vec<String>> {
+        match value {
+            Value::Array(values) => values.iter().map(scalar_text).collect(),
+            value => scalar_text(value).map(|text| vec![text]),
+        }
+    };
+
+    match (elements(a), elements(b)) {
+        (Some(a), Some(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|(a, b)| scalars_equal(a, b, option_type))
+        }
+        _ => false,
+    }
+}
+
+/// Keys of `values` that re-declare the value resolved from the `inherits` chain.
+pub fn redundant_keys(
+    values: &Map<String, Value>,
+    inherited_values: &Map<String, Value>,
+    config_type: &str,
+) -> Vec<String> {
+    values
+        .iter()
+        .filter(|(key, _)| {
+            !IDENTITY_KEYS.contains(&key.as_str()) && !PRESET_METADATA_KEYS.contains(&key.as_str())
+        })
+        .filter(|(key, value)| {
+            inherited_values.get(*key).is_some_and(|inherited_value| {
+                option_values_equal(value, inherited_value, option_type_of(config_type, key))
+            })
+        })
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Flags the keys of a child preset whose value equals the inherited one.
+pub fn analyse_redundant_overrides(
+    values: &Map<String, Value>,
+    inherited_values: &Map<String, Value>,
+    config_type: &str,
+    config_details: &ConfigDetails,
+) -> HashMap<String, Vec<AnalysisMessageDetails>> {
+    let mut analysis_result: HashMap<String, Vec<AnalysisMessageDetails>> = HashMap::new();
+
+    for key in redundant_keys(values, inherited_values, config_type) {
+        push_analysis_message(
+            &mut analysis_result,
+            &key,
+            format!(
+                "'{}' re-declares the inherited value {} and can be removed",
+                key, inherited_values[&key]
+            ),
+            ErrType::Warning,
+            config_details,
+        );
+    }
+
+    analysis_result
+}
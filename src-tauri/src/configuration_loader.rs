@@ -6,6 +6,10 @@ use std::fs;
 use std::path;
 use ts_rs::TS;
 
+use crate::file_store::sha256_hex;
+use crate::json_diagnostics::{diagnose_json, JsonDiagnostic};
+use crate::overlay::{merged_listing, read_path};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Extra(pub HashMap<String, Value>);
 
@@ -225,70 +229,104 @@ pub struct GenericJsonSchema {
     pub extra: Extra,
 }
 
-fn get_all_json_files(path: &str) -> Result<Vec<String>, String> {
+fn json_files_in_dir(path: &path::Path) -> Vec<path::PathBuf> {
     match fs::read_dir(path) {
-        Ok(entries) => Ok(
-            entries
-                .filter_map(|entry_red| match entry_red {
-                    Ok(entry) => {
-                        let path = entry.path();
-
-                        if entry.path().is_file()
-                            && path.extension().and_then(|ext| ext.to_str()) == Some("json")
-                        {
-                            Some(entry)
-                        } else {
-                            None
-                        }
-                    }
-                    Err(_) => None,
-                })
-                .map(|entry| {
+        Ok(entries) => entries
+            .filter_map(|entry_red| match entry_red {
+                Ok(entry) => {
                     let path = entry.path();
-                    format!("{}", path.display())
-                })
-                .collect::<Vec<String>>(), // Collect as Vec<String>
-        ),
-        Err(_err) => {
-            Err("Could not open or read directory. Or the structure is deformed".to_string())
-        }
-    }
-}
 
-#[tauri::command]
-pub fn load_all_system_vendor_profiles(
-    path: &str,
-) -> Result<BTreeMap<String, VendorJsonSchema>, String> {
-    let all_vendor_json_files_res = get_all_json_files(path);
-
-    match all_vendor_json_files_res {
-        Ok(all_vendor_json_files) => Ok(all_vendor_json_files
-            .into_iter()
-            .filter_map(|vendor_json_file| {
-                let data = fs::read_to_string(&vendor_json_file).unwrap();
-                let parsed_vendor_config_res: Result<VendorJsonSchema, _> =
-                    serde_json::from_str(&data);
-
-                match parsed_vendor_config_res {
-                    Ok(parsed_vendor_config) => {
-                        let path_stem = path::Path::new(&vendor_json_file).file_stem().unwrap();
-
-                        Some((
-                            path_stem.to_str().unwrap().to_string(),
-                            parsed_vendor_config,
-                        ))
-                    }
-                    Err(err) => {
-                        println!("{:#?}", err);
+                    if path.is_file()
+                        && path.extension().and_then(|ext| ext.to_str()) == Some("json")
+                    {
+                        Some(path)
+                    } else {
                         None
                     }
                 }
+                Err(_) => None,
             })
-            .collect::<BTreeMap<_, _>>()),
-        Err(err) => Err(err),
+            .collect(),
+        Err(_) => Vec::new(),
     }
 }
 
+/// The json files in `path`, merged with the overlay when there is one.
+pub fn get_all_json_files(path: &str) -> Result<Vec<String>, String> {
+    let dir_path = path::Path::new(path);
+
+    if !dir_path.is_dir() && !read_path(dir_path).is_dir() {
+        return Err("Could not open or read directory. Or the structure is deformed".to_string());
+    }
+
+    Ok(merged_listing(dir_path, json_files_in_dir)
+        .into_iter()
+        .map(|path| format!("{}", path.display()))
+        .collect())
+}
+
+/// A vendor file that could not be loaded, with the problems found in it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BrokenVendor {
+    pub path: String,
+    pub error: String,
+    pub diagnostics: Vec<JsonDiagnostic>,
+}
+
+/// The vendors of a profiles directory by file name, and the vendor files that
+/// could not be loaded.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SystemVendorProfiles {
+    pub vendors: BTreeMap<String, VendorJsonSchema>,
+    pub broken: Vec<BrokenVendor>,
+}
+
+#[tauri::command]
+pub fn load_all_system_vendor_profiles(path: &str) -> Result<SystemVendorProfiles, String> {
+    let mut profiles = SystemVendorProfiles {
+        vendors: BTreeMap::new(),
+        broken: Vec::new(),
+    };
+
+    for vendor_json_file in get_all_json_files(path)? {
+        let vendor_json_path = path::Path::new(&vendor_json_file);
+
+        let parsed_vendor_config_res = fs::read_to_string(read_path(vendor_json_path))
+            .map_err(|_| {
+                (
+                    format!("Could not read profile: {}", vendor_json_file),
+                    None,
+                )
+            })
+            .and_then(|data| {
+                parse_preset::<VendorJsonSchema>(&vendor_json_file, &data)
+                    .map_err(|err| (err, Some(data)))
+            });
+
+        match parsed_vendor_config_res {
+            Ok(parsed_vendor_config) => {
+                let path_stem = vendor_json_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                profiles.vendors.insert(path_stem, parsed_vendor_config);
+            }
+            Err((error, data)) => profiles.broken.push(BrokenVendor {
+                path: vendor_json_file,
+                error,
+                diagnostics: data
+                    .map(|data| diagnose_json(&data).diagnostics)
+                    .unwrap_or_default(),
+            }),
+        }
+    }
+
+    Ok(profiles)
+}
+
 #[tauri::command]
 pub fn load_all_user_printer_profiles_in_dir(
     path: &str,
@@ -381,22 +419,59 @@ pub fn load_all_process_presets(
     load_all_x_presets(path, config_name_and_paths)
 }
 
+fn parse_preset<T: DeserializeOwned>(path: &str, data: &str) -> Result<T, String> {
+    let parsed_config_res: Result<T, _> = serde_json::from_str(data);
+
+    match parsed_config_res {
+        Ok(parsed_config) => Ok(parsed_config),
+        Err(err) => {
+            // serde stops at the first problem, the diagnostics list all of them
+            let diagnosis = diagnose_json(data);
+            let problems: Vec<String> = diagnosis.errors().map(JsonDiagnostic::describe).collect();
+
+            if problems.is_empty() {
+                Err(format!("Malformed or invalid JSON file: {}\n{}", path, err))
+            } else {
+                Err(format!(
+                    "Malformed or invalid JSON file: {}\n{}",
+                    path,
+                    problems.join("\n")
+                ))
+            }
+        }
+    }
+}
+
 pub fn load_preset<T: DeserializeOwned>(path: &str) -> Result<T, String> {
-    let read_file_res = fs::read_to_string(path);
+    let read_file_res = fs::read_to_string(read_path(path::Path::new(path)));
 
     match read_file_res {
-        Ok(data) => {
-            let parsed_config_res: Result<T, _> = serde_json::from_str(&data);
-
-            match parsed_config_res {
-                Ok(parsed_config) => Ok(parsed_config),
-                Err(err) => Err(format!("Malformed or invalid JSON file: {}\n{}", path, err)),
-            }
-        }
+        Ok(data) => parse_preset(path, &data),
         _ => Err(format!("Could not read profile: {}", path)),
     }
 }
 
+/// A config together with the version token of the file it was read from.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TokenedPreset {
+    pub config: GenericJsonSchema,
+    pub token: String,
+}
+
+/// Loads a config for a read-modify-write. Pass the token back to the write
+/// command so that it refuses to overwrite changes made in between.
+#[tauri::command]
+pub fn load_generic_preset_with_token(path: &str) -> Result<TokenedPreset, String> {
+    let data = fs::read_to_string(read_path(path::Path::new(path)))
+        .map_err(|_| format!("Could not read profile: {}", path))?;
+
+    Ok(TokenedPreset {
+        config: parse_preset(path, &data)?,
+        token: sha256_hex(data.as_bytes()),
+    })
+}
+
 #[tauri::command]
 pub fn load_filament_preset(path: &str) -> Result<FilamentJsonSchema, String> {
     load_preset(path)
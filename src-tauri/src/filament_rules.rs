@@ -0,0 +1,277 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use crate::commons::{parse_numbers, push_analysis_message};
+use crate::configuration_loader::{AnalysisMessageDetails, ConfigDetails, ErrType};
+
+const BED_TEMPERATURE_KEYS: [&str; 12] = [
+    "cool_plate_temp",
+    "cool_plate_temp_initial_layer",
+    "eng_plate_temp",
+    "eng_plate_temp_initial_layer",
+    "hot_plate_temp",
+    "hot_plate_temp_initial_layer",
+    "supertack_plate_temp",
+    "supertack_plate_temp_initial_layer",
+    "textured_cool_plate_temp",
+    "textured_cool_plate_temp_initial_layer",
+    "textured_plate_temp",
+    "textured_plate_temp_initial_layer",
+];
+
+const NOZZLE_TEMPERATURE_KEYS: [&str; 2] =
+    ["nozzle_temperature", "nozzle_temperature_initial_layer"];
+
+const DIAMETER_TOLERANCE: f64 = 1e-6;
+
+/// Tunable limits of the built-in filament rules. Anything left out by the caller
+/// falls back to the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct FilamentRuleSettings {
+    pub allowed_filament_diameters: Vec<f64>,
+    pub max_bed_temperature: f64,
+}
+
+impl Default for FilamentRuleSettings {
+    fn default() -> Self {
+        Self {
+            allowed_filament_diameters: vec![1.75, 2.85],
+            max_bed_temperature: 150.0,
+        }
+    }
+}
+
+/// Per extruder values of a numeric key. Reports keys that cannot be read as numbers
+/// and returns `None` for them, as for missing keys.
+fn numbers_of(
+    analysis_result: &mut HashMap<String, Vec<AnalysisMessageDetails>>,
+    values: &Map<String, Value>,
+    key: &str,
+    config_details: &ConfigDetails,
+) -> Option<Vec<f64>> {
+    let value = values.get(key)?;
+    let numbers = parse_numbers(value);
+
+    if numbers.is_none() {
+        push_analysis_message(
+            analysis_result,
+            key,
+            format!("Could not parse {} '{}' as numbers", key, value),
+            ErrType::Error,
+            config_details,
+        );
+    }
+
+    numbers
+}
+
+fn extruder_suffix(numbers: &[f64], index: usize) -> String {
+    if numbers.len() > 1 {
+        format!(" for extruder {}", index + 1)
+    } else {
+        "".into()
+    }
+}
+
+// Vector options may hold a single value that applies to every extruder
+fn value_at(numbers: &[f64], index: usize) -> Option<f64> {
+    numbers.get(index).or(numbers.last()).copied()
+}
+
+/// Physical sanity rules for filament presets, to be run on the effective config.
+pub fn analyse_filament_rules(
+    values: &Map<String, Value>,
+    settings: &FilamentRuleSettings,
+    config_details: &ConfigDetails,
+) -> HashMap<String, Vec<AnalysisMessageDetails>> {
+    let mut analysis_result: HashMap<String, Vec<AnalysisMessageDetails>> = HashMap::new();
+    let result = &mut analysis_result;
+
+    let range_low = numbers_of(
+        result,
+        values,
+        "nozzle_temperature_range_low",
+        config_details,
+    );
+    let range_high = numbers_of(
+        result,
+        values,
+        "nozzle_temperature_range_high",
+        config_details,
+    );
+
+    let mut valid_ranges: Vec<Option<(f64, f64)>> = Vec::new();
+
+    if let (Some(range_low), Some(range_high)) = (&range_low, &range_high) {
+        for index in 0..range_low.len().max(range_high.len()) {
+            let (Some(low), Some(high)) = (value_at(range_low, index), value_at(range_high, index))
+            else {
+                continue;
+            };
+
+            if low > high {
+                push_analysis_message(
+                    result,
+                    "nozzle_temperature_range_low",
+                    format!(
+                        "nozzle_temperature_range_low ({}) is higher than nozzle_temperature_range_high ({}){}",
+                        low,
+                        high,
+                        extruder_suffix(range_low, index)
+                    ),
+                    ErrType::Error,
+                    config_details,
+                );
+                valid_ranges.push(None);
+            } else {
+                valid_ranges.push(Some((low, high)));
+            }
+        }
+    }
+
+    for key in NOZZLE_TEMPERATURE_KEYS {
+        let Some(temperatures) = numbers_of(result, values, key, config_details) else {
+            continue;
+        };
+
+        for (index, temperature) in temperatures.iter().enumerate() {
+            let range = valid_ranges
+                .get(index)
+                .or(valid_ranges.last())
+                .copied()
+                .flatten();
+
+            if let Some((low, high)) = range {
+                if *temperature < low || *temperature > high {
+                    push_analysis_message(
+                        result,
+                        key,
+                        format!(
+                            "{} ({}) is outside of the nozzle temperature range {}-{}{}",
+                            key,
+                            temperature,
+                            low,
+                            high,
+                            extruder_suffix(&temperatures, index)
+                        ),
+                        ErrType::Error,
+                        config_details,
+                    );
+                }
+            }
+        }
+    }
+
+    for key in BED_TEMPERATURE_KEYS {
+        let Some(temperatures) = numbers_of(result, values, key, config_details) else {
+            continue;
+        };
+
+        for (index, temperature) in temperatures.iter().enumerate() {
+            if *temperature < 0.0 {
+                push_analysis_message(
+                    result,
+                    key,
+                    format!(
+                        "{} ({}) cannot be negative{}",
+                        key,
+                        temperature,
+                        extruder_suffix(&temperatures, index)
+                    ),
+                    ErrType::Error,
+                    config_details,
+                );
+            } else if *temperature > settings.max_bed_temperature {
+                push_analysis_message(
+                    result,
+                    key,
+                    format!(
+                        "{} ({}) is implausibly high, bed temperatures above {} are not expected{}",
+                        key,
+                        temperature,
+                        settings.max_bed_temperature,
+                        extruder_suffix(&temperatures, index)
+                    ),
+                    ErrType::Warning,
+                    config_details,
+                );
+            }
+        }
+    }
+
+    for key in ["filament_density", "filament_cost"] {
+        let Some(numbers) = numbers_of(result, values, key, config_details) else {
+            continue;
+        };
+
+        for (index, number) in numbers.iter().enumerate() {
+            if *number <= 0.0 {
+                push_analysis_message(
+                    result,
+                    key,
+                    format!(
+                        "{} must be positive, found {}{}",
+                        key,
+                        number,
+                        extruder_suffix(&numbers, index)
+                    ),
+                    ErrType::Error,
+                    config_details,
+                );
+            }
+        }
+    }
+
+    if let Some(speeds) = numbers_of(
+        result,
+        values,
+        "filament_max_volumetric_speed",
+        config_details,
+    ) {
+        for (index, speed) in speeds.iter().enumerate() {
+            if *speed <= 0.0 {
+                push_analysis_message(
+                    result,
+                    "filament_max_volumetric_speed",
+                    format!(
+                        "filament_max_volumetric_speed must be greater than zero, found {}{}",
+                        speed,
+                        extruder_suffix(&speeds, index)
+                    ),
+                    ErrType::Error,
+                    config_details,
+                );
+            }
+        }
+    }
+
+    if let Some(diameters) = numbers_of(result, values, "filament_diameter", config_details) {
+        for (index, diameter) in diameters.iter().enumerate() {
+            let allowed = settings
+                .allowed_filament_diameters
+                .iter()
+                .any(|allowed| (allowed - diameter).abs() <= DIAMETER_TOLERANCE);
+
+            if !allowed {
+                push_analysis_message(
+                    result,
+                    "filament_diameter",
+                    format!(
+                        "filament_diameter ({}) is not one of the allowed diameters {:?}{}",
+                        diameter,
+                        settings.allowed_filament_diameters,
+                        extruder_suffix(&diameters, index)
+                    ),
+                    ErrType::Error,
+                    config_details,
+                );
+            }
+        }
+    }
+
+    analysis_result
+}
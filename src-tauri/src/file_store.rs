@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+use crate::audit::AUDIT_DIRECTORY;
+use crate::journal::{load_journal_state, JournalState, JOURNAL_DIRECTORY};
+use crate::overlay::read_path;
+use crate::trash::TRASH_DIRECTORY;
+use crate::workspace::WorkspaceRoots;
+
+const BACKUP_DIRECTORY: &str = "backups";
+
+// Each backed up file gets a directory with this file naming the original path
+const SOURCE_FILE: &str = "source.txt";
+
+const DEFAULT_MAX_BACKUPS_PER_FILE: usize = 10;
+
+// Keeps temporary files of concurrent writes apart
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A previous version of a file, kept by the `FileStore`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BackupEntry {
+    pub id: String,
+    pub path: String,
+    pub backup_path: String,
+    #[ts(type = "number")]
+    pub created_at: u64,
+    #[ts(type = "number")]
+    pub size: u64,
+}
+
+/// Every write to a config goes through here, so that files are replaced
+/// atomically, the previous version is kept in the app managed backups and
+/// whole operations are journaled for undo and logged for auditing. Only paths
+/// inside the workspace directories are written to.
+pub struct FileStore {
+    pub audit_directory: PathBuf,
+    pub audit_lock: Mutex<()>,
+    pub backup_directory: PathBuf,
+    pub max_backups_per_file: usize,
+    pub journal_directory: PathBuf,
+    pub journal: Mutex<JournalState>,
+    pub trash_directory: PathBuf,
+    pub workspace: WorkspaceRoots,
+}
+
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// The content hash of the file at `path`, `None` when there is no file. Loaders
+/// hand it out as the version token that writes are checked against.
+pub fn file_hash(path: &Path) -> Result<Option<String>, String> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    fs::read(path)
+        .map(|content| Some(sha256_hex(&content)))
+        .map_err(|err| format!("Could not read '{}': {}", path.display(), err))
+}
+
+/// A write refused because the file changed after it was read, with what is on
+/// disk now so that the changes can be merged.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WriteConflict {
+    pub path: String,
+    pub message: String,
+    pub token: Option<String>,
+    pub current_content: Option<String>,
+}
+
+/// The error of a command that checks version tokens. Plain errors stay strings.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum WriteError {
+    Message(String),
+    Conflict(WriteConflict),
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WriteError::Message(message) => f.write_str(message),
+            WriteError::Conflict(conflict) => f.write_str(&conflict.message),
+        }
+    }
+}
+
+impl From<String> for WriteError {
+    fn from(message: String) -> Self {
+        WriteError::Message(message)
+    }
+}
+
+/// Refuses to go on when `path` no longer matches the token it was read with.
+/// Without a token the write is not checked.
+pub fn check_file_token(path: &Path, expected_token: &Option<String>) -> Result<(), WriteError> {
+    let Some(expected_token) = expected_token else {
+        return Ok(());
+    };
+
+    let path = &read_path(path);
+    let token = file_hash(path)?;
+    if token.as_ref() == Some(expected_token) {
+        return Ok(());
+    }
+
+    Err(WriteError::Conflict(WriteConflict {
+        path: path.to_string_lossy().to_string(),
+        message: format!(
+            "'{}' was changed by another program after it was loaded",
+            path.display()
+        ),
+        token,
+        current_content: fs::read_to_string(path).ok(),
+    }))
+}
+
+// The key of a file must not change when the file itself is deleted, so only the
+// parent directory is resolved
+fn absolute_path(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(file_name)) => fs::canonicalize(parent)
+            .map(|parent| parent.join(file_name))
+            .unwrap_or(path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn io_error(action: &str, path: &Path, err: std::io::Error) -> String {
+    format!(
+        "Failed to {} '{}': {}\nYou may need to relaunch the app as administrator",
+        action,
+        path.display(),
+        err
+    )
+}
+
+/// Writes `content` to a temporary file next to `path`, flushes it to disk and
+/// renames it over `path`, so readers see either the old or the new file.
+pub fn atomic_write(path: &Path, content: &[u8]) -> Result<(), String> {
+    let directory = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or(format!("'{}' is not a file path", path.display()))?;
+
+    let temp_path = directory.join(format!(
+        ".{}.{}-{}.tmp",
+        file_name,
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let write_res = File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .map_err(|err| io_error("write", &temp_path, err))
+        .and_then(|_| fs::rename(&temp_path, path).map_err(|err| io_error("replace", path, err)));
+
+    if write_res.is_err() {
+        fs::remove_file(&temp_path).ok();
+        return write_res;
+    }
+
+    // persist the rename itself, directories cannot be opened for this on Windows
+    #[cfg(unix)]
+    if let Ok(directory) = File::open(directory) {
+        directory.sync_all().ok();
+    }
+
+    Ok(())
+}
+
+impl FileStore {
+    pub fn new(app_data_directory: PathBuf) -> Self {
+        let journal_directory = app_data_directory.join(JOURNAL_DIRECTORY);
+
+        Self {
+            audit_directory: app_data_directory.join(AUDIT_DIRECTORY),
+            audit_lock: Mutex::new(()),
+            backup_directory: app_data_directory.join(BACKUP_DIRECTORY),
+            max_backups_per_file: DEFAULT_MAX_BACKUPS_PER_FILE,
+            journal: Mutex::new(load_journal_state(&journal_directory)),
+            journal_directory,
+            trash_directory: app_data_directory.join(TRASH_DIRECTORY),
+            workspace: WorkspaceRoots::default(),
+        }
+    }
+
+    fn file_backup_directory(&self, path: &Path) -> PathBuf {
+        let key = sha256_hex(absolute_path(path).to_string_lossy().as_bytes());
+
+        self.backup_directory.join(&key[..16])
+    }
+
+    /// Backs up `path`, then replaces it atomically with `content`.
+    pub fn write(&self, path: &Path, content: &[u8]) -> Result<(), String> {
+        self.backup(path)?;
+        atomic_write(path, content)
+    }
+
+    /// Copies the current version of `path` into its backups and drops the oldest
+    /// backups beyond the limit. Does nothing for files that do not exist yet.
+    pub fn backup(&self, path: &Path) -> Result<Option<BackupEntry>, String> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read(path).map_err(|err| io_error("read", path, err))?;
+        let directory = self.file_backup_directory(path);
+        fs::create_dir_all(&directory).map_err(|err| io_error("create", &directory, err))?;
+
+        let source_path = directory.join(SOURCE_FILE);
+        if !source_path.is_file() {
+            atomic_write(
+                &source_path,
+                absolute_path(path).to_string_lossy().as_bytes(),
+            )?;
+        }
+
+        let mut created_at = unix_millis();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("bak");
+
+        // two writes within the same millisecond must not share a backup
+        while directory
+            .join(format!("{}.{}", created_at, extension))
+            .exists()
+        {
+            created_at += 1;
+        }
+
+        let backup_path = directory.join(format!("{}.{}", created_at, extension));
+        atomic_write(&backup_path, &content)?;
+
+        let backups = self.list_backups(path)?;
+        for old_backup in backups.iter().skip(self.max_backups_per_file) {
+            fs::remove_file(&old_backup.backup_path).ok();
+        }
+
+        Ok(backups
+            .into_iter()
+            .find(|backup| Path::new(&backup.backup_path).file_name() == backup_path.file_name()))
+    }
+
+    /// Backups of `path`, newest first.
+    pub fn list_backups(&self, path: &Path) -> Result<Vec<BackupEntry>, String> {
+        let directory = self.file_backup_directory(path);
+        if !directory.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let source = fs::read_to_string(directory.join(SOURCE_FILE))
+            .unwrap_or(path.to_string_lossy().to_string());
+
+        let entries = fs::read_dir(&directory).map_err(|err| io_error("read", &directory, err))?;
+
+        let mut backups: Vec<BackupEntry> = entries
+            .filter_map(|entry| {
+                let backup_path = entry.ok()?.path();
+                let id = backup_path.file_name()?.to_str()?.to_string();
+                let created_at: u64 = backup_path.file_stem()?.to_str()?.parse().ok()?;
+                let size = backup_path.metadata().ok()?.len();
+
+                Some(BackupEntry {
+                    id,
+                    path: source.clone(),
+                    backup_path: backup_path.to_string_lossy().to_string(),
+                    created_at,
+                    size,
+                })
+            })
+            .collect();
+
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+
+        Ok(backups)
+    }
+
+    /// Puts a backup back in place, as a journaled operation. The version it
+    /// replaces is backed up first.
+    pub fn restore_backup(&self, path: &Path, backup_id: &str) -> Result<(), String> {
+        let backup = self
+            .list_backups(path)?
+            .into_iter()
+            .find(|backup| backup.id == backup_id)
+            .ok_or(format!(
+                "There is no backup '{}' of '{}'",
+                backup_id,
+                path.display()
+            ))?;
+
+        let backup_path = PathBuf::from(&backup.backup_path);
+        let content = fs::read(&backup_path).map_err(|err| io_error("read", &backup_path, err))?;
+
+        self.record(
+            "restore_backup",
+            format!("Restore backup '{}' of '{}'", backup_id, path.display()),
+            |operation| operation.write(path, &content),
+        )
+    }
+}
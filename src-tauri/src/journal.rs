@@ -0,0 +1,611 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::audit::AuditRecord;
+use crate::file_store::{atomic_write, file_hash, sha256_hex, unix_millis, FileStore};
+use crate::overlay::{
+    overlay_counterpart, overlay_directory, read_path, whiteout_path, write_path,
+};
+
+pub const JOURNAL_DIRECTORY: &str = "journal";
+const JOURNAL_FILE: &str = "journal.json";
+const BLOB_DIRECTORY: &str = "blobs";
+
+const MAX_JOURNAL_ENTRIES: usize = 100;
+
+/// One file touched by an operation. `before` and `after` are content hashes of
+/// the stored versions, `None` when the file did not exist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FileChange {
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// A mutating command as a whole, undone and redone in one step.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JournalEntry {
+    #[ts(type = "number")]
+    pub id: u64,
+    pub command: String,
+    pub description: String,
+    #[ts(type = "number")]
+    pub created_at: u64,
+    pub changes: Vec<FileChange>,
+    pub created_directories: Vec<String>,
+    /// Why the operation, or the undo or redo returning the entry, is missing
+    /// from the audit log.
+    #[serde(default)]
+    pub warning: Option<String>,
+}
+
+/// The recorded operations. Entries before `position` can be undone, the
+/// ones after it redone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JournalState {
+    pub entries: Vec<JournalEntry>,
+    pub position: usize,
+}
+
+pub fn load_journal_state(journal_directory: &Path) -> JournalState {
+    fs::read_to_string(journal_directory.join(JOURNAL_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn io_error(action: &str, path: &Path, err: std::io::Error) -> String {
+    format!("Failed to {} '{}': {}", action, path.display(), err)
+}
+
+/// Collects the file changes of one command while applying them. Every path
+/// is checked against the workspace directories first, and redirected into
+/// the overlay when the installation is read-only.
+pub struct Operation<'a> {
+    store: &'a FileStore,
+    command: String,
+    description: String,
+    changes: Vec<FileChange>,
+    created_directories: Vec<PathBuf>,
+}
+
+impl<'a> Operation<'a> {
+    fn store_blob(&self, content: &[u8]) -> Result<String, String> {
+        let hash = sha256_hex(content);
+        let blob_path = self
+            .store
+            .journal_directory
+            .join(BLOB_DIRECTORY)
+            .join(&hash);
+
+        if !blob_path.is_file() {
+            let blob_directory = self.store.journal_directory.join(BLOB_DIRECTORY);
+            fs::create_dir_all(&blob_directory)
+                .map_err(|err| io_error("create", &blob_directory, err))?;
+            atomic_write(&blob_path, content)?;
+        }
+
+        Ok(hash)
+    }
+
+    fn snapshot(&self, path: &Path) -> Result<Option<String>, String> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read(path).map_err(|err| io_error("read", path, err))?;
+        self.store_blob(&content).map(Some)
+    }
+
+    // keeps one change per file, from its first known state to its last
+    fn record(&mut self, path: &Path, before: Option<String>, after: Option<String>) {
+        let path = path.to_string_lossy().to_string();
+
+        match self.changes.iter_mut().find(|change| change.path == path) {
+            Some(change) => change.after = after,
+            None => self.changes.push(FileChange {
+                path,
+                before,
+                after,
+            }),
+        }
+    }
+
+    /// `path` if it lies in the workspace directories.
+    pub fn check_path(&self, path: &Path) -> Result<PathBuf, String> {
+        self.store.workspace.check_path(path)
+    }
+
+    /// Creates `path` and its missing parents, remembering the new directories.
+    pub fn create_dir_all(&mut self, path: &Path) -> Result<(), String> {
+        let path = &write_path(&self.store.workspace.check_path(path)?);
+        let missing: Vec<PathBuf> = path
+            .ancestors()
+            .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+            .map(Path::to_path_buf)
+            .collect();
+
+        fs::create_dir_all(path).map_err(|err| io_error("create", path, err))?;
+
+        self.created_directories.extend(missing.into_iter().rev());
+        Ok(())
+    }
+
+    pub fn write(&mut self, path: &Path, content: &[u8]) -> Result<(), String> {
+        let path = &write_path(&self.store.workspace.check_path(path)?);
+        let before = self.snapshot(path)?;
+
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent)?;
+        }
+
+        self.store.write(path, content)?;
+
+        let after = self.store_blob(content)?;
+        self.record(path, before, Some(after));
+
+        self.remove_whiteout(path)
+    }
+
+    // a file written again through the overlay is no longer deleted
+    fn remove_whiteout(&mut self, path: &Path) -> Result<(), String> {
+        let whiteout = whiteout_path(path);
+        if overlay_directory().is_some_and(|overlay| path.starts_with(overlay))
+            && whiteout.is_file()
+        {
+            self.remove_file(&whiteout)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn copy(&mut self, path: &Path, new_path: &Path) -> Result<(), String> {
+        let path = &read_path(&self.store.workspace.check_path(path)?);
+        let content = fs::read(path).map_err(|err| io_error("read", path, err))?;
+        self.write(new_path, &content)
+    }
+
+    pub fn remove_file(&mut self, path: &Path) -> Result<(), String> {
+        let path = &self.store.workspace.check_path(path)?;
+
+        // the base file stays, a whiteout next to its overlay version hides it
+        if let Some(overlay_path) = overlay_counterpart(path) {
+            let in_base = path.is_file();
+            let in_overlay = overlay_path.is_file();
+
+            if (!in_base && !in_overlay) || whiteout_path(&overlay_path).is_file() {
+                return Err(format!("'{}' does not exist", path.display()));
+            }
+            if in_overlay {
+                self.remove_file(&overlay_path)?;
+            }
+            if in_base {
+                self.write(&whiteout_path(&overlay_path), b"")?;
+            }
+            return Ok(());
+        }
+
+        let before = self.snapshot(path)?;
+
+        self.store.backup(path)?;
+        fs::remove_file(path).map_err(|err| io_error("delete", path, err))?;
+
+        self.record(path, before, None);
+        Ok(())
+    }
+
+    pub fn rename(&mut self, path: &Path, new_path: &Path) -> Result<(), String> {
+        let path = &self.store.workspace.check_path(path)?;
+        let new_path = &self.store.workspace.check_path(new_path)?;
+
+        // base files cannot be moved, they are copied into the overlay instead
+        if overlay_counterpart(path).is_some() || overlay_counterpart(new_path).is_some() {
+            self.copy(path, new_path)?;
+            return self.remove_file(path);
+        }
+
+        let moved = self.snapshot(path)?;
+        let replaced = self.snapshot(new_path)?;
+
+        if let Some(parent) = new_path.parent() {
+            self.create_dir_all(parent)?;
+        }
+
+        // renaming replaces an existing file at the new path
+        self.store.backup(new_path)?;
+        fs::rename(path, new_path).map_err(|err| io_error("rename", path, err))?;
+
+        self.record(path, moved.clone(), None);
+        self.record(new_path, replaced, moved);
+        Ok(())
+    }
+
+    /// Moves staged files and directories to paths that do not exist yet with a
+    /// single rename each, and records everything moved in as created. When a
+    /// rename fails, the ones done before it are moved back.
+    pub fn move_into_place(&mut self, moves: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+        let mut paths = Vec::new();
+        for (_, path) in moves {
+            let path = write_path(&self.store.workspace.check_path(path)?);
+            if path.exists() {
+                return Err(format!("'{}' already exists", path.display()));
+            }
+            if let Some(parent) = path.parent() {
+                self.create_dir_all(parent)?;
+            }
+            paths.push(path);
+        }
+
+        let move_back = |moved: usize| {
+            for ((staged_path, _), path) in moves.iter().zip(&paths).take(moved).rev() {
+                fs::rename(path, staged_path).ok();
+            }
+        };
+
+        for (index, ((staged_path, _), path)) in moves.iter().zip(&paths).enumerate() {
+            if let Err(err) = fs::rename(staged_path, path) {
+                move_back(index);
+                return Err(io_error("move", staged_path, err));
+            }
+        }
+
+        let record_res = paths.iter().try_for_each(|path| {
+            self.record_moved_in(path)?;
+            self.remove_whiteout(path)
+        });
+        if record_res.is_err() {
+            move_back(moves.len());
+        }
+
+        record_res
+    }
+
+    // remembers the files below `path` as created, with their directories
+    fn record_moved_in(&mut self, path: &Path) -> Result<(), String> {
+        if path.is_file() {
+            let content = fs::read(path).map_err(|err| io_error("read", path, err))?;
+            let after = self.store_blob(&content)?;
+            self.record(path, None, Some(after));
+            return Ok(());
+        }
+
+        self.created_directories.push(path.to_path_buf());
+
+        let entries = fs::read_dir(path).map_err(|err| io_error("read", path, err))?;
+        for entry in entries.flatten() {
+            self.record_moved_in(&entry.path())?;
+        }
+
+        Ok(())
+    }
+
+    /// Puts back every file changed so far and removes the new directories, for
+    /// an operation that failed part way and must leave nothing behind.
+    pub fn rollback(&mut self) {
+        for change in self.changes.drain(..).rev() {
+            self.store
+                .apply_version(Path::new(&change.path), &change.before)
+                .ok();
+        }
+
+        for directory in self.created_directories.drain(..).rev() {
+            fs::remove_dir(directory).ok();
+        }
+    }
+
+    fn into_entry(self) -> Option<JournalEntry> {
+        let changes: Vec<FileChange> = self
+            .changes
+            .into_iter()
+            .filter(|change| change.before != change.after)
+            .collect();
+
+        if changes.is_empty() && self.created_directories.is_empty() {
+            return None;
+        }
+
+        let created_at = unix_millis();
+
+        Some(JournalEntry {
+            id: created_at,
+            command: self.command,
+            description: self.description,
+            created_at,
+            changes,
+            created_directories: self
+                .created_directories
+                .iter()
+                .map(|directory| directory.to_string_lossy().to_string())
+                .collect(),
+            warning: None,
+        })
+    }
+}
+
+impl FileStore {
+    /// Runs `apply` as one journal entry. The changes made are recorded even when
+    /// `apply` fails part way, so that they can still be undone, and are added to
+    /// the audit log with the error.
+    pub fn record<T>(
+        &self,
+        command: &str,
+        description: String,
+        apply: impl FnOnce(&mut Operation) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut operation = Operation {
+            store: self,
+            command: command.into(),
+            description,
+            changes: Vec::new(),
+            created_directories: Vec::new(),
+        };
+
+        let result = apply(&mut operation);
+
+        if let Some(entry) = operation.into_entry() {
+            let record = AuditRecord {
+                error: result.as_ref().err().cloned(),
+                ..AuditRecord::from_entry(&entry)
+            };
+            self.push_journal_entry(entry)?;
+
+            if let Some(warning) = self.audit_warning(record) {
+                self.warn_last_entry(warning);
+            }
+        }
+
+        result
+    }
+
+    // the files are changed and journaled already, a lost audit line must not
+    // turn that into a failure, it becomes a warning of the entry instead
+    fn audit_warning(&self, record: AuditRecord) -> Option<String> {
+        self.audit(record)
+            .err()
+            .map(|err| format!("The change is missing from the audit log: {}", err))
+    }
+
+    fn warn_last_entry(&self, warning: String) {
+        let mut state = self.journal.lock().unwrap();
+        let Some(entry) = state.entries.last_mut() else {
+            return;
+        };
+        entry.warning = Some(warning);
+
+        // the warning is still listed with the journal until the app restarts
+        if let Err(err) = self.save_journal(&state) {
+            eprintln!("{}", err);
+        }
+    }
+
+    fn save_journal(&self, state: &JournalState) -> Result<(), String> {
+        fs::create_dir_all(&self.journal_directory)
+            .map_err(|err| io_error("create", &self.journal_directory, err))?;
+
+        let data = serde_json::to_string_pretty(state)
+            .map_err(|err| format!("Failed to serialize the journal: {}", err))?;
+
+        atomic_write(&self.journal_directory.join(JOURNAL_FILE), data.as_bytes())
+    }
+
+    fn push_journal_entry(&self, mut entry: JournalEntry) -> Result<(), String> {
+        let mut state = self.journal.lock().unwrap();
+
+        if let Some(last_entry) = state.entries.last() {
+            entry.id = entry.id.max(last_entry.id + 1);
+        }
+
+        // a new operation replaces whatever could have been redone
+        let position = state.position;
+        state.entries.truncate(position);
+        state.entries.push(entry);
+
+        let overflow = state.entries.len().saturating_sub(MAX_JOURNAL_ENTRIES);
+        state.entries.drain(..overflow);
+        state.position = state.entries.len();
+
+        self.save_journal(&state)?;
+
+        if overflow > 0 {
+            self.remove_unused_blobs(&state);
+        }
+
+        Ok(())
+    }
+
+    fn remove_unused_blobs(&self, state: &JournalState) {
+        let used: std::collections::HashSet<&String> = state
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.changes)
+            .flat_map(|change| change.before.iter().chain(change.after.iter()))
+            .collect();
+
+        let blob_directory = self.journal_directory.join(BLOB_DIRECTORY);
+        let Ok(blobs) = fs::read_dir(&blob_directory) else {
+            return;
+        };
+
+        for blob in blobs.flatten() {
+            let name = blob.file_name().to_string_lossy().to_string();
+            if !used.contains(&name) {
+                fs::remove_file(blob.path()).ok();
+            }
+        }
+    }
+
+    fn apply_version(&self, path: &Path, version: &Option<String>) -> Result<(), String> {
+        match version {
+            Some(hash) => {
+                let blob_path = self.journal_directory.join(BLOB_DIRECTORY).join(hash);
+                let content =
+                    fs::read(&blob_path).map_err(|err| io_error("read", &blob_path, err))?;
+
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|err| io_error("create", parent, err))?;
+                }
+
+                self.write(path, &content)
+            }
+            None if path.is_file() => {
+                self.backup(path)?;
+                fs::remove_file(path).map_err(|err| io_error("delete", path, err))
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Applies one version of every change. When a file cannot be written the
+    // changes applied so far get their other version back, so that a failed
+    // undo or redo leaves nothing behind, like a failed operation
+    fn apply_versions<'e>(
+        &self,
+        changes: impl Iterator<Item = &'e FileChange>,
+        version: impl Fn(&FileChange) -> &Option<String>,
+        previous_version: impl Fn(&FileChange) -> &Option<String>,
+    ) -> Result<(), String> {
+        let mut applied: Vec<&FileChange> = Vec::new();
+
+        for change in changes {
+            if let Err(err) = self.apply_version(Path::new(&change.path), version(change)) {
+                self.revert_versions(&applied, previous_version);
+                return Err(err);
+            }
+            applied.push(change);
+        }
+
+        Ok(())
+    }
+
+    fn revert_versions(
+        &self,
+        applied: &[&FileChange],
+        previous_version: impl Fn(&FileChange) -> &Option<String>,
+    ) {
+        for change in applied.iter().rev() {
+            self.apply_version(Path::new(&change.path), previous_version(change))
+                .ok();
+        }
+    }
+
+    // files edited since the operation must not be overwritten silently
+    fn check_versions(
+        entry: &JournalEntry,
+        expected: impl Fn(&FileChange) -> &Option<String>,
+    ) -> Result<(), String> {
+        for change in &entry.changes {
+            if file_hash(Path::new(&change.path))? != *expected(change) {
+                return Err(format!(
+                    "'{}' was changed after '{}', it cannot be reverted automatically",
+                    change.path, entry.description
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reverts the last recorded operation.
+    pub fn undo(&self) -> Result<JournalEntry, String> {
+        let mut state = self.journal.lock().unwrap();
+
+        if state.position == 0 {
+            return Err("There is nothing to undo".into());
+        }
+
+        let mut entry = state.entries[state.position - 1].clone();
+        Self::check_versions(&entry, |change| &change.after)?;
+
+        self.apply_versions(
+            entry.changes.iter().rev(),
+            |change| &change.before,
+            |change| &change.after,
+        )?;
+
+        state.position -= 1;
+        if let Err(err) = self.save_journal(&state) {
+            let applied: Vec<&FileChange> = entry.changes.iter().rev().collect();
+            self.revert_versions(&applied, |change| &change.after);
+            state.position += 1;
+            return Err(err);
+        }
+
+        // directories left empty by the undo, deepest first
+        for directory in entry.created_directories.iter().rev() {
+            fs::remove_dir(directory).ok();
+        }
+
+        entry.warning = self.audit_warning(AuditRecord {
+            changes: entry
+                .changes
+                .iter()
+                .rev()
+                .map(|change| FileChange {
+                    path: change.path.clone(),
+                    before: change.after.clone(),
+                    after: change.before.clone(),
+                })
+                .collect(),
+            ..AuditRecord::new("undo", format!("Undo '{}'", entry.description))
+        });
+
+        Ok(entry)
+    }
+
+    /// Applies the last undone operation again.
+    pub fn redo(&self) -> Result<JournalEntry, String> {
+        let mut state = self.journal.lock().unwrap();
+
+        if state.position == state.entries.len() {
+            return Err("There is nothing to redo".into());
+        }
+
+        let mut entry = state.entries[state.position].clone();
+        Self::check_versions(&entry, |change| &change.before)?;
+
+        for directory in &entry.created_directories {
+            fs::create_dir_all(directory)
+                .map_err(|err| io_error("create", Path::new(directory), err))?;
+        }
+
+        let mut redo_res = self.apply_versions(
+            entry.changes.iter(),
+            |change| &change.after,
+            |change| &change.before,
+        );
+        if redo_res.is_ok() {
+            state.position += 1;
+            redo_res = self.save_journal(&state);
+
+            if redo_res.is_err() {
+                let applied: Vec<&FileChange> = entry.changes.iter().collect();
+                self.revert_versions(&applied, |change| &change.before);
+                state.position -= 1;
+            }
+        }
+
+        if let Err(err) = redo_res {
+            for directory in entry.created_directories.iter().rev() {
+                fs::remove_dir(directory).ok();
+            }
+            return Err(err);
+        }
+
+        entry.warning = self.audit_warning(AuditRecord {
+            changes: entry.changes.clone(),
+            created_directories: entry.created_directories.clone(),
+            ..AuditRecord::new("redo", format!("Redo '{}'", entry.description))
+        });
+
+        Ok(entry)
+    }
+
+    pub fn journal_state(&self) -> JournalState {
+        self.journal.lock().unwrap().clone()
+    }
+}
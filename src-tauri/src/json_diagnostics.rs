@@ -0,0 +1,545 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::commons::push_analysis_message;
+use crate::configuration_loader::{AnalysisMessageDetails, ConfigDetails, ErrType};
+use crate::overlay::read_path;
+
+const BOM: char = '\u{FEFF}';
+
+/// A problem found in a JSON file, with a 1-based line/column span.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JsonDiagnostic {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub r#type: ErrType,
+    /// Whether the problem is removed when the file is repaired.
+    pub fixable: bool,
+}
+
+impl JsonDiagnostic {
+    pub fn describe(&self) -> String {
+        format!(
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonDiagnosis {
+    pub diagnostics: Vec<JsonDiagnostic>,
+    /// The text with every fixable problem removed. `None` when a problem could
+    /// not be fixed, as the rest of the file was not understood.
+    pub repaired: Option<String>,
+}
+
+impl JsonDiagnosis {
+    /// Problems that keep a strict parser from reading the file.
+    pub fn errors(&self) -> impl Iterator<Item = &JsonDiagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| matches!(diagnostic.r#type, ErrType::Error))
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    line_starts: Vec<usize>,
+    diagnostics: Vec<JsonDiagnostic>,
+    // byte ranges removed by the repair
+    removals: Vec<(usize, usize)>,
+}
+
+// Aborts parsing, the diagnostic has been recorded
+struct Fatal;
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        Self {
+            text,
+            pos: 0,
+            line_starts,
+            diagnostics: Vec::new(),
+            removals: Vec::new(),
+        }
+    }
+
+    fn location(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let column = self.text[self.line_starts[line]..offset].chars().count();
+        (line + 1, column + 1)
+    }
+
+    fn report(
+        &mut self,
+        start: usize,
+        end: usize,
+        message: String,
+        r#type: ErrType,
+        fixable: bool,
+    ) {
+        let (line, column) = self.location(start);
+        let (end_line, end_column) = self.location(end);
+
+        self.diagnostics.push(JsonDiagnostic {
+            message,
+            line,
+            column,
+            end_line,
+            end_column,
+            r#type,
+            fixable,
+        });
+    }
+
+    fn fatal(&mut self, message: String) -> Fatal {
+        let end = self.pos + self.peek().map(char::len_utf8).unwrap_or(0);
+        self.report(self.pos, end, message, ErrType::Error, false);
+        Fatal
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn unexpected(&mut self, expected: &str) -> Fatal {
+        match self.peek() {
+            Some(found) => self.fatal(format!("Expected {}, found '{}'", expected, found)),
+            None => self.fatal(format!("Expected {}, found the end of the file", expected)),
+        }
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), Fatal> {
+        loop {
+            let trimmed = self.rest().trim_start_matches([' ', '\t', '\n', '\r']);
+            self.pos = self.text.len() - trimmed.len();
+
+            if self.rest().starts_with("//") {
+                let start = self.pos;
+                let end = self
+                    .rest()
+                    .find('\n')
+                    .map_or(self.text.len(), |i| start + i);
+                self.pos = end;
+                self.remove_comment(start, end);
+            } else if self.rest().starts_with("/*") {
+                let start = self.pos;
+                let Some(length) = self.rest()[2..].find("*/") else {
+                    return Err(self.fatal("Unterminated block comment".into()));
+                };
+                let end = start + 2 + length + 2;
+                self.pos = end;
+                self.remove_comment(start, end);
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn remove_comment(&mut self, start: usize, end: usize) {
+        self.report(
+            start,
+            end,
+            "Comments are not allowed in JSON".into(),
+            ErrType::Error,
+            true,
+        );
+
+        // a comment on its own line takes the line with it
+        let line_start = self.text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let own_line =
+            self.text[line_start..start].trim().is_empty() && self.text[end..].starts_with('\n');
+
+        if own_line {
+            self.removals.push((line_start, end + 1));
+        } else {
+            self.removals.push((start, end));
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<(), Fatal> {
+        self.skip_trivia()?;
+
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => self.parse_string().map(|_| ()),
+            Some('-' | '0'..='9') => self.parse_number(),
+            Some(_) => {
+                for literal in ["true", "false", "null"] {
+                    if self.rest().starts_with(literal) {
+                        self.pos += literal.len();
+                        return Ok(());
+                    }
+                }
+                Err(self.unexpected("a value"))
+            }
+            None => Err(self.unexpected("a value")),
+        }
+    }
+
+    /// Parses the separator after an element. Returns whether more elements follow.
+    fn parse_separator(&mut self, close: char) -> Result<bool, Fatal> {
+        self.skip_trivia()?;
+
+        match self.peek() {
+            Some(',') => {
+                let comma = self.pos;
+                self.pos += 1;
+                self.skip_trivia()?;
+
+                if self.peek() == Some(close) {
+                    self.report(
+                        comma,
+                        comma + 1,
+                        format!("Trailing comma before '{}'", close),
+                        ErrType::Error,
+                        true,
+                    );
+                    self.removals.push((comma, comma + 1));
+                    self.pos += 1;
+                    Ok(false)
+                } else {
+                    Ok(true)
+                }
+            }
+            Some(found) if found == close => {
+                self.pos += 1;
+                Ok(false)
+            }
+            _ => Err(self.unexpected(&format!("',' or '{}'", close))),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<(), Fatal> {
+        self.pos += 1;
+        self.skip_trivia()?;
+
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(());
+        }
+
+        let mut keys: HashMap<String, usize> = HashMap::new();
+
+        loop {
+            self.skip_trivia()?;
+            if self.peek() != Some('"') {
+                return Err(self.unexpected("a key in double quotes"));
+            }
+
+            let key_start = self.pos;
+            let key = self.parse_string()?;
+
+            if let Some(first) = keys.get(&key).copied() {
+                let (first_line, first_column) = self.location(first);
+                self.report(
+                    key_start,
+                    self.pos,
+                    format!(
+                        "Duplicate key '{}', first defined at line {}, column {}. Only the last value is used",
+                        key, first_line, first_column
+                    ),
+                    ErrType::Warning,
+                    false,
+                );
+            }
+            keys.insert(key, key_start);
+
+            self.skip_trivia()?;
+            if self.peek() != Some(':') {
+                return Err(self.unexpected("':'"));
+            }
+            self.pos += 1;
+
+            self.parse_value()?;
+
+            if !self.parse_separator('}')? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<(), Fatal> {
+        self.pos += 1;
+        self.skip_trivia()?;
+
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(());
+        }
+
+        loop {
+            self.parse_value()?;
+
+            if !self.parse_separator(']')? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, Fatal> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut value = String::new();
+
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(escaped @ ('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't')) => {
+                            value.push(escaped);
+                            self.pos += 1;
+                        }
+                        Some('u')
+                            if self.rest().len() >= 5
+                                && self.rest()[1..5].chars().all(|c| c.is_ascii_hexdigit()) =>
+                        {
+                            value.push_str(&self.rest()[..5]);
+                            self.pos += 5;
+                        }
+                        _ => return Err(self.fatal("Invalid escape sequence in string".into())),
+                    }
+                }
+                Some(c) if c == '\n' || c == '\r' || c.is_control() => {
+                    self.pos = start;
+                    return Err(self.fatal("Unterminated string".into()));
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.pos += c.len_utf8();
+                }
+                None => {
+                    self.pos = start;
+                    return Err(self.fatal("Unterminated string".into()));
+                }
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<(), Fatal> {
+        let start = self.pos;
+        let digits = |text: &str| text.chars().take_while(char::is_ascii_digit).count();
+
+        if self.rest().starts_with('-') {
+            self.pos += 1;
+        }
+
+        let integer = digits(self.rest());
+        if integer == 0 || (integer > 1 && self.rest().starts_with('0')) {
+            self.pos = start;
+            return Err(self.fatal("Invalid number".into()));
+        }
+        self.pos += integer;
+
+        if self.rest().starts_with('.') {
+            let fraction = digits(&self.rest()[1..]);
+            if fraction == 0 {
+                self.pos = start;
+                return Err(self.fatal("Invalid number".into()));
+            }
+            self.pos += 1 + fraction;
+        }
+
+        if self.rest().starts_with(['e', 'E']) {
+            self.pos += 1;
+            if self.rest().starts_with(['+', '-']) {
+                self.pos += 1;
+            }
+            let exponent = digits(self.rest());
+            if exponent == 0 {
+                self.pos = start;
+                return Err(self.fatal("Invalid number".into()));
+            }
+            self.pos += exponent;
+        }
+
+        Ok(())
+    }
+
+    fn parse_document(&mut self) -> Result<(), Fatal> {
+        if self.text.starts_with(BOM) {
+            self.report(
+                0,
+                BOM.len_utf8(),
+                "The file starts with a byte order mark".into(),
+                ErrType::Error,
+                true,
+            );
+            self.removals.push((0, BOM.len_utf8()));
+            self.pos = BOM.len_utf8();
+        }
+
+        self.parse_value()?;
+        self.skip_trivia()?;
+
+        if self.pos < self.text.len() {
+            return Err(self.fatal("Unexpected content after the end of the JSON value".into()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses `text` leniently, reporting syntax errors with their position,
+/// duplicate keys, and the hand-editing mistakes it can fix: trailing commas,
+/// comments and a byte order mark.
+pub fn diagnose_json(text: &str) -> JsonDiagnosis {
+    let mut parser = Parser::new(text);
+    let parsed = parser.parse_document();
+
+    let repaired = parsed.ok().map(|_| {
+        parser.removals.sort();
+
+        let mut repaired = String::with_capacity(text.len());
+        let mut copied_until = 0;
+        for (start, end) in &parser.removals {
+            if *start >= copied_until {
+                repaired.push_str(&text[copied_until..*start]);
+                copied_until = *end;
+            }
+        }
+        repaired.push_str(&text[copied_until..]);
+        repaired
+    });
+
+    JsonDiagnosis {
+        diagnostics: parser.diagnostics,
+        repaired,
+    }
+}
+
+/// Reports the JSON problems of the file at `path` under `key`, prefixed by `label`.
+pub fn analyse_json_file(
+    path: &str,
+    label: &str,
+    key: &str,
+    config_details: &ConfigDetails,
+) -> HashMap<String, Vec<AnalysisMessageDetails>> {
+    let mut analysis_result: HashMap<String, Vec<AnalysisMessageDetails>> = HashMap::new();
+
+    let Ok(text) = fs::read_to_string(read_path(Path::new(path))) else {
+        return analysis_result;
+    };
+
+    for diagnostic in diagnose_json(&text).diagnostics {
+        push_analysis_message(
+            &mut analysis_result,
+            key,
+            format!(
+                "{} {}{}",
+                label,
+                diagnostic.describe(),
+                if diagnostic.fixable {
+                    " (can be repaired automatically)"
+                } else {
+                    ""
+                }
+            ),
+            diagnostic.r#type,
+            config_details,
+        );
+    }
+
+    analysis_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict(text: &str) -> serde_json::Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn valid_json_has_no_diagnostics() {
+        let text = "{\n  \"name\": \"PLA\",\n  \"values\": [1, 2.5e3, true, null]\n}";
+        let diagnosis = diagnose_json(text);
+
+        assert!(diagnosis.diagnostics.is_empty());
+        assert_eq!(diagnosis.repaired.as_deref(), Some(text));
+    }
+
+    #[test]
+    fn trailing_commas_are_located_and_repaired() {
+        let text = "{\n  \"a\": [1, 2,],\n  \"b\": 3,\n}";
+        let diagnosis = diagnose_json(text);
+
+        let positions: Vec<(usize, usize, bool)> = diagnosis
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.column, diagnostic.fixable))
+            .collect();
+        assert_eq!(positions, vec![(2, 13, true), (3, 9, true)]);
+        assert!(diagnosis.diagnostics[0].message.contains("Trailing comma"));
+        assert_eq!(
+            strict(&diagnosis.repaired.unwrap()),
+            serde_json::json!({"a": [1, 2], "b": 3})
+        );
+    }
+
+    #[test]
+    fn comments_and_bom_are_removed() {
+        let text = "\u{feff}{\n  // line comment\n  \"a\": /* inline */ 1\n}";
+        let diagnosis = diagnose_json(text);
+
+        assert_eq!(diagnosis.errors().count(), 3);
+        assert!(diagnosis
+            .diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.fixable));
+        assert_eq!(
+            strict(&diagnosis.repaired.unwrap()),
+            serde_json::json!({"a": 1})
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_are_reported_but_not_fixed() {
+        let diagnosis = diagnose_json("{\"a\": 1, \"a\": 2}");
+
+        assert_eq!(diagnosis.diagnostics.len(), 1);
+        assert!(diagnosis.diagnostics[0].message.contains("Duplicate key"));
+        assert!(!diagnosis.diagnostics[0].fixable);
+        assert_eq!(diagnosis.errors().count(), 0);
+    }
+
+    #[test]
+    fn broken_documents_cannot_be_repaired() {
+        for text in [
+            "{\"a\": }",
+            "{\"a\": 1",
+            "[1 2]",
+            "{\"a\": \"unterminated}",
+            "",
+        ] {
+            let diagnosis = diagnose_json(text);
+
+            assert!(diagnosis.errors().count() > 0, "{:?}", text);
+            assert!(diagnosis.repaired.is_none(), "{:?}", text);
+        }
+    }
+}
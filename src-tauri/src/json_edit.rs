@@ -0,0 +1,391 @@
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::ops::Range;
+
+const DEFAULT_INDENT: &str = "    ";
+
+/// One member of the top level object, as ranges into the original text.
+/// `lead` is the whitespace before the key, `trail` the one after the value,
+/// up to the comma or the closing brace.
+struct Member {
+    key: String,
+    lead: Range<usize>,
+    key_to_value: Range<usize>,
+    value: Range<usize>,
+    trail: Range<usize>,
+}
+
+struct ObjectLayout {
+    open: usize,
+    close: usize,
+    members: Vec<Member>,
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn skip_whitespace(&mut self) {
+        while matches!(
+            self.bytes.get(self.position),
+            Some(b' ' | b'\t' | b'\r' | b'\n')
+        ) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        (self.bytes.get(self.position) == Some(&byte)).then(|| self.position += 1)
+    }
+
+    fn skip_string(&mut self) -> Option<()> {
+        self.expect(b'"')?;
+        loop {
+            match self.bytes.get(self.position)? {
+                b'\\' => self.position += 2,
+                b'"' => {
+                    self.position += 1;
+                    return Some(());
+                }
+                _ => self.position += 1,
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        match self.bytes.get(self.position)? {
+            b'"' => self.skip_string(),
+            b'{' | b'[' => {
+                let mut depth = 0;
+                loop {
+                    match self.bytes.get(self.position)? {
+                        b'"' => {
+                            self.skip_string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+                    self.position += 1;
+                    if depth == 0 {
+                        return Some(());
+                    }
+                }
+            }
+            _ => {
+                while !matches!(
+                    self.bytes.get(self.position),
+                    None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\r' | b'\n')
+                ) {
+                    self.position += 1;
+                }
+                Some(())
+            }
+        }
+    }
+}
+
+// Only called on text that parsed as strict JSON, so the scanner does not
+// have to validate anything.
+fn scan_object(text: &str) -> Option<ObjectLayout> {
+    let mut scanner = Scanner {
+        bytes: text.as_bytes(),
+        position: if text.starts_with('\u{feff}') { 3 } else { 0 },
+    };
+
+    scanner.skip_whitespace();
+    let open = scanner.position;
+    scanner.expect(b'{')?;
+
+    let mut members = Vec::new();
+    loop {
+        let lead_start = scanner.position;
+        scanner.skip_whitespace();
+
+        if scanner.expect(b'}').is_some() {
+            return Some(ObjectLayout {
+                open,
+                close: scanner.position - 1,
+                members,
+            });
+        }
+
+        let key_start = scanner.position;
+        scanner.skip_string()?;
+        let key: String = serde_json::from_str(&text[key_start..scanner.position]).ok()?;
+
+        scanner.skip_whitespace();
+        scanner.expect(b':')?;
+        scanner.skip_whitespace();
+
+        let value_start = scanner.position;
+        scanner.skip_value()?;
+        let value_end = scanner.position;
+        scanner.skip_whitespace();
+
+        members.push(Member {
+            key,
+            lead: lead_start..key_start,
+            key_to_value: key_start..value_start,
+            value: value_start..value_end,
+            trail: value_end..scanner.position,
+        });
+
+        if scanner.expect(b',').is_none() && scanner.bytes.get(scanner.position) != Some(&b'}') {
+            return None;
+        }
+    }
+}
+
+/// How the original document is laid out, used for the values written anew.
+struct Style {
+    newline: &'static str,
+    indent: String,
+    multiline: bool,
+}
+
+impl Style {
+    fn detect(text: &str, layout: &ObjectLayout) -> Self {
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+
+        let Some(first_member) = layout.members.first() else {
+            return Style {
+                newline,
+                indent: DEFAULT_INDENT.into(),
+                multiline: true,
+            };
+        };
+
+        let lead = &text[first_member.lead.clone()];
+        match lead.rfind('\n') {
+            Some(line_start) => Style {
+                newline,
+                indent: lead[line_start + 1..].into(),
+                multiline: true,
+            },
+            None => Style {
+                newline,
+                indent: String::new(),
+                multiline: false,
+            },
+        }
+    }
+
+    fn render(&self, value: &Value, depth: usize, multiline: bool) -> String {
+        let (open, close, items): (&str, &str, Vec<String>) = match value {
+            Value::Array(array) if !array.is_empty() => (
+                "[",
+                "]",
+                array
+                    .iter()
+                    .map(|item| self.render(item, depth + 1, multiline))
+                    .collect(),
+            ),
+            Value::Object(object) if !object.is_empty() => (
+                "{",
+                "}",
+                object
+                    .iter()
+                    .map(|(key, item)| {
+                        format!(
+                            "{}: {}",
+                            Value::String(key.clone()),
+                            self.render(item, depth + 1, multiline)
+                        )
+                    })
+                    .collect(),
+            ),
+            _ => return value.to_string(),
+        };
+
+        if multiline {
+            let inner_indent = format!("{}{}", self.newline, self.indent.repeat(depth + 1));
+            let outer_indent = format!("{}{}", self.newline, self.indent.repeat(depth));
+            format!(
+                "{}{}{}{}{}",
+                open,
+                inner_indent,
+                items.join(&format!(",{}", inner_indent)),
+                outer_indent,
+                close
+            )
+        } else {
+            format!("{}{}{}", open, items.join(", "), close)
+        }
+    }
+}
+
+/// Applies `updated` to the JSON object in `original` while keeping its layout:
+/// unchanged members stay as they are, changed values are replaced in place,
+/// removed keys are dropped and new keys are inserted after the key preceding
+/// them in `updated`. `None` when `original` is not a strict JSON object.
+pub fn edit_json(original: &str, updated: &Map<String, Value>) -> Option<String> {
+    serde_json::from_str::<Map<String, Value>>(original.trim_start_matches('\u{feff}')).ok()?;
+    let layout = scan_object(original)?;
+    let style = Style::detect(original, &layout);
+
+    let key_separator = layout
+        .members
+        .first()
+        .map(|member| {
+            let key_to_value = &original[member.key_to_value.clone()];
+            key_to_value[key_to_value.rfind(':').unwrap_or(0)..].to_string()
+        })
+        .unwrap_or_else(|| ": ".into());
+    let new_lead = match layout.members.last() {
+        Some(member) => original[member.lead.clone()].to_string(),
+        None if style.multiline => format!("{}{}", style.newline, style.indent),
+        None => " ".into(),
+    };
+    let closing = match layout.members.last() {
+        Some(member) => original[member.trail.clone()].to_string(),
+        None if style.multiline => style.newline.into(),
+        None => " ".into(),
+    };
+    let last_index = layout.members.len().saturating_sub(1);
+
+    let mut written_keys: HashSet<&str> = HashSet::new();
+    let mut members: Vec<(&str, String)> = Vec::new();
+
+    for (index, member) in layout.members.iter().enumerate() {
+        // a duplicate key keeps its first position
+        let Some(value) = updated.get(&member.key) else {
+            continue;
+        };
+        if !written_keys.insert(&member.key) {
+            continue;
+        }
+
+        let original_value = &original[member.value.clone()];
+        let value_text = match serde_json::from_str::<Value>(original_value) {
+            Ok(parsed) if parsed == *value => original_value.to_string(),
+            _ => style.render(value, 1, original_value.contains('\n')),
+        };
+        let trail = if index == last_index {
+            ""
+        } else {
+            &original[member.trail.clone()]
+        };
+
+        members.push((
+            &member.key,
+            format!(
+                "{}{}{}{}",
+                &original[member.lead.clone()],
+                &original[member.key_to_value.clone()],
+                value_text,
+                trail
+            ),
+        ));
+    }
+
+    let mut previous_key: Option<&str> = None;
+    for (key, value) in updated {
+        if written_keys.insert(key) {
+            let member = format!(
+                "{}{}{}{}",
+                new_lead,
+                Value::String(key.clone()),
+                key_separator,
+                style.render(value, 1, style.multiline)
+            );
+            let position = match previous_key {
+                Some(previous_key) => members
+                    .iter()
+                    .position(|(key, _)| *key == previous_key)
+                    .map_or(members.len(), |index| index + 1),
+                None => 0,
+            };
+            members.insert(position, (key, member));
+        }
+        previous_key = Some(key);
+    }
+
+    let members: Vec<String> = members.into_iter().map(|(_, member)| member).collect();
+
+    Some(format!(
+        "{}{}{}{}",
+        &original[..=layout.open],
+        members.join(","),
+        if members.is_empty() { "" } else { &closing },
+        &original[layout.close..]
+    ))
+}
+
+/// The text to write for `updated` over a file that contained `original`: the
+/// original layout is kept where possible, new files are pretty printed.
+pub fn edited_json(original: Option<&str>, updated: &Value) -> Result<String, String> {
+    if let (Some(original), Value::Object(updated)) = (original, updated) {
+        if let Some(edited) = edit_json(original, updated) {
+            return Ok(edited);
+        }
+    }
+
+    serde_json::to_string_pretty(updated)
+        .map_err(|err| format!("Failed to serialize JSON: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn edit(original: &str, updated: Value) -> Option<String> {
+        edit_json(original, updated.as_object().unwrap())
+    }
+
+    #[test]
+    fn unchanged_object_keeps_its_text() {
+        let original = "{\n\t\"b\" : 1,\n\t\"a\": [1,2]\n}\n";
+
+        assert_eq!(
+            edit(original, json!({"a": [1, 2], "b": 1})).as_deref(),
+            Some(original)
+        );
+    }
+
+    #[test]
+    fn changed_value_is_replaced_in_place() {
+        let original = "{\n  \"name\": \"PLA\",\n  \"temp\": [\"200\"]\n}";
+
+        assert_eq!(
+            edit(original, json!({"name": "PLA", "temp": ["210"]})).unwrap(),
+            "{\n  \"name\": \"PLA\",\n  \"temp\": [\"210\"]\n}"
+        );
+    }
+
+    #[test]
+    fn removed_and_added_keys() {
+        let original = "{\n  \"a\": 1,\n  \"b\": 2,\n  \"c\": 3\n}";
+        let edited = edit(original, json!({"a": 1, "new": true, "c": 3})).unwrap();
+
+        assert_eq!(edited, "{\n  \"a\": 1,\n  \"new\": true,\n  \"c\": 3\n}");
+    }
+
+    #[test]
+    fn removing_the_last_key_drops_its_comma() {
+        let original = "{\n  \"a\": 1,\n  \"b\": 2\n}";
+
+        assert_eq!(edit(original, json!({"a": 1})).unwrap(), "{\n  \"a\": 1\n}");
+    }
+
+    #[test]
+    fn lenient_or_non_object_originals_are_refused() {
+        assert!(edit("{\"a\": 1,}", json!({"a": 2})).is_none());
+        assert!(edit("[1, 2]", json!({"a": 2})).is_none());
+    }
+
+    #[test]
+    fn new_files_are_pretty_printed() {
+        let edited = edited_json(None, &json!({"a": 1})).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&edited).unwrap(),
+            json!({"a": 1})
+        );
+        assert!(edited.contains('\n'));
+    }
+}
@@ -1,31 +1,77 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod audit;
+mod bed_geometry;
 mod commons;
+mod config_inheritance;
+mod config_keys;
+mod config_overrides;
 mod configuration_loader;
+mod filament_rules;
+mod file_store;
+mod journal;
+mod json_diagnostics;
+mod json_edit;
+mod name_references;
+mod option_registry;
+mod overlay;
+mod preset_deletion;
+mod preset_rename;
+mod trash;
+mod vendor_bundle;
+mod vendor_copy;
+mod vendor_duplication;
+mod vendor_merge;
+mod vendor_plan;
+mod vendor_split;
+mod workspace;
+use audit::{AuditQuery, AuditRecord};
 use commons::{
-    add_new_prop_to_file, analyse_installed_filament_config, analyse_installed_printer_config,
-    analyse_installed_process_config, analyse_vendor_config, check_collision_in_config_file,
-    check_directory, check_file, check_in_set, copy_file, create_directory, delete_file,
-    duplicate_vendor, find_possible_values, populate_key_set, rename_config, rename_file,
-    show_in_folder, write_to_file,
+    add_new_prop_to_file, analyse_generic_config, analyse_installed_filament_config,
+    analyse_installed_printer_config, analyse_installed_process_config, analyse_vendor_config,
+    analyse_vendor_directory, check_collision_in_config_file, check_directory, check_file,
+    check_in_set, copy_configs_to_vendor, copy_file, create_directory, delete_file, delete_preset,
+    diagnose_json_file, duplicate_vendor, execute_vendor_plan, find_possible_values,
+    get_file_token, get_journal, import_vendor_bundle, list_backups, list_trash,
+    materialize_overlay, merge_vendors, minimize_config, plan_copy_configs_to_vendor,
+    plan_delete_preset, plan_duplicate_vendor, plan_merge_vendors, plan_rename_preset,
+    plan_rename_vendor, plan_split_vendor, populate_key_set, preset_delete_impact, purge_trash,
+    query_audit_log, redo, rename_config, rename_file, rename_preset, rename_vendor,
+    repair_json_file, restore_backup, restore_from_trash, set_overlay_directory,
+    set_workspace_roots, show_in_folder, split_vendor, undo, write_to_file,
 };
 use configuration_loader::{
     load_all_filament_presets, load_all_printer_model_presets, load_all_printer_presets,
     load_all_process_presets, load_all_system_vendor_profiles,
     load_all_user_filaments_profiles_in_dir, load_all_user_printer_profiles_in_dir,
-    load_all_user_process_profiles_in_dir, load_generic_preset, load_printer_model_preset,
-    load_printer_variant_preset, AnalysisMessageDetails, ConfigDetails, ErrWan, FilamentJsonSchema,
-    GenericJsonSchema, MinFilamentJsonSchema, MinPrinterModelJsonSchema,
-    MinPrinterVariantJsonSchema, MinProcessJsonSchema, PrinterModelJsonSchema,
-    PrinterVariantJsonSchema, ProcessJsonSchema, VendorJsonSchema,
+    load_all_user_process_profiles_in_dir, load_generic_preset, load_generic_preset_with_token,
+    load_printer_model_preset, load_printer_variant_preset, AnalysisMessageDetails, ConfigDetails,
+    ErrWan, FilamentJsonSchema, GenericJsonSchema, MinFilamentJsonSchema,
+    MinPrinterModelJsonSchema, MinPrinterVariantJsonSchema, MinProcessJsonSchema,
+    PrinterModelJsonSchema, PrinterVariantJsonSchema, ProcessJsonSchema, TokenedPreset,
+    VendorJsonSchema,
 };
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use filament_rules::FilamentRuleSettings;
+use file_store::{atomic_write, file_hash, BackupEntry, FileStore, WriteConflict, WriteError};
+use journal::{FileChange, JournalEntry, JournalState};
+use json_diagnostics::JsonDiagnostic;
+use overlay::OverlaySettings;
+use preset_deletion::{DeleteImpact, DeleteStrategy, PresetDependent};
+use preset_rename::{PresetEdit, RenamedPreset};
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
 use tauri::api::dialog::blocking::FileDialogBuilder;
 use tauri::async_runtime::spawn_blocking;
+use tauri::Manager;
+use trash::{TrashEntry, VendorListEntry};
 use ts_rs::TS;
+use vendor_bundle::BundleImport;
+use vendor_merge::CollisionStrategy;
+use vendor_plan::{
+    PlannedAction, PlannedFile, PresetRename, ReferenceChange, VendorPlan, VendorProblem,
+};
+use vendor_split::SharedBaseStrategy;
 use zip::write::SimpleFileOptions;
 
 use crate::commons::KeySet;
@@ -44,7 +90,11 @@ fn greet2(name: &str) -> String {
 }
 
 #[tauri::command]
-async fn save_and_zip_json(data: serde_json::Value, file_name: String) -> Result<bool, String> {
+async fn save_and_zip_json(
+    data: serde_json::Value,
+    file_name: String,
+    store: tauri::State<'_, Arc<FileStore>>,
+) -> Result<bool, String> {
     // Run the blocking folder picker in a separate thread
     let save_path: Option<PathBuf> = spawn_blocking(move || {
         FileDialogBuilder::new()
@@ -59,14 +109,17 @@ async fn save_and_zip_json(data: serde_json::Value, file_name: String) -> Result
     };
 
     let zip_path = base_path.join(file_name);
+    let audited_path = zip_path.clone();
+    let before = file_hash(&zip_path)?;
 
     // Now spawn the blocking zip logic
     let data_clone = data.clone(); // clone to move into thread
     spawn_blocking(move || {
         let json_str = serde_json::to_string_pretty(&data_clone).map_err(|e| e.to_string())?;
 
-        let file = File::create(&zip_path).map_err(|e| e.to_string())?;
-        let mut zip = zip::ZipWriter::new(file);
+        // built in memory and written in one go, a failed export leaves no
+        // truncated archive behind
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
 
         let file_name = data_clone
             .get("name")
@@ -80,12 +133,15 @@ async fn save_and_zip_json(data: serde_json::Value, file_name: String) -> Result
         zip.write_all(json_str.as_bytes())
             .map_err(|e| e.to_string())?;
 
-        zip.finish().map_err(|e| e.to_string())?;
+        let zip_content = zip.finish().map_err(|e| e.to_string())?.into_inner();
+        atomic_write(&zip_path, &zip_content)?;
 
         Ok(true)
     })
     .await
-    .unwrap_or_else(|e| Err(format!("Task error: {e}")))
+    .unwrap_or_else(|e| Err(format!("Task error: {e}")))?;
+
+    audit_export(&store, "save_and_zip_json", &audited_path, before)
 }
 
 #[tauri::command]
@@ -94,17 +150,21 @@ async fn save_and_zip_json_bundle(
     file_names: Vec<String>,
     folder: String,
     zip_name: String,
+    store: tauri::State<'_, Arc<FileStore>>,
 ) -> Result<bool, String> {
     if data.len() != file_names.len() {
         return Err("Data and file_names must be of the same length.".to_string());
     }
 
-    let base_path = PathBuf::from(folder);
+    let base_path = store.workspace.check_path(&PathBuf::from(folder))?;
     let zip_path = base_path.join(zip_name);
+    let audited_path = zip_path.clone();
+    let before = file_hash(&zip_path)?;
 
     spawn_blocking(move || {
-        let file = File::create(&zip_path).map_err(|e| e.to_string())?;
-        let mut zip = zip::ZipWriter::new(file);
+        // built in memory and written in one go, a failed export leaves no
+        // truncated archive behind
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
         let options = SimpleFileOptions::default();
 
         for (entry, file_name) in data.into_iter().zip(file_names.into_iter()) {
@@ -124,16 +184,75 @@ async fn save_and_zip_json_bundle(
                 .map_err(|e| format!("Zip write error: {e}"))?;
         }
 
-        zip.finish().map_err(|e| format!("Zip finish error: {e}"))?;
+        let zip_content = zip
+            .finish()
+            .map_err(|e| format!("Zip finish error: {e}"))?
+            .into_inner();
+        atomic_write(&zip_path, &zip_content)?;
 
         Ok(true)
     })
     .await
-    .unwrap_or_else(|e| Err(format!("Task error: {e}")))
+    .unwrap_or_else(|e| Err(format!("Task error: {e}")))?;
+
+    audit_export(&store, "save_and_zip_json_bundle", &audited_path, before)
 }
 
+/// Archives the vendor config at `path` with every config it lists and the
+/// assets of its printer models, returning the archived files.
 #[tauri::command]
-async fn pick_folder() -> Result<PathBuf, String> {
+async fn export_vendor_bundle(
+    path: String,
+    zip_path: String,
+    store: tauri::State<'_, Arc<FileStore>>,
+) -> Result<Vec<String>, Vec<VendorProblem>> {
+    let checked_path = |path: &str| {
+        store
+            .workspace
+            .check_path(Path::new(path))
+            .map_err(|err| vec![VendorProblem::new(Path::new(path), err)])
+    };
+    let path = checked_path(&path)?;
+    let zip_path = checked_path(&zip_path)?;
+    let audited_path = zip_path.clone();
+    let before = file_hash(&zip_path).map_err(|err| vec![VendorProblem::new(&zip_path, err)])?;
+
+    let files = spawn_blocking(move || vendor_bundle::write_bundle(&path, &zip_path))
+        .await
+        .unwrap_or_else(|e| {
+            Err(vec![VendorProblem::new(
+                &audited_path,
+                format!("Task error: {e}"),
+            )])
+        })?;
+
+    audit_export(&store, "export_vendor_bundle", &audited_path, before)
+        .map_err(|err| vec![VendorProblem::new(&audited_path, err)])?;
+
+    Ok(files)
+}
+
+// exports are written outside of the journal, they are only logged
+fn audit_export(
+    store: &FileStore,
+    command: &str,
+    zip_path: &Path,
+    before: Option<String>,
+) -> Result<bool, String> {
+    store.audit(AuditRecord {
+        changes: vec![FileChange {
+            path: zip_path.to_string_lossy().to_string(),
+            before,
+            after: file_hash(zip_path)?,
+        }],
+        ..AuditRecord::new(command, format!("Export '{}'", zip_path.display()))
+    })?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+async fn pick_folder(store: tauri::State<'_, Arc<FileStore>>) -> Result<PathBuf, String> {
     // Run the blocking folder picker in a separate thread
     let save_path: Option<PathBuf> = spawn_blocking(move || {
         FileDialogBuilder::new()
@@ -147,6 +266,9 @@ async fn pick_folder() -> Result<PathBuf, String> {
         return Err("No path selected".into());
     };
 
+    // the user picked it, so exports may be written there
+    store.workspace.add_export_root(&base_path)?;
+
     Ok(base_path)
 }
 
@@ -167,12 +289,49 @@ fn main() {
         ErrWan::export_all_to(type_export_directory).unwrap();
         ConfigDetails::export_all_to(type_export_directory).unwrap();
         AnalysisMessageDetails::export_all_to(type_export_directory).unwrap();
+        FilamentRuleSettings::export_all_to(type_export_directory).unwrap();
+        JsonDiagnostic::export_all_to(type_export_directory).unwrap();
+        BackupEntry::export_all_to(type_export_directory).unwrap();
+        FileChange::export_all_to(type_export_directory).unwrap();
+        JournalEntry::export_all_to(type_export_directory).unwrap();
+        JournalState::export_all_to(type_export_directory).unwrap();
+        VendorProblem::export_all_to(type_export_directory).unwrap();
+        PlannedAction::export_all_to(type_export_directory).unwrap();
+        PlannedFile::export_all_to(type_export_directory).unwrap();
+        PresetRename::export_all_to(type_export_directory).unwrap();
+        ReferenceChange::export_all_to(type_export_directory).unwrap();
+        VendorPlan::export_all_to(type_export_directory).unwrap();
+        WriteConflict::export_all_to(type_export_directory).unwrap();
+        WriteError::export_all_to(type_export_directory).unwrap();
+        TokenedPreset::export_all_to(type_export_directory).unwrap();
+        TrashEntry::export_all_to(type_export_directory).unwrap();
+        VendorListEntry::export_all_to(type_export_directory).unwrap();
+        OverlaySettings::export_all_to(type_export_directory).unwrap();
+        AuditRecord::export_all_to(type_export_directory).unwrap();
+        AuditQuery::export_all_to(type_export_directory).unwrap();
+        CollisionStrategy::export_all_to(type_export_directory).unwrap();
+        SharedBaseStrategy::export_all_to(type_export_directory).unwrap();
+        BundleImport::export_all_to(type_export_directory).unwrap();
+        RenamedPreset::export_all_to(type_export_directory).unwrap();
+        PresetEdit::export_all_to(type_export_directory).unwrap();
+        DeleteStrategy::export_all_to(type_export_directory).unwrap();
+        PresetDependent::export_all_to(type_export_directory).unwrap();
+        DeleteImpact::export_all_to(type_export_directory).unwrap();
     }
 
     tauri::Builder::default()
         .manage(Arc::new(KeySet {
             values: RwLock::new(HashSet::new()),
         }))
+        .setup(|app| {
+            let app_data_directory = app
+                .path_resolver()
+                .app_data_dir()
+                .ok_or("Could not resolve the app data directory")?;
+
+            app.manage(Arc::new(FileStore::new(app_data_directory)));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             greet2,
@@ -180,6 +339,7 @@ fn main() {
             load_printer_model_preset,
             load_printer_variant_preset,
             load_generic_preset,
+            load_generic_preset_with_token,
             load_all_filament_presets,
             load_all_printer_model_presets,
             load_all_printer_presets,
@@ -192,19 +352,54 @@ fn main() {
             show_in_folder,
             load_all_process_presets,
             write_to_file,
+            get_file_token,
             rename_file,
             rename_config,
+            plan_rename_preset,
+            rename_preset,
             delete_file,
+            preset_delete_impact,
+            plan_delete_preset,
+            delete_preset,
             copy_file,
+            plan_copy_configs_to_vendor,
+            copy_configs_to_vendor,
             create_directory,
             find_possible_values,
             pick_folder,
             save_and_zip_json_bundle,
+            export_vendor_bundle,
             duplicate_vendor,
+            plan_duplicate_vendor,
+            rename_vendor,
+            plan_merge_vendors,
+            merge_vendors,
+            plan_split_vendor,
+            import_vendor_bundle,
+            split_vendor,
+            plan_rename_vendor,
+            execute_vendor_plan,
             analyse_vendor_config,
             analyse_installed_filament_config,
             analyse_installed_printer_config,
             analyse_installed_process_config,
+            analyse_generic_config,
+            minimize_config,
+            analyse_vendor_directory,
+            diagnose_json_file,
+            repair_json_file,
+            list_backups,
+            restore_backup,
+            undo,
+            redo,
+            get_journal,
+            query_audit_log,
+            list_trash,
+            restore_from_trash,
+            purge_trash,
+            set_workspace_roots,
+            set_overlay_directory,
+            materialize_overlay,
             add_new_prop_to_file,
             populate_key_set,
             check_in_set,
@@ -0,0 +1,445 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::commons::push_analysis_message;
+use crate::configuration_loader::{
+    AnalysisMessageDetails, ConfigDetails, ErrType, VendorJsonSchema,
+};
+use crate::overlay::read_path;
+use crate::vendor_duplication::config_lists;
+
+/// How a field holds the names it references.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameEncoding {
+    /// One name.
+    String,
+    /// An array of names.
+    Array,
+    /// One string of names separated by the given character.
+    Delimited(char),
+}
+
+/// What the names of a field refer to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameTarget {
+    /// A preset of the type of the config holding the field.
+    OwnType,
+    /// A preset of the given config type.
+    Preset(&'static str),
+    /// A nozzle variant listed by the printer model, not a preset. Renaming
+    /// presets never changes it.
+    Variant,
+}
+
+/// A key of a config that holds the names of other presets.
+#[derive(Debug)]
+pub struct NameField {
+    pub key: &'static str,
+    pub encoding: NameEncoding,
+    /// The config types the key is found in.
+    pub config_types: &'static [&'static str],
+    pub target: NameTarget,
+}
+
+/// Every key of a config naming other presets. Renames, duplicates, merges
+/// and the analysis all go through this list.
+pub const NAME_FIELDS: [NameField; 11] = [
+    NameField {
+        key: "inherits",
+        encoding: NameEncoding::String,
+        config_types: &["printer", "filament", "process"],
+        target: NameTarget::OwnType,
+    },
+    NameField {
+        key: "printer_model",
+        encoding: NameEncoding::String,
+        config_types: &["printer"],
+        target: NameTarget::Preset("printer-model"),
+    },
+    NameField {
+        key: "printer_variant",
+        encoding: NameEncoding::String,
+        config_types: &["printer"],
+        target: NameTarget::Variant,
+    },
+    NameField {
+        key: "default_print_profile",
+        encoding: NameEncoding::String,
+        config_types: &["printer"],
+        target: NameTarget::Preset("process"),
+    },
+    NameField {
+        key: "default_filament_profile",
+        encoding: NameEncoding::Array,
+        config_types: &["printer"],
+        target: NameTarget::Preset("filament"),
+    },
+    NameField {
+        key: "default_materials",
+        encoding: NameEncoding::Delimited(';'),
+        config_types: &["printer-model"],
+        target: NameTarget::Preset("filament"),
+    },
+    NameField {
+        key: "compatible_printers",
+        encoding: NameEncoding::Array,
+        config_types: &["filament", "process"],
+        target: NameTarget::Preset("printer"),
+    },
+    NameField {
+        key: "compatible_prints",
+        encoding: NameEncoding::Array,
+        config_types: &["filament"],
+        target: NameTarget::Preset("process"),
+    },
+    NameField {
+        key: "printer_settings_id",
+        encoding: NameEncoding::String,
+        config_types: &["printer"],
+        target: NameTarget::OwnType,
+    },
+    NameField {
+        key: "print_settings_id",
+        encoding: NameEncoding::String,
+        config_types: &["process"],
+        target: NameTarget::OwnType,
+    },
+    NameField {
+        key: "filament_settings_id",
+        encoding: NameEncoding::Array,
+        config_types: &["filament"],
+        target: NameTarget::OwnType,
+    },
+];
+
+/// The name fields of configs of `config_type`.
+pub fn name_fields(config_type: &str) -> impl Iterator<Item = &'static NameField> + '_ {
+    NAME_FIELDS
+        .iter()
+        .filter(move |field| field.config_types.contains(&config_type))
+}
+
+/// The name field `key` of configs of `config_type`, if it is one.
+pub fn name_field(config_type: &str, key: &str) -> Option<&'static NameField> {
+    name_fields(config_type).find(|field| field.key == key)
+}
+
+impl NameField {
+    /// The config type of the presets the field of a `config_type` config
+    /// names, `None` for nozzle variants.
+    pub fn target_type<'a>(&self, config_type: &'a str) -> Option<&'a str> {
+        match self.target {
+            NameTarget::OwnType => Some(config_type),
+            NameTarget::Preset(target_type) => Some(target_type),
+            NameTarget::Variant => None,
+        }
+    }
+
+    fn encoding_error(&self) -> String {
+        match self.encoding {
+            NameEncoding::String => format!("'{}' must be a string", self.key),
+            NameEncoding::Array => format!("'{}' must be an array of strings", self.key),
+            NameEncoding::Delimited(separator) => {
+                format!("'{}' must be a '{}' separated string", self.key, separator)
+            }
+        }
+    }
+
+    /// The names the field holds in `values`, none without the field.
+    pub fn names(&self, values: &Map<String, Value>) -> Result<Vec<String>, String> {
+        let Some(value) = values.get(self.key) else {
+            return Ok(Vec::new());
+        };
+
+        match (self.encoding, value) {
+            (NameEncoding::String, Value::String(name)) => Ok(vec![name.clone()]),
+            (NameEncoding::Array, Value::Array(names)) => names
+                .iter()
+                .map(|name| {
+                    name.as_str()
+                        .map(String::from)
+                        .ok_or(format!("'{}' must only contain strings", self.key))
+                })
+                .collect(),
+            (NameEncoding::Delimited(separator), Value::String(names)) => Ok(names
+                .split(separator)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect()),
+            _ => Err(self.encoding_error()),
+        }
+    }
+
+    /// Passes every name the field holds in `values` through `rename`,
+    /// keeping its encoding. Empty names are left alone, a field of the wrong
+    /// kind is not changed.
+    pub fn rename(
+        &self,
+        values: &mut Map<String, Value>,
+        mut rename: impl FnMut(&str) -> String,
+    ) -> Result<(), String> {
+        self.names(values)?;
+
+        let Some(value) = values.get_mut(self.key) else {
+            return Ok(());
+        };
+        let mut rename = |name: &str| {
+            if name.is_empty() {
+                name.to_string()
+            } else {
+                rename(name)
+            }
+        };
+
+        match (self.encoding, value) {
+            (NameEncoding::String, Value::String(name)) => *name = rename(name),
+            (NameEncoding::Array, Value::Array(names)) => {
+                for name in names.iter_mut() {
+                    if let Value::String(name) = name {
+                        *name = rename(name);
+                    }
+                }
+            }
+            (NameEncoding::Delimited(separator), Value::String(names)) => {
+                *names = names
+                    .split(separator)
+                    .map(&mut rename)
+                    .collect::<Vec<_>>()
+                    .join(&separator.to_string());
+            }
+            _ => return Err(self.encoding_error()),
+        }
+
+        Ok(())
+    }
+
+    /// Drops the names the field holds in `values` that `keep` refuses,
+    /// keeping its encoding. Dropping the one name of a string field removes
+    /// the field, a field of the wrong kind is not changed.
+    pub fn retain(
+        &self,
+        values: &mut Map<String, Value>,
+        mut keep: impl FnMut(&str) -> bool,
+    ) -> Result<(), String> {
+        self.names(values)?;
+
+        let Some(value) = values.get_mut(self.key) else {
+            return Ok(());
+        };
+
+        match (self.encoding, value) {
+            (NameEncoding::String, Value::String(name)) => {
+                if !keep(name) {
+                    values.shift_remove(self.key);
+                }
+            }
+            (NameEncoding::Array, Value::Array(names)) => {
+                names.retain(|name| name.as_str().is_none_or(&mut keep));
+            }
+            (NameEncoding::Delimited(separator), Value::String(names)) => {
+                *names = names
+                    .split(separator)
+                    .filter(|name| name.is_empty() || keep(name))
+                    .collect::<Vec<_>>()
+                    .join(&separator.to_string());
+            }
+            _ => return Err(self.encoding_error()),
+        }
+
+        Ok(())
+    }
+}
+
+// The nozzle variants a printer model lists, as `0.4;0.6`
+fn model_variants(values: &Map<String, Value>) -> Vec<String> {
+    values
+        .get("nozzle_diameter")
+        .and_then(Value::as_str)
+        .map(|variants| variants.split(';').map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Warns about the presets named by the configs of a vendor that the vendor
+/// does not list, and about printers using a variant their model does not
+/// list. Filaments may come from the filament library and `inherits` is
+/// checked while resolving the inheritance, so those are left out.
+pub fn analyse_vendor_references(
+    vendor_config: &VendorJsonSchema,
+    directory: &Path,
+    config_details: &ConfigDetails,
+) -> HashMap<String, Vec<AnalysisMessageDetails>> {
+    let mut analysis_result: HashMap<String, Vec<AnalysisMessageDetails>> = HashMap::new();
+    let mut vendor_config = vendor_config.clone();
+
+    let mut configs: Vec<(&str, String, Map<String, Value>)> = Vec::new();
+    for (config_type, list) in config_lists(&mut vendor_config) {
+        for entry in list.iter().flatten() {
+            // configs that cannot be read are reported by the other checks
+            let Some(values) = fs::read_to_string(read_path(&directory.join(&entry.sub_path)))
+                .ok()
+                .and_then(|data| serde_json::from_str(&data).ok())
+            else {
+                continue;
+            };
+            configs.push((config_type, entry.name.clone(), values));
+        }
+    }
+
+    let find = |config_type: &str, name: &str| {
+        configs
+            .iter()
+            .find(|(other_type, other_name, _)| *other_type == config_type && other_name == name)
+            .map(|(_, _, values)| values)
+    };
+
+    for (config_type, name, values) in &configs {
+        for field in name_fields(config_type) {
+            let Ok(names) = field.names(values) else {
+                continue;
+            };
+
+            if field.target == NameTarget::Variant {
+                let model = values
+                    .get("printer_model")
+                    .and_then(Value::as_str)
+                    .and_then(|model| find("printer-model", model));
+
+                for variant in names {
+                    if model.is_some_and(|model| !model_variants(model).contains(&variant)) {
+                        push_analysis_message(
+                            &mut analysis_result,
+                            field.key,
+                            format!(
+                                "Config '{}' uses the variant '{}' that its printer model does not list",
+                                name, variant
+                            ),
+                            ErrType::Warning,
+                            config_details,
+                        );
+                    }
+                }
+                continue;
+            }
+
+            let Some(target_type) = field.target_type(config_type) else {
+                continue;
+            };
+            if target_type == "filament" || field.target == NameTarget::OwnType {
+                continue;
+            }
+
+            for missing in names
+                .iter()
+                .filter(|name| find(target_type, name).is_none())
+            {
+                push_analysis_message(
+                    &mut analysis_result,
+                    field.key,
+                    format!(
+                        "Config '{}' names the {} preset '{}' that the vendor does not list",
+                        name, target_type, missing
+                    ),
+                    ErrType::Warning,
+                    config_details,
+                );
+            }
+        }
+    }
+
+    analysis_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn values(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    fn upper(name: &str) -> String {
+        name.to_uppercase()
+    }
+
+    #[test]
+    fn rename_keeps_the_encoding() {
+        let mut config = values(json!({
+            "inherits": "base",
+            "compatible_printers": ["one", "two"],
+            "default_materials": "pla;;petg;",
+        }));
+
+        for key in ["inherits", "compatible_printers"] {
+            name_field("filament", key)
+                .unwrap()
+                .rename(&mut config, upper)
+                .unwrap();
+        }
+        name_field("printer-model", "default_materials")
+            .unwrap()
+            .rename(&mut config, upper)
+            .unwrap();
+
+        assert_eq!(config["inherits"], json!("BASE"));
+        assert_eq!(config["compatible_printers"], json!(["ONE", "TWO"]));
+        assert_eq!(config["default_materials"], json!("PLA;;PETG;"));
+    }
+
+    #[test]
+    fn empty_names_and_missing_fields_are_left_alone() {
+        let mut config = values(json!({"inherits": "", "compatible_printers": ["", "x"]}));
+        let mut renamed = Vec::new();
+
+        for key in ["inherits", "compatible_printers", "compatible_prints"] {
+            name_field("filament", key)
+                .unwrap()
+                .rename(&mut config, |name| {
+                    renamed.push(name.to_string());
+                    upper(name)
+                })
+                .unwrap();
+        }
+
+        assert_eq!(renamed, vec!["x"]);
+        assert_eq!(config["inherits"], json!(""));
+        assert_eq!(config["compatible_printers"], json!(["", "X"]));
+        assert!(!config.contains_key("compatible_prints"));
+    }
+
+    #[test]
+    fn fields_of_the_wrong_kind_are_refused_unchanged() {
+        let mut config = values(json!({"compatible_printers": "one", "filament_settings_id": 3}));
+
+        let field = name_field("filament", "compatible_printers").unwrap();
+        assert!(field.rename(&mut config, upper).is_err());
+        let field = name_field("filament", "filament_settings_id").unwrap();
+        assert!(field.rename(&mut config, upper).is_err());
+
+        assert_eq!(config["compatible_printers"], json!("one"));
+        assert_eq!(config["filament_settings_id"], json!(3));
+    }
+
+    #[test]
+    fn arrays_with_other_values_are_refused() {
+        let mut config = values(json!({"default_filament_profile": ["pla", 1]}));
+        let field = name_field("printer", "default_filament_profile").unwrap();
+
+        assert!(field.rename(&mut config, upper).is_err());
+        assert_eq!(config["default_filament_profile"], json!(["pla", 1]));
+    }
+
+    #[test]
+    fn settings_ids_name_the_preset_itself() {
+        for (config_type, key) in [
+            ("printer", "printer_settings_id"),
+            ("process", "print_settings_id"),
+            ("filament", "filament_settings_id"),
+        ] {
+            let field = name_field(config_type, key).unwrap();
+            assert_eq!(field.target_type(config_type), Some(config_type));
+        }
+    }
+}
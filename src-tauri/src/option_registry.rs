@@ -0,0 +1,1299 @@
+// Option tables mirrored from the frontend definitions in src/lib/all-configuration-options.ts,
+// printer-configuration-options.ts, model-configuration-options.ts and
+// vendor-configuration-options.ts. Keep them in sync when options are added there.
+
+/// Mirror of the frontend `ConfigOptionType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionType {
+    None,
+    Float,
+    Floats,
+    Int,
+    Ints,
+    String,
+    Strings,
+    Percent,
+    Percents,
+    FloatOrPercent,
+    Point,
+    Points,
+    Bool,
+    Bools,
+    Enum,
+    Enums,
+    NameAndSubpaths,
+}
+
+/// Every option known to the editor.
+pub const ALL_OPTIONS: &[(&str, OptionType)] = &[
+    ("absolute_correction", OptionType::Float),
+    ("accel_to_decel_enable", OptionType::Bool),
+    ("accel_to_decel_factor", OptionType::Percent),
+    ("activate_air_filtration", OptionType::Bools),
+    ("activate_chamber_temp_control", OptionType::Bools),
+    ("adaptive_bed_mesh_margin", OptionType::Float),
+    ("adaptive_pressure_advance", OptionType::Bools),
+    ("adaptive_pressure_advance_bridges", OptionType::Floats),
+    ("adaptive_pressure_advance_model", OptionType::Strings),
+    ("adaptive_pressure_advance_overhangs", OptionType::Bools),
+    ("additional_cooling_fan_speed", OptionType::Ints),
+    ("alternate_extra_wall", OptionType::Bool),
+    ("area_fill", OptionType::Float),
+    ("auxiliary_fan", OptionType::Bool),
+    ("bbl_calib_mark_logo", OptionType::Bool),
+    ("bbl_use_printhost", OptionType::Bool),
+    ("bed_custom_model", OptionType::String),
+    ("bed_custom_texture", OptionType::String),
+    ("bed_exclude_area", OptionType::Points),
+    ("bed_mesh_max", OptionType::Point),
+    ("bed_mesh_min", OptionType::Point),
+    ("bed_mesh_probe_distance", OptionType::Point),
+    ("before_layer_change_gcode", OptionType::String),
+    ("best_object_pos", OptionType::Point),
+    ("bottle_cost", OptionType::Float),
+    ("bottle_volume", OptionType::Float),
+    ("bottle_weight", OptionType::Float),
+    ("bottom_shell_layers", OptionType::Int),
+    ("bottom_shell_thickness", OptionType::Float),
+    ("bottom_solid_infill_flow_ratio", OptionType::Float),
+    ("bottom_surface_pattern", OptionType::Enum),
+    ("bridge_acceleration", OptionType::FloatOrPercent),
+    ("bridge_angle", OptionType::Float),
+    ("bridge_density", OptionType::Percent),
+    ("bridge_flow", OptionType::Float),
+    ("bridge_no_support", OptionType::Bool),
+    ("bridge_speed", OptionType::Float),
+    ("brim_ears", OptionType::Bool),
+    ("brim_ears_detection_length", OptionType::Float),
+    ("brim_ears_max_angle", OptionType::Float),
+    ("brim_object_gap", OptionType::Float),
+    ("brim_type", OptionType::Enum),
+    ("brim_width", OptionType::Float),
+    ("chamber_temperature", OptionType::Ints),
+    ("change_extrusion_role_gcode", OptionType::String),
+    ("change_filament_gcode", OptionType::String),
+    ("close_fan_the_first_x_layers", OptionType::Ints),
+    ("compatible_machine_expression_group", OptionType::Strings),
+    ("compatible_printers", OptionType::Strings),
+    ("compatible_printers_condition", OptionType::String),
+    ("compatible_prints", OptionType::Strings),
+    ("compatible_prints_condition", OptionType::String),
+    ("compatible_process_expression_group", OptionType::Strings),
+    ("complete_print_exhaust_fan_speed", OptionType::Ints),
+    ("cool_plate_temp", OptionType::Ints),
+    ("cool_plate_temp_initial_layer", OptionType::Ints),
+    ("cooling_tube_length", OptionType::Float),
+    ("cooling_tube_retraction", OptionType::Float),
+    ("counterbore_hole_bridging", OptionType::Enum),
+    ("curr_bed_type", OptionType::Enum),
+    ("default_acceleration", OptionType::Float),
+    ("default_filament_colour", OptionType::Strings),
+    ("default_filament_profile", OptionType::Strings),
+    ("default_jerk", OptionType::Float),
+    ("default_print_profile", OptionType::String),
+    ("default_sla_material_profile", OptionType::String),
+    ("default_sla_print_profile", OptionType::String),
+    ("deretraction_speed", OptionType::Floats),
+    ("detect_narrow_internal_solid_infill", OptionType::Bool),
+    ("detect_overhang_wall", OptionType::Bool),
+    ("detect_thin_wall", OptionType::Bool),
+    ("different_settings_to_system", OptionType::Strings),
+    ("disable_m73", OptionType::Bool),
+    ("display_height", OptionType::Float),
+    ("display_mirror_x", OptionType::Bool),
+    ("display_mirror_y", OptionType::Bool),
+    ("display_orientation", OptionType::Enum),
+    ("display_pixels_x", OptionType::Int),
+    ("display_pixels_y", OptionType::Int),
+    ("display_width", OptionType::Float),
+    ("dont_filter_internal_bridges", OptionType::Enum),
+    ("dont_slow_down_outer_wall", OptionType::Bools),
+    ("draft_shield", OptionType::Enum),
+    ("during_print_exhaust_fan_speed", OptionType::Ints),
+    ("elefant_foot_compensation", OptionType::Float),
+    ("elefant_foot_compensation_layers", OptionType::Int),
+    ("elefant_foot_min_width", OptionType::Float),
+    ("emit_machine_limits_to_gcode", OptionType::Bool),
+    ("enable_arc_fitting", OptionType::Bool),
+    ("enable_extra_bridge_layer", OptionType::Enum),
+    ("enable_filament_ramming", OptionType::Bool),
+    ("enable_long_retraction_when_cut", OptionType::Int),
+    ("enable_overhang_bridge_fan", OptionType::Bools),
+    ("enable_overhang_speed", OptionType::Bool),
+    ("enable_pressure_advance", OptionType::Bools),
+    ("enable_prime_tower", OptionType::Bool),
+    ("enable_support", OptionType::Bool),
+    ("enforce_support_layers", OptionType::Int),
+    ("eng_plate_temp", OptionType::Ints),
+    ("eng_plate_temp_initial_layer", OptionType::Ints),
+    ("ensure_vertical_shell_thickness", OptionType::Enum),
+    ("exclude_object", OptionType::Bool),
+    ("exposure_time", OptionType::Float),
+    ("extra_loading_move", OptionType::Float),
+    ("extra_perimeters_on_overhangs", OptionType::Bool),
+    ("extruder", OptionType::Int),
+    ("extruder_clearance_height_to_lid", OptionType::Float),
+    ("extruder_clearance_height_to_rod", OptionType::Float),
+    ("extruder_clearance_radius", OptionType::Float),
+    ("extruder_colour", OptionType::Strings),
+    ("extruder_offset", OptionType::Points),
+    (
+        "extrusion_rate_smoothing_external_perimeter_only",
+        OptionType::Bool,
+    ),
+    ("faded_layers", OptionType::Int),
+    ("fan_cooling_layer_time", OptionType::Floats),
+    ("fan_kickstart", OptionType::Float),
+    ("fan_max_speed", OptionType::Floats),
+    ("fan_min_speed", OptionType::Floats),
+    ("fan_speedup_overhangs", OptionType::Bool),
+    ("fan_speedup_time", OptionType::Float),
+    ("fast_tilt_time", OptionType::Float),
+    ("filament_colour", OptionType::Strings),
+    ("filament_cooling_final_speed", OptionType::Floats),
+    ("filament_cooling_initial_speed", OptionType::Floats),
+    ("filament_cooling_moves", OptionType::Ints),
+    ("filament_cost", OptionType::Floats),
+    ("filament_density", OptionType::Floats),
+    ("filament_deretraction_speed", OptionType::Floats),
+    ("filament_diameter", OptionType::Floats),
+    ("filament_end_gcode", OptionType::Strings),
+    ("filament_flow_ratio", OptionType::Floats),
+    ("filament_ids", OptionType::Strings),
+    ("filament_is_support", OptionType::Bools),
+    ("filament_loading_speed", OptionType::Floats),
+    ("filament_loading_speed_start", OptionType::Floats),
+    ("filament_long_retractions_when_cut", OptionType::Bools),
+    ("filament_max_volumetric_speed", OptionType::Floats),
+    ("filament_minimal_purge_on_wipe_tower", OptionType::Floats),
+    ("filament_multitool_ramming", OptionType::Bools),
+    ("filament_multitool_ramming_flow", OptionType::Floats),
+    ("filament_multitool_ramming_volume", OptionType::Floats),
+    ("filament_notes", OptionType::Strings),
+    ("filament_ramming_parameters", OptionType::Strings),
+    ("filament_retract_before_wipe", OptionType::Percents),
+    ("filament_retract_lift_above", OptionType::Floats),
+    ("filament_retract_lift_below", OptionType::Floats),
+    ("filament_retract_lift_enforce", OptionType::Enums),
+    ("filament_retract_on_top_layer", OptionType::Bools),
+    ("filament_retract_restart_extra", OptionType::Floats),
+    ("filament_retract_when_changing_layer", OptionType::Bools),
+    ("filament_retraction_distances_when_cut", OptionType::Floats),
+    ("filament_retraction_length", OptionType::Floats),
+    ("filament_retraction_minimum_travel", OptionType::Floats),
+    ("filament_retraction_speed", OptionType::Floats),
+    ("filament_settings_id", OptionType::Strings),
+    ("filament_shrink", OptionType::Percents),
+    ("filament_shrinkage_compensation_z", OptionType::Percents),
+    ("filament_soluble", OptionType::Bools),
+    ("filament_stamping_distance", OptionType::Floats),
+    ("filament_stamping_loading_speed", OptionType::Floats),
+    ("filament_start_gcode", OptionType::Strings),
+    ("filament_toolchange_delay", OptionType::Floats),
+    ("filament_type", OptionType::Strings),
+    ("filament_unloading_speed", OptionType::Floats),
+    ("filament_unloading_speed_start", OptionType::Floats),
+    ("filament_vendor", OptionType::Strings),
+    ("filament_wipe", OptionType::Bools),
+    ("filament_wipe_distance", OptionType::Floats),
+    ("filament_z_hop", OptionType::Floats),
+    ("filament_z_hop_types", OptionType::Enums),
+    ("filename_format", OptionType::String),
+    ("filter_out_gap_fill", OptionType::Float),
+    ("first_layer_print_sequence", OptionType::Ints),
+    ("first_layer_sequence_choice", OptionType::Enum),
+    ("flush_into_infill", OptionType::Bool),
+    ("flush_into_objects", OptionType::Bool),
+    ("flush_into_support", OptionType::Bool),
+    ("flush_multiplier", OptionType::Float),
+    ("flush_volumes_matrix", OptionType::Floats),
+    ("flush_volumes_vector", OptionType::Floats),
+    ("full_fan_speed_layer", OptionType::Ints),
+    ("fuzzy_skin", OptionType::Enum),
+    ("fuzzy_skin_first_layer", OptionType::Bool),
+    ("fuzzy_skin_noise_type", OptionType::Enum),
+    ("fuzzy_skin_octaves", OptionType::Int),
+    ("fuzzy_skin_persistence", OptionType::Float),
+    ("fuzzy_skin_point_distance", OptionType::Float),
+    ("fuzzy_skin_scale", OptionType::Float),
+    ("fuzzy_skin_thickness", OptionType::Float),
+    ("gamma_correction", OptionType::Float),
+    ("gap_fill_target", OptionType::Enum),
+    ("gap_infill_speed", OptionType::Float),
+    ("gcode_add_line_number", OptionType::Bool),
+    ("gcode_comments", OptionType::Bool),
+    ("gcode_flavor", OptionType::Enum),
+    ("gcode_label_objects", OptionType::Bool),
+    ("has_scarf_joint_seam", OptionType::Bool),
+    ("head_wrap_detect_zone", OptionType::Points),
+    ("helio_filament_id", OptionType::String),
+    ("helio_initial_room_air_temp", OptionType::Float),
+    ("helio_layer_threshold", OptionType::Float),
+    ("helio_object_proximity_airtemp", OptionType::Float),
+    ("helio_printer_id", OptionType::String),
+    ("high_current_on_filament_swap", OptionType::Bool),
+    ("hole_to_polyhole", OptionType::Bool),
+    ("hole_to_polyhole_threshold", OptionType::FloatOrPercent),
+    ("hole_to_polyhole_twisted", OptionType::Bool),
+    ("hollowing_closing_distance", OptionType::Float),
+    ("hollowing_enable", OptionType::Bool),
+    ("hollowing_min_thickness", OptionType::Float),
+    ("hollowing_quality", OptionType::Float),
+    ("host_type", OptionType::Enum),
+    ("hot_plate_temp", OptionType::Ints),
+    ("hot_plate_temp_initial_layer", OptionType::Ints),
+    ("idle_temperature", OptionType::Ints),
+    ("independent_support_layer_height", OptionType::Bool),
+    ("infill_anchor", OptionType::FloatOrPercent),
+    ("infill_anchor_max", OptionType::FloatOrPercent),
+    ("infill_combination", OptionType::Bool),
+    (
+        "infill_combination_max_layer_height",
+        OptionType::FloatOrPercent,
+    ),
+    ("infill_direction", OptionType::Float),
+    ("infill_jerk", OptionType::Float),
+    ("infill_wall_overlap", OptionType::Percent),
+    ("inherits_group", OptionType::Strings),
+    ("initial_exposure_time", OptionType::Float),
+    ("initial_layer_acceleration", OptionType::Float),
+    ("initial_layer_height", OptionType::Float),
+    ("initial_layer_infill_speed", OptionType::Float),
+    ("initial_layer_jerk", OptionType::Float),
+    ("initial_layer_line_width", OptionType::FloatOrPercent),
+    ("initial_layer_min_bead_width", OptionType::Percent),
+    ("initial_layer_print_height", OptionType::Float),
+    ("initial_layer_speed", OptionType::Float),
+    ("initial_layer_travel_speed", OptionType::FloatOrPercent),
+    ("inner_wall_acceleration", OptionType::Float),
+    ("inner_wall_jerk", OptionType::Float),
+    ("inner_wall_line_width", OptionType::FloatOrPercent),
+    ("inner_wall_speed", OptionType::Float),
+    ("interface_shells", OptionType::Bool),
+    ("interlocking_beam", OptionType::Bool),
+    ("interlocking_beam_layer_count", OptionType::Int),
+    ("interlocking_beam_width", OptionType::Float),
+    ("interlocking_boundary_avoidance", OptionType::Int),
+    ("interlocking_depth", OptionType::Int),
+    ("interlocking_orientation", OptionType::Float),
+    ("internal_bridge_angle", OptionType::Float),
+    ("internal_bridge_density", OptionType::Percent),
+    ("internal_bridge_fan_speed", OptionType::Ints),
+    ("internal_bridge_flow", OptionType::Float),
+    ("internal_bridge_speed", OptionType::FloatOrPercent),
+    (
+        "internal_solid_infill_acceleration",
+        OptionType::FloatOrPercent,
+    ),
+    (
+        "internal_solid_infill_line_width",
+        OptionType::FloatOrPercent,
+    ),
+    ("internal_solid_infill_pattern", OptionType::Enum),
+    ("internal_solid_infill_speed", OptionType::Float),
+    ("ironing_angle", OptionType::Float),
+    ("ironing_flow", OptionType::Percent),
+    ("ironing_inset", OptionType::Float),
+    ("ironing_pattern", OptionType::Enum),
+    ("ironing_spacing", OptionType::Float),
+    ("ironing_speed", OptionType::Float),
+    ("ironing_type", OptionType::Enum),
+    ("is_infill_first", OptionType::Bool),
+    ("lattice_angle_1", OptionType::Float),
+    ("lattice_angle_2", OptionType::Float),
+    ("layer_change_gcode", OptionType::String),
+    ("layer_height", OptionType::Float),
+    ("line_width", OptionType::FloatOrPercent),
+    ("long_retractions_when_cut", OptionType::Bools),
+    ("machine_end_gcode", OptionType::String),
+    ("machine_load_filament_time", OptionType::Float),
+    ("machine_max_acceleration_e", OptionType::Floats),
+    ("machine_max_acceleration_extruding", OptionType::Floats),
+    ("machine_max_acceleration_retracting", OptionType::Floats),
+    ("machine_max_acceleration_travel", OptionType::Floats),
+    ("machine_max_acceleration_x", OptionType::Floats),
+    ("machine_max_acceleration_y", OptionType::Floats),
+    ("machine_max_acceleration_z", OptionType::Floats),
+    ("machine_max_jerk_e", OptionType::Floats),
+    ("machine_max_jerk_x", OptionType::Floats),
+    ("machine_max_jerk_y", OptionType::Floats),
+    ("machine_max_jerk_z", OptionType::Floats),
+    ("machine_max_speed_e", OptionType::Floats),
+    ("machine_max_speed_x", OptionType::Floats),
+    ("machine_max_speed_y", OptionType::Floats),
+    ("machine_max_speed_z", OptionType::Floats),
+    ("machine_min_extruding_rate", OptionType::Floats),
+    ("machine_min_travel_rate", OptionType::Floats),
+    ("machine_pause_gcode", OptionType::String),
+    ("machine_start_gcode", OptionType::String),
+    ("machine_tool_change_time", OptionType::Float),
+    ("machine_unload_filament_time", OptionType::Float),
+    ("make_overhang_printable", OptionType::Bool),
+    ("make_overhang_printable_angle", OptionType::Float),
+    ("make_overhang_printable_hole_size", OptionType::Float),
+    ("manual_filament_change", OptionType::Bool),
+    ("material_colour", OptionType::String),
+    ("material_correction", OptionType::Floats),
+    ("material_correction_x", OptionType::Float),
+    ("material_correction_y", OptionType::Float),
+    ("material_correction_z", OptionType::Float),
+    ("material_density", OptionType::Float),
+    ("material_print_speed", OptionType::Enum),
+    ("material_type", OptionType::String),
+    ("material_vendor", OptionType::String),
+    ("max_bridge_length", OptionType::Float),
+    ("max_exposure_time", OptionType::Float),
+    ("max_initial_exposure_time", OptionType::Float),
+    ("max_layer_height", OptionType::Floats),
+    ("max_travel_detour_distance", OptionType::FloatOrPercent),
+    ("max_volumetric_extrusion_rate_slope", OptionType::Float),
+    (
+        "max_volumetric_extrusion_rate_slope_segment_length",
+        OptionType::Float,
+    ),
+    ("min_bead_width", OptionType::Percent),
+    ("min_exposure_time", OptionType::Float),
+    ("min_feature_size", OptionType::Percent),
+    ("min_initial_exposure_time", OptionType::Float),
+    ("min_layer_height", OptionType::Floats),
+    ("min_length_factor", OptionType::Float),
+    ("min_skirt_length", OptionType::Float),
+    ("min_width_top_surface", OptionType::FloatOrPercent),
+    ("minimum_sparse_infill_area", OptionType::Float),
+    ("mmu_segmented_region_interlocking_depth", OptionType::Float),
+    ("mmu_segmented_region_max_width", OptionType::Float),
+    ("notes", OptionType::String),
+    ("nozzle_diameter", OptionType::Floats),
+    ("nozzle_height", OptionType::Float),
+    ("nozzle_hrc", OptionType::Int),
+    ("nozzle_temperature", OptionType::Ints),
+    ("nozzle_temperature_initial_layer", OptionType::Ints),
+    ("nozzle_temperature_range_high", OptionType::Ints),
+    ("nozzle_temperature_range_low", OptionType::Ints),
+    ("nozzle_type", OptionType::Enum),
+    ("nozzle_volume", OptionType::Float),
+    ("only_one_wall_first_layer", OptionType::Bool),
+    ("only_one_wall_top", OptionType::Bool),
+    ("ooze_prevention", OptionType::Bool),
+    ("other_layers_print_sequence", OptionType::Ints),
+    ("other_layers_print_sequence_nums", OptionType::Int),
+    ("other_layers_sequence_choice", OptionType::Enum),
+    ("outer_wall_acceleration", OptionType::Float),
+    ("outer_wall_jerk", OptionType::Float),
+    ("outer_wall_line_width", OptionType::FloatOrPercent),
+    ("outer_wall_speed", OptionType::Float),
+    ("overhang_1_4_speed", OptionType::FloatOrPercent),
+    ("overhang_2_4_speed", OptionType::FloatOrPercent),
+    ("overhang_3_4_speed", OptionType::FloatOrPercent),
+    ("overhang_4_4_speed", OptionType::FloatOrPercent),
+    ("overhang_fan_speed", OptionType::Ints),
+    ("overhang_fan_threshold", OptionType::Enums),
+    ("overhang_reverse", OptionType::Bool),
+    ("overhang_reverse_internal_only", OptionType::Bool),
+    ("overhang_reverse_threshold", OptionType::FloatOrPercent),
+    ("overhang_speed_classic", OptionType::Bool),
+    ("pad_around_object", OptionType::Bool),
+    ("pad_around_object_everywhere", OptionType::Bool),
+    ("pad_brim_size", OptionType::Float),
+    ("pad_enable", OptionType::Bool),
+    ("pad_max_merge_distance", OptionType::Float),
+    ("pad_object_connector_penetration", OptionType::Float),
+    ("pad_object_connector_stride", OptionType::Float),
+    ("pad_object_connector_width", OptionType::Float),
+    ("pad_object_gap", OptionType::Float),
+    ("pad_wall_height", OptionType::Float),
+    ("pad_wall_slope", OptionType::Float),
+    ("pad_wall_thickness", OptionType::Float),
+    ("parking_pos_retraction", OptionType::Float),
+    ("pellet_flow_coefficient", OptionType::Floats),
+    ("pellet_modded_printer", OptionType::Bool),
+    ("post_process", OptionType::Strings),
+    ("precise_outer_wall", OptionType::Bool),
+    ("precise_z_height", OptionType::Bool),
+    ("preferred_orientation", OptionType::Float),
+    ("preheat_steps", OptionType::Int),
+    ("preheat_time", OptionType::Float),
+    ("preset_name", OptionType::String),
+    ("preset_names", OptionType::Strings),
+    ("pressure_advance", OptionType::Floats),
+    ("prime_tower_brim_width", OptionType::Float),
+    ("prime_tower_width", OptionType::Float),
+    ("prime_volume", OptionType::Float),
+    ("print_compatible_printers", OptionType::Strings),
+    ("print_flow_ratio", OptionType::Float),
+    ("print_host", OptionType::String),
+    ("print_host_webui", OptionType::String),
+    ("print_order", OptionType::Enum),
+    ("print_sequence", OptionType::Enum),
+    ("print_settings_id", OptionType::String),
+    ("printable_area", OptionType::Points),
+    ("printable_height", OptionType::Float),
+    ("printer_model", OptionType::String),
+    ("printer_notes", OptionType::String),
+    ("printer_settings_id", OptionType::String),
+    ("printer_structure", OptionType::Enum),
+    ("printer_technology", OptionType::Enum),
+    ("printer_variant", OptionType::String),
+    ("printhost_apikey", OptionType::String),
+    ("printhost_authorization_type", OptionType::Enum),
+    ("printhost_cafile", OptionType::String),
+    ("printhost_password", OptionType::String),
+    ("printhost_port", OptionType::String),
+    ("printhost_ssl_ignore_revoke", OptionType::Bool),
+    ("printhost_user", OptionType::String),
+    ("printing_by_object_gcode", OptionType::String),
+    ("purge_in_prime_tower", OptionType::Bool),
+    ("raft_contact_distance", OptionType::Float),
+    ("raft_expansion", OptionType::Float),
+    ("raft_first_layer_density", OptionType::Percent),
+    ("raft_first_layer_expansion", OptionType::Float),
+    ("raft_layers", OptionType::Int),
+    ("reduce_crossing_wall", OptionType::Bool),
+    ("reduce_fan_stop_start_freq", OptionType::Bools),
+    ("reduce_infill_retraction", OptionType::Bool),
+    ("relative_correction", OptionType::Floats),
+    ("relative_correction_x", OptionType::Float),
+    ("relative_correction_y", OptionType::Float),
+    ("relative_correction_z", OptionType::Float),
+    ("required_nozzle_HRC", OptionType::Ints),
+    ("resolution", OptionType::Float),
+    ("retract_before_wipe", OptionType::Percents),
+    ("retract_length_toolchange", OptionType::Floats),
+    ("retract_lift_above", OptionType::Floats),
+    ("retract_lift_below", OptionType::Floats),
+    ("retract_lift_enforce", OptionType::Enums),
+    ("retract_on_top_layer", OptionType::Bools),
+    ("retract_restart_extra", OptionType::Floats),
+    ("retract_restart_extra_toolchange", OptionType::Floats),
+    ("retract_when_changing_layer", OptionType::Bools),
+    ("retraction_distances_when_cut", OptionType::Floats),
+    ("retraction_length", OptionType::Floats),
+    ("retraction_minimum_travel", OptionType::Floats),
+    ("retraction_speed", OptionType::Floats),
+    ("role_based_wipe_speed", OptionType::Bool),
+    ("rotate_solid_infill_direction", OptionType::Bool),
+    ("scan_first_layer", OptionType::Bool),
+    ("scarf_angle_threshold", OptionType::Int),
+    ("scarf_joint_flow_ratio", OptionType::Float),
+    ("scarf_joint_speed", OptionType::FloatOrPercent),
+    ("scarf_overhang_threshold", OptionType::Percent),
+    ("seam_gap", OptionType::FloatOrPercent),
+    ("seam_position", OptionType::Enum),
+    ("seam_slope_conditional", OptionType::Bool),
+    ("seam_slope_entire_loop", OptionType::Bool),
+    ("seam_slope_inner_walls", OptionType::Bool),
+    ("seam_slope_min_length", OptionType::Float),
+    ("seam_slope_start_height", OptionType::FloatOrPercent),
+    ("seam_slope_steps", OptionType::Int),
+    ("seam_slope_type", OptionType::Enum),
+    ("silent_mode", OptionType::Bool),
+    ("single_extruder_multi_material", OptionType::Bool),
+    ("single_extruder_multi_material_priming", OptionType::Bool),
+    ("skirt_distance", OptionType::Float),
+    ("skirt_height", OptionType::Int),
+    ("skirt_loops", OptionType::Int),
+    ("skirt_speed", OptionType::Float),
+    ("skirt_start_angle", OptionType::Float),
+    ("skirt_type", OptionType::Enum),
+    ("sla_material_settings_id", OptionType::String),
+    ("sla_print_settings_id", OptionType::String),
+    ("slice_closing_radius", OptionType::Float),
+    ("slicing_mode", OptionType::Enum),
+    ("slow_down_for_layer_cooling", OptionType::Bools),
+    ("slow_down_layer_time", OptionType::Floats),
+    ("slow_down_layers", OptionType::Int),
+    ("slow_down_min_speed", OptionType::Floats),
+    ("slow_tilt_time", OptionType::Float),
+    ("slowdown_for_curled_perimeters", OptionType::Bool),
+    ("small_area_infill_flow_compensation", OptionType::Bool),
+    (
+        "small_area_infill_flow_compensation_model",
+        OptionType::Strings,
+    ),
+    ("small_perimeter_speed", OptionType::FloatOrPercent),
+    ("small_perimeter_threshold", OptionType::Float),
+    ("solid_infill_direction", OptionType::Float),
+    ("solid_infill_filament", OptionType::Int),
+    ("sparse_infill_acceleration", OptionType::FloatOrPercent),
+    ("sparse_infill_density", OptionType::Percent),
+    ("sparse_infill_filament", OptionType::Int),
+    ("sparse_infill_line_width", OptionType::FloatOrPercent),
+    ("sparse_infill_pattern", OptionType::Enum),
+    ("sparse_infill_speed", OptionType::Float),
+    ("spiral_finishing_flow_ratio", OptionType::Float),
+    ("spiral_mode", OptionType::Bool),
+    ("spiral_mode_max_xy_smoothing", OptionType::FloatOrPercent),
+    ("spiral_mode_smooth", OptionType::Bool),
+    ("spiral_starting_flow_ratio", OptionType::Float),
+    ("staggered_inner_seams", OptionType::Bool),
+    ("standby_temperature_delta", OptionType::Int),
+    ("start_end_points", OptionType::Points),
+    ("supertack_plate_temp", OptionType::Ints),
+    ("supertack_plate_temp_initial_layer", OptionType::Ints),
+    ("support_air_filtration", OptionType::Bool),
+    ("support_angle", OptionType::Float),
+    ("support_base_diameter", OptionType::Float),
+    ("support_base_height", OptionType::Float),
+    ("support_base_pattern", OptionType::Enum),
+    ("support_base_pattern_spacing", OptionType::Float),
+    ("support_base_safety_distance", OptionType::Float),
+    ("support_bottom_interface_spacing", OptionType::Float),
+    ("support_bottom_z_distance", OptionType::Float),
+    ("support_buildplate_only", OptionType::Bool),
+    ("support_chamber_temp_control", OptionType::Bool),
+    ("support_critical_angle", OptionType::Float),
+    ("support_critical_regions_only", OptionType::Bool),
+    ("support_expansion", OptionType::Float),
+    ("support_filament", OptionType::Int),
+    ("support_head_front_diameter", OptionType::Float),
+    ("support_head_penetration", OptionType::Float),
+    ("support_head_width", OptionType::Float),
+    ("support_interface_bottom_layers", OptionType::Int),
+    ("support_interface_filament", OptionType::Int),
+    ("support_interface_loop_pattern", OptionType::Bool),
+    ("support_interface_not_for_body", OptionType::Bool),
+    ("support_interface_pattern", OptionType::Enum),
+    ("support_interface_spacing", OptionType::Float),
+    ("support_interface_speed", OptionType::Float),
+    ("support_interface_top_layers", OptionType::Int),
+    ("support_line_width", OptionType::FloatOrPercent),
+    ("support_material_interface_fan_speed", OptionType::Ints),
+    ("support_max_bridge_length", OptionType::Float),
+    ("support_max_bridges_on_pillar", OptionType::Int),
+    ("support_max_pillar_link_distance", OptionType::Float),
+    ("support_multi_bed_types", OptionType::Bool),
+    ("support_object_elevation", OptionType::Float),
+    ("support_object_first_layer_gap", OptionType::Float),
+    ("support_object_xy_distance", OptionType::Float),
+    ("support_on_build_plate_only", OptionType::Bool),
+    ("support_pillar_connection_mode", OptionType::Enum),
+    ("support_pillar_diameter", OptionType::Float),
+    ("support_pillar_widening_factor", OptionType::Float),
+    ("support_points_density_relative", OptionType::Int),
+    ("support_points_minimal_distance", OptionType::Float),
+    ("support_remove_small_overhang", OptionType::Bool),
+    ("support_small_pillar_diameter_percent", OptionType::Percent),
+    ("support_speed", OptionType::Float),
+    ("support_style", OptionType::Enum),
+    ("support_threshold_angle", OptionType::Int),
+    ("support_threshold_overlap", OptionType::FloatOrPercent),
+    ("support_top_z_distance", OptionType::Float),
+    ("support_type", OptionType::Enum),
+    ("supports_enable", OptionType::Bool),
+    ("temperature_vitrification", OptionType::Ints),
+    ("template_custom_gcode", OptionType::String),
+    ("textured_cool_plate_temp", OptionType::Ints),
+    ("textured_cool_plate_temp_initial_layer", OptionType::Ints),
+    ("textured_plate_temp", OptionType::Ints),
+    ("textured_plate_temp_initial_layer", OptionType::Ints),
+    ("thick_bridges", OptionType::Bool),
+    ("thick_internal_bridges", OptionType::Bool),
+    ("thumbnails", OptionType::String),
+    ("thumbnails_format", OptionType::Enum),
+    ("time_cost", OptionType::Float),
+    ("time_lapse_gcode", OptionType::String),
+    ("timelapse_type", OptionType::Enum),
+    ("top_bottom_infill_wall_overlap", OptionType::Percent),
+    ("top_shell_layers", OptionType::Int),
+    ("top_shell_thickness", OptionType::Float),
+    ("top_solid_infill_flow_ratio", OptionType::Float),
+    ("top_surface_acceleration", OptionType::Float),
+    ("top_surface_jerk", OptionType::Float),
+    ("top_surface_line_width", OptionType::FloatOrPercent),
+    ("top_surface_pattern", OptionType::Enum),
+    ("top_surface_speed", OptionType::Float),
+    ("travel_acceleration", OptionType::Float),
+    ("travel_jerk", OptionType::Float),
+    ("travel_slope", OptionType::Floats),
+    ("travel_speed", OptionType::Float),
+    ("travel_speed_z", OptionType::Float),
+    ("tree_support_adaptive_layer_height", OptionType::Bool),
+    ("tree_support_angle_slow", OptionType::Float),
+    ("tree_support_auto_brim", OptionType::Bool),
+    ("tree_support_branch_angle", OptionType::Float),
+    ("tree_support_branch_angle_organic", OptionType::Float),
+    ("tree_support_branch_diameter", OptionType::Float),
+    ("tree_support_branch_diameter_angle", OptionType::Float),
+    ("tree_support_branch_diameter_organic", OptionType::Float),
+    ("tree_support_branch_distance", OptionType::Float),
+    ("tree_support_branch_distance_organic", OptionType::Float),
+    ("tree_support_brim_width", OptionType::Float),
+    ("tree_support_tip_diameter", OptionType::Float),
+    ("tree_support_top_rate", OptionType::Percent),
+    ("tree_support_wall_count", OptionType::Int),
+    ("tree_support_with_infill", OptionType::Bool),
+    ("upward_compatible_machine", OptionType::Strings),
+    ("use_firmware_retraction", OptionType::Bool),
+    ("use_relative_e_distances", OptionType::Bool),
+    ("wall_direction", OptionType::Enum),
+    ("wall_distribution_count", OptionType::Int),
+    ("wall_filament", OptionType::Int),
+    ("wall_generator", OptionType::Enum),
+    ("wall_loops", OptionType::Int),
+    ("wall_sequence", OptionType::Enum),
+    ("wall_transition_angle", OptionType::Float),
+    ("wall_transition_filter_deviation", OptionType::Percent),
+    ("wall_transition_length", OptionType::Percent),
+    ("wipe", OptionType::Bools),
+    ("wipe_before_external_loop", OptionType::Bool),
+    ("wipe_distance", OptionType::Floats),
+    ("wipe_on_loops", OptionType::Bool),
+    ("wipe_speed", OptionType::FloatOrPercent),
+    ("wipe_tower_bridging", OptionType::Float),
+    ("wipe_tower_cone_angle", OptionType::Float),
+    ("wipe_tower_extra_flow", OptionType::Percent),
+    ("wipe_tower_extra_spacing", OptionType::Percent),
+    ("wipe_tower_filament", OptionType::Int),
+    ("wipe_tower_max_purge_speed", OptionType::Float),
+    ("wipe_tower_no_sparse_layers", OptionType::Bool),
+    ("wipe_tower_rotation_angle", OptionType::Float),
+    ("wipe_tower_x", OptionType::Floats),
+    ("wipe_tower_y", OptionType::Floats),
+    ("wiping_volumes_extruders", OptionType::Floats),
+    ("xy_contour_compensation", OptionType::Float),
+    ("xy_hole_compensation", OptionType::Float),
+    ("z_hop", OptionType::Floats),
+    ("z_hop_types", OptionType::Enums),
+    ("z_offset", OptionType::Float),
+];
+
+/// Options of printer (machine) presets.
+pub const PRINTER_OPTIONS: &[(&str, OptionType)] = &[
+    ("name", OptionType::String),
+    ("type", OptionType::String),
+    ("version", OptionType::String),
+    ("from", OptionType::Enum),
+    ("instantiation", OptionType::Bool),
+    ("inherits", OptionType::String),
+    ("num_extruders", OptionType::Int),
+    ("default_filament_profile", OptionType::Strings),
+    ("setting_id", OptionType::String),
+    ("printer_settings_id", OptionType::String),
+    ("printer_technology", OptionType::Enum),
+    ("helio_printer_id", OptionType::String),
+    ("helio_initial_room_air_temp", OptionType::Float),
+    ("helio_layer_threshold", OptionType::Float),
+    ("helio_object_proximity_airtemp", OptionType::Float),
+    ("printable_area", OptionType::Points),
+    ("bed_exclude_area", OptionType::Points),
+    ("bed_custom_texture", OptionType::String),
+    ("bed_custom_model", OptionType::String),
+    ("gcode_flavor", OptionType::Enum),
+    ("fan_kickstart", OptionType::Float),
+    ("fan_speedup_time", OptionType::Float),
+    ("fan_speedup_overhangs", OptionType::Bool),
+    ("single_extruder_multi_material", OptionType::Bool),
+    ("manual_filament_change", OptionType::Bool),
+    ("printer_model", OptionType::String),
+    ("printer_variant", OptionType::String),
+    ("printable_height", OptionType::Float),
+    ("extruder_clearance_radius", OptionType::Float),
+    ("extruder_clearance_height_to_lid", OptionType::Float),
+    ("extruder_clearance_height_to_rod", OptionType::Float),
+    ("nozzle_height", OptionType::Float),
+    ("default_print_profile", OptionType::String),
+    ("silent_mode", OptionType::Bool),
+    ("scan_first_layer", OptionType::Bool),
+    ("machine_load_filament_time", OptionType::Float),
+    ("machine_unload_filament_time", OptionType::Float),
+    ("machine_tool_change_time", OptionType::Float),
+    ("time_cost", OptionType::Float),
+    ("nozzle_type", OptionType::Enum),
+    ("nozzle_hrc", OptionType::Int),
+    ("auxiliary_fan", OptionType::Bool),
+    ("nozzle_volume", OptionType::Float),
+    ("upward_compatible_machine", OptionType::Strings),
+    ("support_chamber_temp_control", OptionType::Bool),
+    ("support_air_filtration", OptionType::Bool),
+    ("printer_structure", OptionType::Enum),
+    ("best_object_pos", OptionType::Point),
+    ("head_wrap_detect_zone", OptionType::Points),
+    ("host_type", OptionType::Enum),
+    ("print_host", OptionType::String),
+    ("printhost_apikey", OptionType::String),
+    ("bbl_use_printhost", OptionType::Bool),
+    ("print_host_webui", OptionType::String),
+    ("printhost_cafile", OptionType::String),
+    ("printhost_port", OptionType::String),
+    ("printhost_authorization_type", OptionType::Enum),
+    ("printhost_user", OptionType::String),
+    ("printhost_password", OptionType::String),
+    ("printhost_ssl_ignore_revoke", OptionType::Bool),
+    ("thumbnails", OptionType::String),
+    ("thumbnails_format", OptionType::Enum),
+    ("use_firmware_retraction", OptionType::Bool),
+    ("use_relative_e_distances", OptionType::Bool),
+    ("printer_notes", OptionType::String),
+    ("cooling_tube_retraction", OptionType::Float),
+    ("cooling_tube_length", OptionType::Float),
+    ("high_current_on_filament_swap", OptionType::Bool),
+    ("parking_pos_retraction", OptionType::Float),
+    ("extra_loading_move", OptionType::Float),
+    ("purge_in_prime_tower", OptionType::Bool),
+    ("enable_filament_ramming", OptionType::Bool),
+    ("z_offset", OptionType::Float),
+    ("disable_m73", OptionType::Bool),
+    ("preferred_orientation", OptionType::Float),
+    ("emit_machine_limits_to_gcode", OptionType::Bool),
+    ("pellet_modded_printer", OptionType::Bool),
+    ("support_multi_bed_types", OptionType::Bool),
+    ("bed_mesh_min", OptionType::Point),
+    ("bed_mesh_max", OptionType::Point),
+    ("bed_mesh_probe_distance", OptionType::Point),
+    ("adaptive_bed_mesh_margin", OptionType::Float),
+    ("enable_long_retraction_when_cut", OptionType::Int),
+    ("z_hop_types", OptionType::Enums),
+    ("travel_slope", OptionType::Floats),
+    ("retract_lift_enforce", OptionType::Enums),
+    ("long_retractions_when_cut", OptionType::Bools),
+    ("retraction_distances_when_cut", OptionType::Floats),
+    ("retract_restart_extra", OptionType::Floats),
+    ("retract_restart_extra_toolchange", OptionType::Floats),
+    ("extruder_offset", OptionType::Points),
+    ("min_layer_height", OptionType::Floats),
+    ("deretraction_speed", OptionType::Floats),
+    ("retraction_speed", OptionType::Floats),
+    ("z_hop", OptionType::Floats),
+    ("wipe", OptionType::Bools),
+    ("retract_before_wipe", OptionType::Percents),
+    ("retract_when_changing_layer", OptionType::Bools),
+    ("retraction_length", OptionType::Floats),
+    ("extruder_colour", OptionType::Strings),
+    ("retraction_minimum_travel", OptionType::Floats),
+    ("max_layer_height", OptionType::Floats),
+    ("retract_length_toolchange", OptionType::Floats),
+    ("nozzle_diameter", OptionType::Floats),
+    ("retract_on_top_layer", OptionType::Bools),
+    ("retract_lift_above", OptionType::Floats),
+    ("retract_lift_below", OptionType::Floats),
+    ("wipe_distance", OptionType::Floats),
+    ("machine_min_travel_rate", OptionType::Floats),
+    ("machine_max_acceleration_x", OptionType::Floats),
+    ("machine_max_acceleration_y", OptionType::Floats),
+    ("machine_max_acceleration_z", OptionType::Floats),
+    ("machine_max_acceleration_e", OptionType::Floats),
+    ("machine_max_acceleration_extruding", OptionType::Floats),
+    ("machine_max_acceleration_retracting", OptionType::Floats),
+    ("machine_max_acceleration_travel", OptionType::Floats),
+    ("machine_min_extruding_rate", OptionType::Floats),
+    ("machine_max_speed_x", OptionType::Floats),
+    ("machine_max_speed_y", OptionType::Floats),
+    ("machine_max_speed_z", OptionType::Floats),
+    ("machine_max_speed_e", OptionType::Floats),
+    ("machine_max_jerk_x", OptionType::Floats),
+    ("machine_max_jerk_y", OptionType::Floats),
+    ("machine_max_jerk_z", OptionType::Floats),
+    ("machine_max_jerk_e", OptionType::Floats),
+    ("machine_start_gcode", OptionType::String),
+    ("machine_end_gcode", OptionType::String),
+    ("before_layer_change_gcode", OptionType::String),
+    ("printing_by_object_gcode", OptionType::String),
+    ("layer_change_gcode", OptionType::String),
+    ("time_lapse_gcode", OptionType::String),
+    ("change_filament_gcode", OptionType::String),
+    ("change_extrusion_role_gcode", OptionType::String),
+    ("machine_pause_gcode", OptionType::String),
+    ("template_custom_gcode", OptionType::String),
+];
+
+/// Options of printer model presets.
+pub const PRINTER_MODEL_OPTIONS: &[(&str, OptionType)] = &[
+    ("name", OptionType::String),
+    ("type", OptionType::String),
+    ("version", OptionType::String),
+    ("url", OptionType::String),
+    ("model_id", OptionType::String),
+    ("nozzle_diameter", OptionType::String),
+    ("family", OptionType::String),
+    ("machine_tech", OptionType::Enum),
+    ("bed_model", OptionType::String),
+    ("bed_texture", OptionType::String),
+    ("hotend_model", OptionType::String),
+    ("default_materials", OptionType::String),
+];
+
+/// Options of filament presets.
+pub const FILAMENT_OPTIONS: &[(&str, OptionType)] = &[
+    ("name", OptionType::String),
+    ("type", OptionType::String),
+    ("version", OptionType::String),
+    ("from", OptionType::Enum),
+    ("instantiation", OptionType::Bool),
+    ("inherits", OptionType::String),
+    ("default_filament_colour", OptionType::Strings),
+    ("required_nozzle_HRC", OptionType::Ints),
+    ("filament_diameter", OptionType::Floats),
+    ("pellet_flow_coefficient", OptionType::Floats),
+    ("filament_type", OptionType::Strings),
+    ("filament_soluble", OptionType::Bools),
+    ("filament_is_support", OptionType::Bools),
+    ("filament_max_volumetric_speed", OptionType::Floats),
+    ("filament_flow_ratio", OptionType::Floats),
+    ("filament_density", OptionType::Floats),
+    ("filament_cost", OptionType::Floats),
+    ("filament_minimal_purge_on_wipe_tower", OptionType::Floats),
+    ("nozzle_temperature", OptionType::Ints),
+    ("nozzle_temperature_initial_layer", OptionType::Ints),
+    ("cool_plate_temp", OptionType::Ints),
+    ("textured_cool_plate_temp", OptionType::Ints),
+    ("eng_plate_temp", OptionType::Ints),
+    ("hot_plate_temp", OptionType::Ints),
+    ("textured_plate_temp", OptionType::Ints),
+    ("cool_plate_temp_initial_layer", OptionType::Ints),
+    ("textured_cool_plate_temp_initial_layer", OptionType::Ints),
+    ("eng_plate_temp_initial_layer", OptionType::Ints),
+    ("hot_plate_temp_initial_layer", OptionType::Ints),
+    ("textured_plate_temp_initial_layer", OptionType::Ints),
+    ("supertack_plate_temp_initial_layer", OptionType::Ints),
+    ("supertack_plate_temp", OptionType::Ints),
+    ("temperature_vitrification", OptionType::Ints),
+    ("reduce_fan_stop_start_freq", OptionType::Bools),
+    ("dont_slow_down_outer_wall", OptionType::Bools),
+    ("slow_down_for_layer_cooling", OptionType::Bools),
+    ("fan_min_speed", OptionType::Floats),
+    ("fan_max_speed", OptionType::Floats),
+    ("enable_overhang_bridge_fan", OptionType::Bools),
+    ("overhang_fan_speed", OptionType::Ints),
+    ("overhang_fan_threshold", OptionType::Enums),
+    ("close_fan_the_first_x_layers", OptionType::Ints),
+    ("full_fan_speed_layer", OptionType::Ints),
+    ("fan_cooling_layer_time", OptionType::Floats),
+    ("slow_down_layer_time", OptionType::Floats),
+    ("slow_down_min_speed", OptionType::Floats),
+    ("filament_start_gcode", OptionType::Strings),
+    ("filament_end_gcode", OptionType::Strings),
+    ("activate_air_filtration", OptionType::Bools),
+    ("during_print_exhaust_fan_speed", OptionType::Ints),
+    ("complete_print_exhaust_fan_speed", OptionType::Ints),
+    ("filament_retraction_length", OptionType::Floats),
+    ("filament_z_hop", OptionType::Floats),
+    ("filament_z_hop_types", OptionType::Enums),
+    ("filament_retract_lift_above", OptionType::Floats),
+    ("filament_retract_lift_below", OptionType::Floats),
+    ("filament_retract_lift_enforce", OptionType::Enums),
+    ("filament_retraction_speed", OptionType::Floats),
+    ("filament_deretraction_speed", OptionType::Floats),
+    ("filament_retract_restart_extra", OptionType::Floats),
+    ("filament_retraction_minimum_travel", OptionType::Floats),
+    ("filament_retract_when_changing_layer", OptionType::Bools),
+    ("filament_wipe", OptionType::Bools),
+    ("filament_retract_before_wipe", OptionType::Percents),
+    ("helio_filament_id", OptionType::String),
+    ("filament_vendor", OptionType::Strings),
+    ("compatible_prints", OptionType::Strings),
+    ("compatible_prints_condition", OptionType::String),
+    ("compatible_printers", OptionType::Strings),
+    ("compatible_printers_condition", OptionType::String),
+    ("filament_wipe_distance", OptionType::Floats),
+    ("additional_cooling_fan_speed", OptionType::Ints),
+    ("nozzle_temperature_range_low", OptionType::Ints),
+    ("nozzle_temperature_range_high", OptionType::Ints),
+    ("enable_pressure_advance", OptionType::Bools),
+    ("pressure_advance", OptionType::Floats),
+    ("adaptive_pressure_advance", OptionType::Bools),
+    ("adaptive_pressure_advance_model", OptionType::Strings),
+    ("adaptive_pressure_advance_overhangs", OptionType::Bools),
+    ("adaptive_pressure_advance_bridges", OptionType::Floats),
+    ("chamber_temperature", OptionType::Ints),
+    ("filament_shrink", OptionType::Percents),
+    ("filament_shrinkage_compensation_z", OptionType::Percents),
+    ("support_material_interface_fan_speed", OptionType::Ints),
+    ("internal_bridge_fan_speed", OptionType::Ints),
+    ("filament_notes", OptionType::Strings),
+    ("filament_seam_gap", OptionType::None),
+    ("filament_loading_speed", OptionType::Floats),
+    ("filament_loading_speed_start", OptionType::Floats),
+    ("filament_unloading_speed", OptionType::Floats),
+    ("filament_unloading_speed_start", OptionType::Floats),
+    ("filament_toolchange_delay", OptionType::Floats),
+    ("filament_cooling_moves", OptionType::Ints),
+    ("filament_stamping_loading_speed", OptionType::Floats),
+    ("filament_stamping_distance", OptionType::Floats),
+    ("filament_cooling_initial_speed", OptionType::Floats),
+    ("filament_cooling_final_speed", OptionType::Floats),
+    ("filament_ramming_parameters", OptionType::Strings),
+    ("filament_multitool_ramming", OptionType::Bools),
+    ("filament_multitool_ramming_volume", OptionType::Floats),
+    ("filament_multitool_ramming_flow", OptionType::Floats),
+    ("activate_chamber_temp_control", OptionType::Bools),
+    ("filament_long_retractions_when_cut", OptionType::Bools),
+    ("filament_retraction_distances_when_cut", OptionType::Floats),
+    ("idle_temperature", OptionType::Ints),
+    ("filament_id", OptionType::String),
+    ("filament_settings_id", OptionType::Strings),
+    ("setting_id", OptionType::String),
+];
+
+/// Options of process presets.
+pub const PROCESS_OPTIONS: &[(&str, OptionType)] = &[
+    ("name", OptionType::String),
+    ("type", OptionType::String),
+    ("version", OptionType::String),
+    ("from", OptionType::Enum),
+    ("instantiation", OptionType::Bool),
+    ("inherits", OptionType::String),
+    ("layer_height", OptionType::Float),
+    ("initial_layer_print_height", OptionType::Float),
+    ("wall_loops", OptionType::Int),
+    ("alternate_extra_wall", OptionType::Bool),
+    ("slice_closing_radius", OptionType::Float),
+    ("spiral_mode", OptionType::Bool),
+    ("spiral_mode_smooth", OptionType::Bool),
+    ("spiral_mode_max_xy_smoothing", OptionType::FloatOrPercent),
+    ("spiral_starting_flow_ratio", OptionType::Float),
+    ("spiral_finishing_flow_ratio", OptionType::Float),
+    ("slicing_mode", OptionType::Enum),
+    ("top_shell_layers", OptionType::Int),
+    ("top_shell_thickness", OptionType::Float),
+    ("bottom_shell_layers", OptionType::Int),
+    ("bottom_shell_thickness", OptionType::Float),
+    ("extra_perimeters_on_overhangs", OptionType::Bool),
+    ("ensure_vertical_shell_thickness", OptionType::Enum),
+    ("reduce_crossing_wall", OptionType::Bool),
+    ("detect_thin_wall", OptionType::Bool),
+    ("detect_overhang_wall", OptionType::Bool),
+    ("overhang_reverse", OptionType::Bool),
+    ("overhang_reverse_threshold", OptionType::FloatOrPercent),
+    ("overhang_reverse_internal_only", OptionType::Bool),
+    ("wall_direction", OptionType::Enum),
+    ("seam_position", OptionType::Enum),
+    ("staggered_inner_seams", OptionType::Bool),
+    ("wall_sequence", OptionType::Enum),
+    ("is_infill_first", OptionType::Bool),
+    ("sparse_infill_density", OptionType::Percent),
+    ("sparse_infill_pattern", OptionType::Enum),
+    ("lattice_angle_1", OptionType::Float),
+    ("lattice_angle_2", OptionType::Float),
+    ("top_surface_pattern", OptionType::Enum),
+    ("bottom_surface_pattern", OptionType::Enum),
+    ("infill_direction", OptionType::Float),
+    ("solid_infill_direction", OptionType::Float),
+    ("rotate_solid_infill_direction", OptionType::Bool),
+    ("counterbore_hole_bridging", OptionType::Enum),
+    ("minimum_sparse_infill_area", OptionType::Float),
+    ("reduce_infill_retraction", OptionType::Bool),
+    ("internal_solid_infill_pattern", OptionType::Enum),
+    ("gap_fill_target", OptionType::Enum),
+    ("ironing_type", OptionType::Enum),
+    ("ironing_pattern", OptionType::Enum),
+    ("ironing_flow", OptionType::Percent),
+    ("ironing_speed", OptionType::Float),
+    ("ironing_spacing", OptionType::Float),
+    ("ironing_angle", OptionType::Float),
+    ("ironing_inset", OptionType::Float),
+    ("max_travel_detour_distance", OptionType::FloatOrPercent),
+    ("fuzzy_skin", OptionType::Enum),
+    ("fuzzy_skin_thickness", OptionType::Float),
+    ("fuzzy_skin_point_distance", OptionType::Float),
+    ("fuzzy_skin_first_layer", OptionType::Bool),
+    ("fuzzy_skin_noise_type", OptionType::Enum),
+    ("fuzzy_skin_scale", OptionType::Float),
+    ("fuzzy_skin_octaves", OptionType::Int),
+    ("fuzzy_skin_persistence", OptionType::Float),
+    ("max_volumetric_extrusion_rate_slope", OptionType::Float),
+    (
+        "max_volumetric_extrusion_rate_slope_segment_length",
+        OptionType::Float,
+    ),
+    (
+        "extrusion_rate_smoothing_external_perimeter_only",
+        OptionType::Bool,
+    ),
+    ("inner_wall_speed", OptionType::Float),
+    ("outer_wall_speed", OptionType::Float),
+    ("sparse_infill_speed", OptionType::Float),
+    ("internal_solid_infill_speed", OptionType::Float),
+    ("top_surface_speed", OptionType::Float),
+    ("support_speed", OptionType::Float),
+    ("support_object_xy_distance", OptionType::Float),
+    ("support_object_first_layer_gap", OptionType::Float),
+    ("support_interface_speed", OptionType::Float),
+    ("bridge_speed", OptionType::Float),
+    ("internal_bridge_speed", OptionType::FloatOrPercent),
+    ("gap_infill_speed", OptionType::Float),
+    ("travel_speed", OptionType::Float),
+    ("travel_speed_z", OptionType::Float),
+    ("initial_layer_speed", OptionType::Float),
+    ("outer_wall_acceleration", OptionType::Float),
+    ("initial_layer_acceleration", OptionType::Float),
+    ("top_surface_acceleration", OptionType::Float),
+    ("default_acceleration", OptionType::Float),
+    ("skirt_type", OptionType::Enum),
+    ("skirt_loops", OptionType::Int),
+    ("skirt_speed", OptionType::Float),
+    ("min_skirt_length", OptionType::Float),
+    ("skirt_distance", OptionType::Float),
+    ("skirt_start_angle", OptionType::Float),
+    ("skirt_height", OptionType::Int),
+    ("draft_shield", OptionType::Enum),
+    ("brim_width", OptionType::Float),
+    ("brim_object_gap", OptionType::Float),
+    ("brim_type", OptionType::Enum),
+    ("brim_ears_max_angle", OptionType::Float),
+    ("brim_ears_detection_length", OptionType::Float),
+    ("enable_support", OptionType::Bool),
+    ("support_type", OptionType::Enum),
+    ("support_threshold_angle", OptionType::Int),
+    ("support_threshold_overlap", OptionType::FloatOrPercent),
+    ("enforce_support_layers", OptionType::Int),
+    ("raft_layers", OptionType::Int),
+    ("raft_first_layer_density", OptionType::Percent),
+    ("raft_first_layer_expansion", OptionType::Float),
+    ("raft_contact_distance", OptionType::Float),
+    ("raft_expansion", OptionType::Float),
+    ("support_base_pattern", OptionType::Enum),
+    ("support_base_pattern_spacing", OptionType::Float),
+    ("support_expansion", OptionType::Float),
+    ("support_style", OptionType::Enum),
+    ("independent_support_layer_height", OptionType::Bool),
+    ("support_angle", OptionType::Float),
+    ("support_interface_top_layers", OptionType::Int),
+    ("support_interface_bottom_layers", OptionType::Int),
+    ("support_interface_pattern", OptionType::Enum),
+    ("support_interface_spacing", OptionType::Float),
+    ("support_interface_loop_pattern", OptionType::Bool),
+    ("support_top_z_distance", OptionType::Float),
+    ("support_on_build_plate_only", OptionType::Bool),
+    ("support_critical_regions_only", OptionType::Bool),
+    ("bridge_no_support", OptionType::Bool),
+    ("thick_bridges", OptionType::Bool),
+    ("thick_internal_bridges", OptionType::Bool),
+    ("dont_filter_internal_bridges", OptionType::Enum),
+    ("enable_extra_bridge_layer", OptionType::Enum),
+    ("max_bridge_length", OptionType::Float),
+    ("print_sequence", OptionType::Enum),
+    ("print_order", OptionType::Enum),
+    ("support_remove_small_overhang", OptionType::Bool),
+    ("filename_format", OptionType::String),
+    ("wall_filament", OptionType::Int),
+    ("support_bottom_z_distance", OptionType::Float),
+    ("sparse_infill_filament", OptionType::Int),
+    ("solid_infill_filament", OptionType::Int),
+    ("support_filament", OptionType::Int),
+    ("support_interface_filament", OptionType::Int),
+    ("support_interface_not_for_body", OptionType::Bool),
+    ("ooze_prevention", OptionType::Bool),
+    ("standby_temperature_delta", OptionType::Int),
+    ("preheat_time", OptionType::Float),
+    ("preheat_steps", OptionType::Int),
+    ("interface_shells", OptionType::Bool),
+    ("line_width", OptionType::FloatOrPercent),
+    ("initial_layer_line_width", OptionType::FloatOrPercent),
+    ("inner_wall_line_width", OptionType::FloatOrPercent),
+    ("outer_wall_line_width", OptionType::FloatOrPercent),
+    ("sparse_infill_line_width", OptionType::FloatOrPercent),
+    (
+        "internal_solid_infill_line_width",
+        OptionType::FloatOrPercent,
+    ),
+    ("top_surface_line_width", OptionType::FloatOrPercent),
+    ("support_line_width", OptionType::FloatOrPercent),
+    ("infill_wall_overlap", OptionType::Percent),
+    ("top_bottom_infill_wall_overlap", OptionType::Percent),
+    ("bridge_flow", OptionType::Float),
+    ("internal_bridge_flow", OptionType::Float),
+    ("elefant_foot_compensation", OptionType::Float),
+    ("elefant_foot_compensation_layers", OptionType::Int),
+    ("xy_contour_compensation", OptionType::Float),
+    ("xy_hole_compensation", OptionType::Float),
+    ("resolution", OptionType::Float),
+    ("enable_prime_tower", OptionType::Bool),
+    ("prime_tower_width", OptionType::Float),
+    ("prime_tower_brim_width", OptionType::Float),
+    ("prime_volume", OptionType::Float),
+    ("wipe_tower_no_sparse_layers", OptionType::Bool),
+    ("compatible_printers", OptionType::Strings),
+    ("compatible_printers_condition", OptionType::String),
+    ("flush_into_infill", OptionType::Bool),
+    ("flush_into_objects", OptionType::Bool),
+    ("flush_into_support", OptionType::Bool),
+    ("tree_support_branch_angle", OptionType::Float),
+    ("tree_support_angle_slow", OptionType::Float),
+    ("tree_support_wall_count", OptionType::Int),
+    ("tree_support_top_rate", OptionType::Percent),
+    ("tree_support_branch_distance", OptionType::Float),
+    ("tree_support_tip_diameter", OptionType::Float),
+    ("tree_support_branch_diameter", OptionType::Float),
+    ("tree_support_branch_diameter_angle", OptionType::Float),
+    ("detect_narrow_internal_solid_infill", OptionType::Bool),
+    ("gcode_add_line_number", OptionType::Bool),
+    ("enable_arc_fitting", OptionType::Bool),
+    ("precise_z_height", OptionType::Bool),
+    ("infill_combination", OptionType::Bool),
+    (
+        "infill_combination_max_layer_height",
+        OptionType::FloatOrPercent,
+    ),
+    ("adaptive_layer_height", OptionType::None),
+    ("support_bottom_interface_spacing", OptionType::Float),
+    ("enable_overhang_speed", OptionType::Bool),
+    ("slowdown_for_curled_perimeters", OptionType::Bool),
+    ("overhang_1_4_speed", OptionType::FloatOrPercent),
+    ("overhang_2_4_speed", OptionType::FloatOrPercent),
+    ("overhang_3_4_speed", OptionType::FloatOrPercent),
+    ("overhang_4_4_speed", OptionType::FloatOrPercent),
+    ("initial_layer_infill_speed", OptionType::Float),
+    ("only_one_wall_top", OptionType::Bool),
+    ("timelapse_type", OptionType::Enum),
+    ("wall_generator", OptionType::Enum),
+    ("wall_transition_length", OptionType::Percent),
+    ("wall_transition_filter_deviation", OptionType::Percent),
+    ("wall_transition_angle", OptionType::Float),
+    ("wall_distribution_count", OptionType::Int),
+    ("min_feature_size", OptionType::Percent),
+    ("min_bead_width", OptionType::Percent),
+    ("post_process", OptionType::Strings),
+    ("min_length_factor", OptionType::Float),
+    ("small_perimeter_speed", OptionType::FloatOrPercent),
+    ("small_perimeter_threshold", OptionType::Float),
+    ("bridge_angle", OptionType::Float),
+    ("internal_bridge_angle", OptionType::Float),
+    ("filter_out_gap_fill", OptionType::Float),
+    ("travel_acceleration", OptionType::Float),
+    ("inner_wall_acceleration", OptionType::Float),
+    ("min_width_top_surface", OptionType::FloatOrPercent),
+    ("default_jerk", OptionType::Float),
+    ("outer_wall_jerk", OptionType::Float),
+    ("inner_wall_jerk", OptionType::Float),
+    ("infill_jerk", OptionType::Float),
+    ("top_surface_jerk", OptionType::Float),
+    ("initial_layer_jerk", OptionType::Float),
+    ("travel_jerk", OptionType::Float),
+    ("top_solid_infill_flow_ratio", OptionType::Float),
+    ("bottom_solid_infill_flow_ratio", OptionType::Float),
+    ("only_one_wall_first_layer", OptionType::Bool),
+    ("print_flow_ratio", OptionType::Float),
+    ("seam_gap", OptionType::FloatOrPercent),
+    ("role_based_wipe_speed", OptionType::Bool),
+    ("wipe_speed", OptionType::FloatOrPercent),
+    ("accel_to_decel_enable", OptionType::Bool),
+    ("accel_to_decel_factor", OptionType::Percent),
+    ("wipe_on_loops", OptionType::Bool),
+    ("wipe_before_external_loop", OptionType::Bool),
+    ("bridge_density", OptionType::Percent),
+    ("internal_bridge_density", OptionType::Percent),
+    ("precise_outer_wall", OptionType::Bool),
+    ("overhang_speed_classic", OptionType::Bool),
+    ("bridge_acceleration", OptionType::FloatOrPercent),
+    ("sparse_infill_acceleration", OptionType::FloatOrPercent),
+    (
+        "internal_solid_infill_acceleration",
+        OptionType::FloatOrPercent,
+    ),
+    ("tree_support_adaptive_layer_height", OptionType::Bool),
+    ("tree_support_auto_brim", OptionType::Bool),
+    ("tree_support_brim_width", OptionType::Float),
+    ("gcode_comments", OptionType::Bool),
+    ("gcode_label_objects", OptionType::Bool),
+    ("initial_layer_travel_speed", OptionType::FloatOrPercent),
+    ("exclude_object", OptionType::Bool),
+    ("slow_down_layers", OptionType::Int),
+    ("infill_anchor", OptionType::FloatOrPercent),
+    ("infill_anchor_max", OptionType::FloatOrPercent),
+    ("initial_layer_min_bead_width", OptionType::Percent),
+    ("make_overhang_printable", OptionType::Bool),
+    ("make_overhang_printable_angle", OptionType::Float),
+    ("make_overhang_printable_hole_size", OptionType::Float),
+    ("notes", OptionType::String),
+    ("wipe_tower_cone_angle", OptionType::Float),
+    ("wipe_tower_extra_spacing", OptionType::Percent),
+    ("wipe_tower_max_purge_speed", OptionType::Float),
+    ("wipe_tower_filament", OptionType::Int),
+    ("wiping_volumes_extruders", OptionType::Floats),
+    ("wipe_tower_bridging", OptionType::Float),
+    ("wipe_tower_extra_flow", OptionType::Percent),
+    ("single_extruder_multi_material_priming", OptionType::Bool),
+    ("wipe_tower_rotation_angle", OptionType::Float),
+    ("tree_support_branch_distance_organic", OptionType::Float),
+    ("tree_support_branch_diameter_organic", OptionType::Float),
+    ("tree_support_branch_angle_organic", OptionType::Float),
+    ("hole_to_polyhole", OptionType::Bool),
+    ("hole_to_polyhole_threshold", OptionType::FloatOrPercent),
+    ("hole_to_polyhole_twisted", OptionType::Bool),
+    ("mmu_segmented_region_max_width", OptionType::Float),
+    ("mmu_segmented_region_interlocking_depth", OptionType::Float),
+    ("small_area_infill_flow_compensation", OptionType::Bool),
+    (
+        "small_area_infill_flow_compensation_model",
+        OptionType::Strings,
+    ),
+    ("seam_slope_type", OptionType::Enum),
+    ("seam_slope_conditional", OptionType::Bool),
+    ("scarf_angle_threshold", OptionType::Int),
+    ("scarf_joint_speed", OptionType::FloatOrPercent),
+    ("scarf_joint_flow_ratio", OptionType::Float),
+    ("seam_slope_start_height", OptionType::FloatOrPercent),
+    ("seam_slope_entire_loop", OptionType::Bool),
+    ("seam_slope_min_length", OptionType::Float),
+    ("seam_slope_steps", OptionType::Int),
+    ("seam_slope_inner_walls", OptionType::Bool),
+    ("scarf_overhang_threshold", OptionType::Percent),
+    ("interlocking_beam", OptionType::Bool),
+    ("interlocking_orientation", OptionType::Float),
+    ("interlocking_beam_layer_count", OptionType::Int),
+    ("interlocking_depth", OptionType::Int),
+    ("interlocking_boundary_avoidance", OptionType::Int),
+    ("interlocking_beam_width", OptionType::Float),
+    ("print_settings_id", OptionType::String),
+    ("setting_id", OptionType::String),
+];
+
+/// Options of vendor files.
+pub const VENDOR_OPTIONS: &[(&str, OptionType)] = &[
+    ("name", OptionType::String),
+    ("version", OptionType::String),
+    ("description", OptionType::String),
+    ("force_update", OptionType::Bool),
+    ("machine_model_list", OptionType::NameAndSubpaths),
+    ("process_list", OptionType::NameAndSubpaths),
+    ("filament_list", OptionType::NameAndSubpaths),
+    ("machine_list", OptionType::NameAndSubpaths),
+];
+
+/// Bookkeeping keys OrcaSlicer writes into presets of every type.
+pub const PRESET_METADATA_KEYS: &[&str] = &[
+    "base_id",
+    "description",
+    "is_custom_defined",
+    "renamed_from",
+    "updated_time",
+    "user_id",
+];
+
+/// The options of the given config type, as named by the frontend.
+pub fn section_options(config_type: &str) -> Option<&'static [(&'static str, OptionType)]> {
+    match config_type {
+        "printer" => Some(PRINTER_OPTIONS),
+        "printer-model" => Some(PRINTER_MODEL_OPTIONS),
+        "filament" => Some(FILAMENT_OPTIONS),
+        "process" => Some(PROCESS_OPTIONS),
+        "vendor" => Some(VENDOR_OPTIONS),
+        _ => None,
+    }
+}
+
+pub fn find_option(options: &[(&str, OptionType)], key: &str) -> Option<OptionType> {
+    options
+        .iter()
+        .find(|(id, _)| *id == key)
+        .map(|(_, option_type)| *option_type)
+}
+
+/// The config types whose options include `key`.
+pub fn config_types_of_key(key: &str) -> Vec<&'static str> {
+    ["printer", "printer-model", "filament", "process", "vendor"]
+        .into_iter()
+        .filter(|config_type| {
+            section_options(config_type)
+                .map(|options| find_option(options, key).is_some())
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// The type of `key` in configs of `config_type`, falling back to the registry wide list.
+pub fn option_type_of(config_type: &str, key: &str) -> Option<OptionType> {
+    section_options(config_type)
+        .and_then(|options| find_option(options, key))
+        .or_else(|| find_option(ALL_OPTIONS, key))
+}
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use ts_rs::TS;
+
+use crate::workspace::resolve_path;
+
+const WHITEOUT_EXTENSION: &str = "deleted";
+
+/// A read-only installation directory and the writable directory that takes
+/// the changes made to it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OverlaySettings {
+    pub base_directory: String,
+    pub overlay_directory: String,
+}
+
+struct OverlayRoots {
+    base: PathBuf,
+    overlay: PathBuf,
+}
+
+// Every loader and writer resolves paths through the overlay, and the loaders
+// are plain functions called from everywhere, so it is kept process wide.
+static OVERLAY: RwLock<Option<OverlayRoots>> = RwLock::new(None);
+
+fn resolve_directory(directory: &str) -> Result<PathBuf, String> {
+    fs::canonicalize(directory)
+        .map_err(|err| format!("'{}' cannot be resolved: {}", directory, err))
+}
+
+/// Turns the overlay on, or off without settings. The overlay directory is
+/// created when missing.
+pub fn set_overlay(settings: Option<OverlaySettings>) -> Result<(), String> {
+    let Some(settings) = settings else {
+        *OVERLAY.write().unwrap() = None;
+        return Ok(());
+    };
+
+    fs::create_dir_all(&settings.overlay_directory).map_err(|err| {
+        format!(
+            "Could not create the overlay directory '{}': {}",
+            settings.overlay_directory, err
+        )
+    })?;
+
+    let base = resolve_directory(&settings.base_directory)?;
+    let overlay = resolve_directory(&settings.overlay_directory)?;
+
+    if overlay.starts_with(&base) || base.starts_with(&overlay) {
+        return Err(format!(
+            "The overlay directory '{}' must not be inside of '{}' or contain it",
+            settings.overlay_directory, settings.base_directory
+        ));
+    }
+
+    *OVERLAY.write().unwrap() = Some(OverlayRoots { base, overlay });
+    Ok(())
+}
+
+pub fn overlay_directory() -> Option<PathBuf> {
+    OVERLAY
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|roots| roots.overlay.clone())
+}
+
+/// Where the changes to `path` go, `None` when it is not below the base
+/// directory or there is no overlay.
+pub fn overlay_counterpart(path: &Path) -> Option<PathBuf> {
+    let overlay = OVERLAY.read().unwrap();
+    let roots = overlay.as_ref()?;
+
+    let resolved = resolve_path(path).ok()?;
+    let relative_path = resolved.strip_prefix(&roots.base).ok()?;
+
+    Some(roots.overlay.join(relative_path))
+}
+
+/// The marker hiding a base file that was deleted through the overlay.
+pub fn whiteout_path(overlay_path: &Path) -> PathBuf {
+    let mut whiteout = overlay_path.as_os_str().to_owned();
+    whiteout.push(".");
+    whiteout.push(WHITEOUT_EXTENSION);
+    PathBuf::from(whiteout)
+}
+
+fn is_whiteout(path: &Path) -> bool {
+    path.extension().and_then(|extension| extension.to_str()) == Some(WHITEOUT_EXTENSION)
+}
+
+/// The file to read for `path`: its overlay version when there is one. A file
+/// deleted through the overlay resolves to a path that does not exist.
+pub fn read_path(path: &Path) -> PathBuf {
+    match overlay_counterpart(path) {
+        Some(overlay_path) if overlay_path.exists() || whiteout_path(&overlay_path).is_file() => {
+            overlay_path
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The file to write for `path`.
+pub fn write_path(path: &Path) -> PathBuf {
+    overlay_counterpart(path).unwrap_or(path.to_path_buf())
+}
+
+/// Lists `directory` with `list`, merged with its overlay version. The files
+/// are returned below `directory`, without the deleted ones.
+pub fn merged_listing(directory: &Path, list: impl Fn(&Path) -> Vec<PathBuf>) -> Vec<PathBuf> {
+    let Some(overlay_directory) = overlay_counterpart(directory) else {
+        return list(directory);
+    };
+
+    let overlay_files: Vec<PathBuf> = list(&overlay_directory)
+        .into_iter()
+        .filter_map(|file| {
+            file.strip_prefix(&overlay_directory)
+                .ok()
+                .map(Path::to_path_buf)
+        })
+        .collect();
+
+    let mut files: Vec<PathBuf> = list(directory)
+        .into_iter()
+        .filter_map(|file| file.strip_prefix(directory).ok().map(Path::to_path_buf))
+        .filter(|file| !overlay_directory.join(whiteout_path(file)).is_file())
+        .chain(overlay_files.into_iter().filter(|file| !is_whiteout(file)))
+        .map(|file| directory.join(file))
+        .collect();
+
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn copy_tree(source: &Path, target: &Path) -> Result<(), String> {
+    let entries = fs::read_dir(source)
+        .map_err(|err| format!("Could not read '{}': {}", source.display(), err))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let target_path = target.join(entry.file_name());
+
+        if path.is_dir() {
+            fs::create_dir_all(&target_path)
+                .map_err(|err| format!("Could not create '{}': {}", target_path.display(), err))?;
+            copy_tree(&path, &target_path)?;
+        } else if is_whiteout(&path) {
+            // hides the copy of the base file
+            let hidden_path = target_path.with_extension("");
+            if hidden_path.is_file() {
+                fs::remove_file(&hidden_path).map_err(|err| {
+                    format!("Could not delete '{}': {}", hidden_path.display(), err)
+                })?;
+            }
+        } else {
+            fs::copy(&path, &target_path)
+                .map_err(|err| format!("Could not copy '{}': {}", path.display(), err))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the base directory with the overlay applied to `target` as a
+/// complete tree, ready to replace the installation.
+pub fn materialize(target: &Path) -> Result<(), String> {
+    let (base, overlay) = {
+        let roots = OVERLAY.read().unwrap();
+        let roots = roots.as_ref().ok_or("There is no overlay to materialize")?;
+        (roots.base.clone(), roots.overlay.clone())
+    };
+
+    let resolved_target = resolve_path(target)?;
+    if resolved_target.starts_with(&base) || resolved_target.starts_with(&overlay) {
+        return Err(format!(
+            "'{}' must not be inside of the installation or the overlay directory",
+            target.display()
+        ));
+    }
+
+    if target.exists()
+        && fs::read_dir(target)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(true)
+    {
+        return Err(format!("'{}' must be an empty directory", target.display()));
+    }
+
+    fs::create_dir_all(target)
+        .map_err(|err| format!("Could not create '{}': {}", target.display(), err))?;
+
+    // the base first, the overlay replaces and hides its files
+    copy_tree(&base, target)?;
+    copy_tree(&overlay, target)
+}
@@ -0,0 +1,421 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::config_inheritance::{detach_from_parent, vendor_list_key};
+use crate::json_edit::edited_json;
+use crate::name_references::name_field;
+use crate::overlay::read_path;
+use crate::preset_rename::{LocatedConfig, PresetLocations, USER_PRESET_INFO_EXTENSION};
+use crate::vendor_duplication::{config_lists, to_edited_json};
+use crate::vendor_plan::{ReferenceChange, VendorPlan, VendorProblem};
+
+// Keys naming a preset another one cannot exist without
+const PARENT_KEYS: [&str; 2] = ["inherits", "printer_model"];
+
+/// How a preset other presets depend on is deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum DeleteStrategy {
+    /// Refuse while any preset names it.
+    Block,
+    /// Delete the presets inheriting from it too, down to the last
+    /// descendant, and drop the deleted names from the presets naming them.
+    Cascade,
+    /// Give the values its children inherit from it to the children, which
+    /// then inherit from its parent. The other presets naming it drop the
+    /// name.
+    Reparent,
+}
+
+/// A vendor list entry or preset naming a preset that is deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PresetDependent {
+    pub path: String,
+    pub name: String,
+    /// The config type of the dependent, `vendor` for a vendor list entry.
+    pub config_type: String,
+    /// The vendor directory listing the dependent, `None` for user presets.
+    pub vendor: Option<String>,
+    /// The keys naming the preset, or the vendor list holding its entry.
+    pub keys: Vec<String>,
+    /// The preset named: the deleted one, or a preset inheriting from it.
+    pub references: String,
+}
+
+/// Everything that depends on a preset, and on the presets a cascading
+/// delete would remove with it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DeleteImpact {
+    pub name: String,
+    pub config_type: String,
+    /// The files of the preset in every copy of its vendor.
+    pub paths: Vec<String>,
+    pub dependents: Vec<PresetDependent>,
+}
+
+// A preset that goes, with its definitions in every copy of its vendor
+struct DeletedPreset<'a> {
+    vendor: Option<&'a str>,
+    config_type: &'static str,
+    name: &'a str,
+    copies: Vec<&'a LocatedConfig>,
+}
+
+impl DeletedPreset<'_> {
+    fn contains(&self, config: &LocatedConfig) -> bool {
+        self.copies.iter().any(|copy| copy.path == config.path)
+    }
+}
+
+fn deleted_preset<'a>(
+    locations: &'a PresetLocations,
+    config: &'a LocatedConfig,
+) -> DeletedPreset<'a> {
+    let vendor = locations.vendor_name(config);
+    let copies = match vendor {
+        Some(vendor) => locations
+            .configs
+            .iter()
+            .filter(|other| {
+                locations.vendor_name(other) == Some(vendor)
+                    && other.config_type == config.config_type
+                    && other.name == config.name
+            })
+            .collect(),
+        None => vec![config],
+    };
+
+    DeletedPreset {
+        vendor,
+        config_type: config.config_type,
+        name: &config.name,
+        copies,
+    }
+}
+
+// `config` with every preset inheriting from it, or of a printer model the
+// printers of the model
+fn descendants<'a>(
+    locations: &'a PresetLocations,
+    config: &'a LocatedConfig,
+) -> Vec<DeletedPreset<'a>> {
+    let mut deleted = vec![deleted_preset(locations, config)];
+
+    let mut next = 0;
+    while next < deleted.len() {
+        let (vendor, config_type, name) = {
+            let preset = &deleted[next];
+            (preset.vendor, preset.config_type, preset.name)
+        };
+
+        for other in &locations.configs {
+            if deleted.iter().any(|preset| preset.contains(other)) {
+                continue;
+            }
+            let keys = locations.references(other, vendor, config_type, name);
+            if keys.iter().any(|key| PARENT_KEYS.contains(key)) {
+                deleted.push(deleted_preset(locations, other));
+            }
+        }
+        next += 1;
+    }
+
+    deleted
+}
+
+fn dependents(locations: &PresetLocations, deleted: &[DeletedPreset]) -> Vec<PresetDependent> {
+    let mut dependents = Vec::new();
+
+    for preset in deleted {
+        for copy in &preset.copies {
+            let (Some(index), Some(list_key)) = (copy.vendor, vendor_list_key(preset.config_type))
+            else {
+                continue;
+            };
+            let vendor = &locations.vendors[index];
+
+            dependents.push(PresetDependent {
+                path: vendor.vendor_file.to_string_lossy().to_string(),
+                name: vendor.name.clone(),
+                config_type: "vendor".into(),
+                vendor: Some(vendor.name.clone()),
+                keys: vec![list_key.into()],
+                references: preset.name.into(),
+            });
+        }
+
+        // the presets deleted with it depend on it too
+        for other in &locations.configs {
+            if deleted[0].contains(other) {
+                continue;
+            }
+            let keys = locations.references(other, preset.vendor, preset.config_type, preset.name);
+            if keys.is_empty() {
+                continue;
+            }
+
+            dependents.push(PresetDependent {
+                path: other.path.to_string_lossy().to_string(),
+                name: other.name.clone(),
+                config_type: other.config_type.into(),
+                vendor: locations.vendor_name(other).map(String::from),
+                keys: keys.into_iter().map(String::from).collect(),
+                references: preset.name.into(),
+            });
+        }
+    }
+
+    dependents
+}
+
+/// Lists what depends on the preset at `path`, of `config_type`, in the
+/// installation at `profiles_directory` and the `system` and `user` folders
+/// of `data_directory`: the vendor list entries, the presets inheriting from
+/// it and the presets naming it, like in `compatible_printers`. The presets
+/// a cascading delete removes with it are listed with their own dependents.
+pub fn preset_delete_impact(
+    path: &Path,
+    config_type: &str,
+    profiles_directory: Option<&Path>,
+    data_directory: Option<&Path>,
+) -> Result<DeleteImpact, Vec<VendorProblem>> {
+    let locations = PresetLocations::load(profiles_directory, data_directory);
+    let config = locations.locate(path, config_type)?;
+    let deleted = descendants(&locations, config);
+
+    Ok(DeleteImpact {
+        name: config.name.clone(),
+        config_type: config_type.into(),
+        paths: deleted[0]
+            .copies
+            .iter()
+            .map(|copy| copy.path.to_string_lossy().to_string())
+            .collect(),
+        dependents: dependents(&locations, &deleted),
+    })
+}
+
+// Removes every copy of the deleted presets, with the metadata of user
+// presets and the covers of printer models
+fn plan_definitions(
+    plan: &mut VendorPlan,
+    locations: &PresetLocations,
+    deleted: &[DeletedPreset],
+    problems: &mut Vec<VendorProblem>,
+) {
+    for preset in deleted {
+        for copy in &preset.copies {
+            let mut paths = vec![copy.path.clone()];
+            match copy.vendor {
+                None => paths.push(copy.path.with_extension(USER_PRESET_INFO_EXTENSION)),
+                Some(index) if preset.config_type == "printer-model" => paths.push(
+                    locations.vendors[index]
+                        .directory
+                        .join(format!("{}_cover.png", preset.name)),
+                ),
+                Some(_) => {}
+            }
+
+            for (position, path) in paths.iter().enumerate() {
+                // only the preset itself has to exist
+                if position > 0 && !read_path(path).is_file() {
+                    continue;
+                }
+                if let Err(problem) = plan.delete_file(path) {
+                    problems.push(problem);
+                }
+            }
+        }
+    }
+
+    for (index, vendor) in locations.vendors.iter().enumerate() {
+        let listed = |config_type: &str, name: &str| {
+            deleted.iter().any(|preset| {
+                preset.config_type == config_type
+                    && preset.name == name
+                    && preset.copies.iter().any(|copy| copy.vendor == Some(index))
+            })
+        };
+
+        let mut config = vendor.config.clone();
+        let mut changed = false;
+        for (config_type, list) in config_lists(&mut config) {
+            if let Some(list) = list {
+                let length = list.len();
+                list.retain(|entry| !listed(config_type, &entry.name));
+                changed |= list.len() != length;
+            }
+        }
+        if !changed {
+            continue;
+        }
+
+        let planned = to_edited_json(&vendor.data, &config)
+            .map_err(|err| VendorProblem::new(&vendor.vendor_file, err))
+            .and_then(|content| plan.modify_file(&vendor.vendor_file, content, Some("vendor")));
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+    }
+}
+
+// Drops the deleted names from the presets that stay, re-parenting the
+// children of the first deleted preset with `reparent`
+fn plan_dependents(
+    plan: &mut VendorPlan,
+    locations: &PresetLocations,
+    deleted: &[DeletedPreset],
+    reparent: bool,
+    problems: &mut Vec<VendorProblem>,
+) {
+    for other in &locations.configs {
+        if deleted.iter().any(|preset| preset.contains(other)) {
+            continue;
+        }
+
+        let mut values = other.values.clone();
+        let mut changed = false;
+
+        for (position, preset) in deleted.iter().enumerate() {
+            let keys = locations.references(other, preset.vendor, preset.config_type, preset.name);
+
+            for key in keys {
+                changed = true;
+
+                if reparent && position == 0 && key == "inherits" {
+                    // the copy of the parent the child resolves to
+                    let parent = preset
+                        .copies
+                        .iter()
+                        .find(|copy| other.vendor.is_some() && copy.vendor == other.vendor)
+                        .or(preset.copies.last());
+                    let Some(parent) = parent else {
+                        continue;
+                    };
+
+                    detach_from_parent(&mut values, &parent.values);
+                    plan.references.push(ReferenceChange {
+                        path: other.path.to_string_lossy().to_string(),
+                        key: key.into(),
+                        old_value: preset.name.into(),
+                        new_value: values
+                            .get(key)
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .into(),
+                    });
+                    continue;
+                }
+
+                if PARENT_KEYS.contains(&key) {
+                    problems.push(VendorProblem::new(
+                        &other.path,
+                        format!(
+                            "'{}' cannot keep its {} '{}' once it is deleted",
+                            other.name, key, preset.name
+                        ),
+                    ));
+                    continue;
+                }
+
+                let Some(field) = name_field(other.config_type, key) else {
+                    continue;
+                };
+                if let Err(err) = field.retain(&mut values, |name| name != preset.name) {
+                    problems.push(VendorProblem::new(&other.path, err));
+                    continue;
+                }
+                plan.references.push(ReferenceChange {
+                    path: other.path.to_string_lossy().to_string(),
+                    key: key.into(),
+                    old_value: preset.name.into(),
+                    new_value: String::new(),
+                });
+            }
+        }
+
+        if !changed {
+            continue;
+        }
+
+        // user presets are not validated, like in the other vendor operations
+        let validated_type = other.vendor.map(|_| other.config_type);
+        let planned = edited_json(Some(&other.data), &Value::Object(values))
+            .map_err(|err| VendorProblem::new(&other.path, err))
+            .and_then(|content| plan.modify_file(&other.path, content, validated_type));
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+    }
+}
+
+/// Plans deleting the preset at `path`, of `config_type`, from every copy of
+/// its vendor, or from the user folder, the way `strategy` says. Nothing is
+/// written, every problem found is returned; a blocked delete returns the
+/// presets depending on it.
+pub fn plan_delete_preset(
+    path: &Path,
+    config_type: &str,
+    strategy: DeleteStrategy,
+    profiles_directory: Option<&Path>,
+    data_directory: Option<&Path>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let locations = PresetLocations::load(profiles_directory, data_directory);
+    let config = locations.locate(path, config_type)?;
+
+    let deleted = match strategy {
+        DeleteStrategy::Cascade => descendants(&locations, config),
+        DeleteStrategy::Block | DeleteStrategy::Reparent => {
+            vec![deleted_preset(&locations, config)]
+        }
+    };
+
+    if strategy == DeleteStrategy::Block {
+        let problems: Vec<VendorProblem> = dependents(&locations, &deleted)
+            .into_iter()
+            .filter(|dependent| dependent.config_type != "vendor")
+            .map(|dependent| {
+                VendorProblem::new(
+                    Path::new(&dependent.path),
+                    format!(
+                        "'{}' names '{}' in {}",
+                        dependent.name,
+                        dependent.references,
+                        dependent.keys.join(", ")
+                    ),
+                )
+            })
+            .collect();
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+    }
+
+    let mut plan = VendorPlan::new(
+        "delete_preset",
+        format!("Delete {} preset '{}'", config_type, config.name),
+        &locations.plan_root(config, data_directory),
+    );
+    let mut problems = Vec::new();
+
+    plan_definitions(&mut plan, &locations, &deleted, &mut problems);
+    plan_dependents(
+        &mut plan,
+        &locations,
+        &deleted,
+        strategy == DeleteStrategy::Reparent,
+        &mut problems,
+    );
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    plan.diff = plan.render_diff();
+    Ok(plan)
+}
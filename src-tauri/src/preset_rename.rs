@@ -0,0 +1,553 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::configuration_loader::{get_all_json_files, ConfigNameAndPath, VendorJsonSchema};
+use crate::json_edit::edited_json;
+use crate::name_references::{name_fields, NameTarget};
+use crate::overlay::read_path;
+use crate::vendor_duplication::{
+    config_lists, new_sub_path, to_edited_json, user_preset_files, SYSTEM_DIRECTORY, USER_DIRECTORY,
+};
+use crate::vendor_merge::load_vendor;
+use crate::vendor_plan::{PresetRename, ReferenceChange, VendorPlan, VendorProblem};
+
+// OrcaSlicer keeps the metadata of a user preset next to it
+pub const USER_PRESET_INFO_EXTENSION: &str = "info";
+
+/// A renamed preset: where it is now and everything the rename changed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RenamedPreset {
+    pub path: String,
+    pub plan: VendorPlan,
+}
+
+/// The edited content a preset is saved with when it is renamed, with the
+/// token of the file it was loaded from.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PresetEdit {
+    pub content: String,
+    pub expected_token: Option<String>,
+}
+
+/// A vendor of the installation, or of the `system` folder of the data
+/// directory.
+pub struct LoadedVendor {
+    /// The name of the vendor directory.
+    pub name: String,
+    pub vendor_file: PathBuf,
+    pub directory: PathBuf,
+    pub data: String,
+    pub config: VendorJsonSchema,
+}
+
+impl LoadedVendor {
+    pub fn list(&self, config_type: &str) -> &[ConfigNameAndPath] {
+        let list = match config_type {
+            "printer" => &self.config.machine_list,
+            "printer-model" => &self.config.machine_model_list,
+            "filament" => &self.config.filament_list,
+            "process" => &self.config.process_list,
+            _ => &None,
+        };
+
+        list.as_deref().unwrap_or_default()
+    }
+
+    pub fn find(&self, config_type: &str, name: &str) -> Option<&ConfigNameAndPath> {
+        self.list(config_type)
+            .iter()
+            .find(|entry| entry.name == name)
+    }
+}
+
+/// A preset read from one of the locations, with the index of the vendor
+/// listing it. User presets have no vendor.
+pub struct LocatedConfig {
+    pub config_type: &'static str,
+    pub name: String,
+    pub path: PathBuf,
+    pub vendor: Option<usize>,
+    pub data: String,
+    pub values: Map<String, Value>,
+}
+
+/// Every vendor and preset of the installation and the data directory.
+pub struct PresetLocations {
+    pub vendors: Vec<LoadedVendor>,
+    pub configs: Vec<LocatedConfig>,
+}
+
+fn read_values(path: &Path) -> Option<(String, Map<String, Value>)> {
+    let data = fs::read_to_string(read_path(path)).ok()?;
+    let values = serde_json::from_str(&data).ok()?;
+    Some((data, values))
+}
+
+impl PresetLocations {
+    /// Reads the vendors of `profiles_directory` and of the `system` folder of
+    /// `data_directory`, with the presets they list, and the user presets of
+    /// every account. Files that cannot be read are left to the analysers.
+    pub fn load(profiles_directory: Option<&Path>, data_directory: Option<&Path>) -> Self {
+        let mut locations = PresetLocations {
+            vendors: Vec::new(),
+            configs: Vec::new(),
+        };
+
+        let system_directory = data_directory.map(|directory| directory.join(SYSTEM_DIRECTORY));
+        let vendor_files = profiles_directory
+            .into_iter()
+            .chain(system_directory.as_deref())
+            .flat_map(|directory| {
+                get_all_json_files(&directory.to_string_lossy()).unwrap_or_default()
+            });
+
+        for vendor_file in vendor_files.map(PathBuf::from) {
+            let Ok((data, mut config)) = load_vendor(&vendor_file) else {
+                continue;
+            };
+            let directory = vendor_file.with_extension("");
+            let vendor = locations.vendors.len();
+
+            for (config_type, list) in config_lists(&mut config) {
+                for entry in list.iter().flatten() {
+                    let path = directory.join(&entry.sub_path);
+                    let Some((data, values)) = read_values(&path) else {
+                        continue;
+                    };
+
+                    locations.configs.push(LocatedConfig {
+                        config_type,
+                        name: entry.name.clone(),
+                        path,
+                        vendor: Some(vendor),
+                        data,
+                        values,
+                    });
+                }
+            }
+
+            locations.vendors.push(LoadedVendor {
+                name: directory
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                vendor_file,
+                directory,
+                data,
+                config,
+            });
+        }
+
+        for (config_type, path) in data_directory.map(user_preset_files).unwrap_or_default() {
+            let Some((data, values)) = read_values(&path) else {
+                continue;
+            };
+            let name = values
+                .get("name")
+                .and_then(Value::as_str)
+                .map(String::from)
+                .unwrap_or_default();
+
+            locations.configs.push(LocatedConfig {
+                config_type,
+                name,
+                path,
+                vendor: None,
+                data,
+                values,
+            });
+        }
+
+        locations
+    }
+
+    /// The located preset at `path`.
+    pub fn find_path(&self, path: &Path) -> Option<&LocatedConfig> {
+        self.configs.iter().find(|config| config.path == path)
+    }
+
+    /// The located `config_type` preset at `path`, a problem if there is none.
+    pub fn locate(
+        &self,
+        path: &Path,
+        config_type: &str,
+    ) -> Result<&LocatedConfig, Vec<VendorProblem>> {
+        self.find_path(path)
+            .filter(|config| config.config_type == config_type)
+            .ok_or_else(|| {
+                vec![VendorProblem::new(
+                    path,
+                    format!(
+                        "This is not a {} preset of a vendor or of a user",
+                        config_type
+                    ),
+                )]
+            })
+    }
+
+    /// The name of the vendor directory listing `config`, `None` for user
+    /// presets.
+    pub fn vendor_name(&self, config: &LocatedConfig) -> Option<&str> {
+        config.vendor.map(|index| self.vendors[index].name.as_str())
+    }
+
+    /// The directory a plan changing `config` works in: the profiles folder
+    /// of its vendor, or the user folder.
+    pub fn plan_root(&self, config: &LocatedConfig, data_directory: Option<&Path>) -> PathBuf {
+        match config.vendor {
+            Some(index) => self.vendors[index]
+                .vendor_file
+                .parent()
+                .unwrap_or(&config.path)
+                .to_path_buf(),
+            None => data_directory
+                .map(|directory| directory.join(USER_DIRECTORY))
+                .unwrap_or(config.path.clone()),
+        }
+    }
+
+    /// Whether `config` names the `config_type` preset `name` of `vendor`,
+    /// or the user preset when `vendor` is `None`. Within a vendor names point
+    /// at its own presets, the presets it does not have come from the other
+    /// vendors, like the filaments of the filament library. Vendors never name
+    /// user presets.
+    pub fn refers_to(
+        &self,
+        config: &LocatedConfig,
+        vendor: Option<&str>,
+        config_type: &str,
+        name: &str,
+    ) -> bool {
+        match (config.vendor.map(|index| &self.vendors[index]), vendor) {
+            (Some(own_vendor), Some(vendor)) => {
+                own_vendor.name == vendor || own_vendor.find(config_type, name).is_none()
+            }
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+
+    /// The name fields of `config` holding the `config_type` preset `name`
+    /// of `vendor`.
+    pub fn references(
+        &self,
+        config: &LocatedConfig,
+        vendor: Option<&str>,
+        config_type: &str,
+        name: &str,
+    ) -> Vec<&'static str> {
+        if !self.refers_to(config, vendor, config_type, name) {
+            return Vec::new();
+        }
+
+        name_fields(config.config_type)
+            .filter(|field| field.target_type(config.config_type) == Some(config_type))
+            .filter(|field| {
+                field
+                    .names(&config.values)
+                    .is_ok_and(|names| names.iter().any(|other| other == name))
+            })
+            .map(|field| field.key)
+            .collect()
+    }
+}
+
+// The content of the renamed preset. Its `*_settings_id` fields name the
+// preset itself, the references of other configs never reach them
+fn renamed_content(data: &str, rename: &PresetRename) -> Result<String, String> {
+    let mut values: Map<String, Value> =
+        serde_json::from_str(data).map_err(|err| format!("Malformed or invalid JSON: {}", err))?;
+    values.insert("name".into(), Value::String(rename.new_name.clone()));
+
+    for field in
+        name_fields(&rename.config_type).filter(|field| field.target == NameTarget::OwnType)
+    {
+        field.rename(&mut values, |name| {
+            if name == rename.old_name {
+                rename.new_name.clone()
+            } else {
+                name.to_string()
+            }
+        })?;
+    }
+
+    edited_json(Some(data), &Value::Object(values))
+}
+
+// Moves the listed preset `old_name` of every copy of the vendor to the new
+// name, with the cover of a printer model
+fn plan_vendor_definitions(
+    plan: &mut VendorPlan,
+    locations: &PresetLocations,
+    vendor: &str,
+    rename: &PresetRename,
+    edited: Option<(&Path, &str)>,
+    problems: &mut Vec<VendorProblem>,
+) {
+    let (config_type, old_name, new_name) = (
+        rename.config_type.as_str(),
+        rename.old_name.as_str(),
+        rename.new_name.as_str(),
+    );
+
+    for loaded_vendor in locations
+        .vendors
+        .iter()
+        .filter(|other| other.name == vendor)
+    {
+        let Some(entry) = loaded_vendor.find(config_type, old_name) else {
+            continue;
+        };
+        let old_path = loaded_vendor.directory.join(&entry.sub_path);
+        let sub_path = new_sub_path(&entry.sub_path, new_name);
+        let new_path = loaded_vendor.directory.join(&sub_path);
+
+        if loaded_vendor.find(config_type, new_name).is_some() {
+            problems.push(VendorProblem::new(
+                &loaded_vendor.vendor_file,
+                format!(
+                    "The vendor already has a {} preset named '{}'",
+                    config_type, new_name
+                ),
+            ));
+            continue;
+        }
+
+        let data = match edited.filter(|(edited_path, _)| *edited_path == old_path) {
+            Some((_, content)) => Ok(content.to_string()),
+            None => fs::read_to_string(read_path(&old_path)),
+        };
+        let planned = data
+            .map_err(|err| VendorProblem::new(&old_path, err.to_string()))
+            .and_then(|data| {
+                renamed_content(&data, rename).map_err(|err| VendorProblem::new(&old_path, err))
+            })
+            .and_then(|content| {
+                plan.create_file(&new_path, Some(&old_path), Some(content), Some(config_type))
+            })
+            .and_then(|_| plan.delete_file(&old_path));
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+
+        if config_type == "printer-model" {
+            let old_cover = loaded_vendor
+                .directory
+                .join(format!("{}_cover.png", old_name));
+            let new_cover = loaded_vendor
+                .directory
+                .join(format!("{}_cover.png", new_name));
+
+            if read_path(&old_cover).is_file() {
+                let planned = plan
+                    .create_file(&new_cover, Some(&old_cover), None, None)
+                    .and_then(|_| plan.delete_file(&old_cover));
+                if let Err(problem) = planned {
+                    problems.push(problem);
+                }
+            }
+        }
+
+        let mut config = loaded_vendor.config.clone();
+        for (list_type, list) in config_lists(&mut config) {
+            if list_type != config_type {
+                continue;
+            }
+            for entry in list.iter_mut().flatten() {
+                if entry.name == old_name {
+                    entry.name = new_name.into();
+                    entry.sub_path = sub_path.clone();
+                }
+            }
+        }
+
+        let planned = to_edited_json(&loaded_vendor.data, &config)
+            .map_err(|err| VendorProblem::new(&loaded_vendor.vendor_file, err))
+            .and_then(|content| {
+                plan.modify_file(&loaded_vendor.vendor_file, content, Some("vendor"))
+            });
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+    }
+}
+
+// Renames the user preset at `path`, with its metadata file
+fn plan_user_definition(
+    plan: &mut VendorPlan,
+    config: &LocatedConfig,
+    rename: &PresetRename,
+    content: Option<&str>,
+    problems: &mut Vec<VendorProblem>,
+) {
+    let new_path = config
+        .path
+        .with_file_name(format!("{}.json", rename.new_name));
+
+    let planned = renamed_content(content.unwrap_or(&config.data), rename)
+        .map_err(|err| VendorProblem::new(&config.path, err))
+        .and_then(|content| {
+            plan.create_file(
+                &new_path,
+                Some(&config.path),
+                Some(content),
+                Some(config.config_type),
+            )
+        })
+        .and_then(|_| plan.delete_file(&config.path));
+    if let Err(problem) = planned {
+        problems.push(problem);
+    }
+
+    let old_info = config.path.with_extension(USER_PRESET_INFO_EXTENSION);
+    if read_path(&old_info).is_file() {
+        let new_info = new_path.with_extension(USER_PRESET_INFO_EXTENSION);
+        let planned = plan
+            .create_file(&new_info, Some(&old_info), None, None)
+            .and_then(|_| plan.delete_file(&old_info));
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+    }
+}
+
+/// Plans renaming the preset at `path`, of `config_type`, to `new_name`
+/// together with every reference to it in the installation at
+/// `profiles_directory` and the `system` and `user` folders of
+/// `data_directory`. A vendor preset is renamed in every copy of its vendor
+/// and in the vendor lists, a user preset next to its metadata. With
+/// `content` the preset at `path` is saved with it under the new name, so
+/// that an edit and a rename are one change. Nothing is written, every
+/// problem found is returned.
+pub fn plan_rename_preset(
+    path: &Path,
+    config_type: &str,
+    new_name: &str,
+    content: Option<&str>,
+    profiles_directory: Option<&Path>,
+    data_directory: Option<&Path>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    if new_name.trim().is_empty() || new_name.contains(['/', '\\']) {
+        return Err(vec![VendorProblem::new(
+            path,
+            format!("'{}' is not a valid preset name", new_name),
+        )]);
+    }
+
+    let locations = PresetLocations::load(profiles_directory, data_directory);
+    let config = locations.locate(path, config_type)?;
+    let old_name = config.name.clone();
+    if old_name == new_name {
+        return Err(vec![VendorProblem::new(
+            path,
+            format!("The preset is named '{}' already", new_name),
+        )]);
+    }
+
+    let vendor = locations.vendor_name(config).map(String::from);
+    let root = locations.plan_root(config, data_directory);
+
+    let mut plan = VendorPlan::new(
+        "rename_preset",
+        format!(
+            "Rename {} preset '{}' to '{}'",
+            config_type, old_name, new_name
+        ),
+        &root,
+    );
+    let mut problems = Vec::new();
+    let rename = PresetRename {
+        config_type: config_type.into(),
+        old_name: old_name.clone(),
+        new_name: new_name.into(),
+    };
+
+    match &vendor {
+        Some(vendor) => plan_vendor_definitions(
+            &mut plan,
+            &locations,
+            vendor,
+            &rename,
+            content.map(|content| (path, content)),
+            &mut problems,
+        ),
+        None => {
+            let taken = locations.configs.iter().any(|other| {
+                other.vendor.is_none()
+                    && other.config_type == config_type
+                    && other.name == new_name
+                    && other.path.parent() == path.parent()
+            });
+            if taken {
+                problems.push(VendorProblem::new(
+                    path,
+                    format!("A user preset named '{}' exists already", new_name),
+                ));
+            }
+            plan_user_definition(&mut plan, config, &rename, content, &mut problems);
+        }
+    }
+
+    plan.renames.push(rename);
+
+    let renamed_paths: Vec<PathBuf> = plan
+        .files
+        .iter()
+        .map(|file| PathBuf::from(&file.path))
+        .collect();
+
+    for other in &locations.configs {
+        if renamed_paths.contains(&other.path) {
+            continue;
+        }
+        let keys = locations.references(other, vendor.as_deref(), config_type, &old_name);
+        if keys.is_empty() {
+            continue;
+        }
+
+        let mut values = other.values.clone();
+        for key in keys {
+            let Some(field) = name_fields(other.config_type).find(|field| field.key == key) else {
+                continue;
+            };
+
+            let renamed = field.rename(&mut values, |name| {
+                if name != old_name {
+                    return name.to_string();
+                }
+
+                plan.references.push(ReferenceChange {
+                    path: other.path.to_string_lossy().to_string(),
+                    key: key.into(),
+                    old_value: old_name.clone(),
+                    new_value: new_name.into(),
+                });
+                new_name.to_string()
+            });
+            if let Err(err) = renamed {
+                problems.push(VendorProblem::new(&other.path, err));
+            }
+        }
+
+        // user presets are not validated, like in the other vendor operations
+        let validated_type = other.vendor.map(|_| other.config_type);
+        let planned = edited_json(Some(&other.data), &Value::Object(values))
+            .map_err(|err| VendorProblem::new(&other.path, err))
+            .and_then(|content| plan.modify_file(&other.path, content, validated_type));
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    plan.diff = plan.render_diff();
+    Ok(plan)
+}
@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::audit::AuditRecord;
+use crate::config_inheritance::vendor_list_key;
+use crate::file_store::{atomic_write, unix_millis, FileStore};
+use crate::journal::Operation;
+use crate::json_edit::edited_json;
+use crate::overlay::read_path;
+
+pub const TRASH_DIRECTORY: &str = "trash";
+const ENTRY_FILE: &str = "entry.json";
+const CONTENT_FILE: &str = "content";
+
+const LISTED_CONFIG_TYPES: [&str; 4] = ["printer-model", "printer", "filament", "process"];
+
+/// A vendor list entry that pointed at a deleted file, with its position so
+/// that a restore puts it back where it was.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VendorListEntry {
+    pub vendor_path: String,
+    pub list_key: String,
+    pub index: usize,
+    pub name: String,
+    pub sub_path: String,
+}
+
+/// A deleted file kept in the trash of the app.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TrashEntry {
+    pub id: String,
+    pub original_path: String,
+    pub name: Option<String>,
+    #[ts(type = "number")]
+    pub deleted_at: u64,
+    pub operation: String,
+    pub vendor_entries: Vec<VendorListEntry>,
+    #[ts(type = "number")]
+    pub size: u64,
+}
+
+fn io_error(action: &str, path: &Path, err: std::io::Error) -> String {
+    format!("Failed to {} '{}': {}", action, path.display(), err)
+}
+
+fn load_object(path: &Path) -> Result<(String, Map<String, Value>), String> {
+    let text = fs::read_to_string(read_path(path)).map_err(|err| io_error("read", path, err))?;
+    let object = serde_json::from_str(&text)
+        .map_err(|err| format!("Malformed JSON file '{}': {}", path.display(), err))?;
+
+    Ok((text, object))
+}
+
+// The vendor config listing `path`: `<vendor>.json` next to one of the
+// directories above it, with an entry whose sub_path leads to the file.
+fn find_vendor_entries(path: &Path) -> Vec<VendorListEntry> {
+    for directory in path.ancestors().skip(1) {
+        let (Some(parent), Some(family)) = (directory.parent(), directory.file_name()) else {
+            continue;
+        };
+        let vendor_path = parent.join(format!("{}.json", family.to_string_lossy()));
+        let Ok((_, vendor_config)) = load_object(&vendor_path) else {
+            continue;
+        };
+        let Ok(relative_path) = path.strip_prefix(directory) else {
+            continue;
+        };
+        let sub_path = relative_path.to_string_lossy().replace('\\', "/");
+
+        let entries: Vec<VendorListEntry> = LISTED_CONFIG_TYPES
+            .iter()
+            .filter_map(|config_type| vendor_list_key(config_type))
+            .flat_map(|list_key| {
+                let list = vendor_config.get(list_key).and_then(Value::as_array);
+
+                list.into_iter()
+                    .flatten()
+                    .enumerate()
+                    .filter(|(_, item)| {
+                        item.get("sub_path").and_then(Value::as_str) == Some(&sub_path)
+                    })
+                    .map(|(index, item)| VendorListEntry {
+                        vendor_path: vendor_path.to_string_lossy().to_string(),
+                        list_key: list_key.into(),
+                        index,
+                        name: item
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .into(),
+                        sub_path: sub_path.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        if !entries.is_empty() {
+            return entries;
+        }
+    }
+
+    Vec::new()
+}
+
+fn remove_vendor_entries(
+    operation: &mut Operation,
+    vendor_entries: &[VendorListEntry],
+) -> Result<(), String> {
+    let Some(first_entry) = vendor_entries.first() else {
+        return Ok(());
+    };
+
+    let vendor_path = Path::new(&first_entry.vendor_path);
+    let (text, mut vendor_config) = load_object(vendor_path)?;
+
+    // from the back, so that the indices stay valid
+    for entry in vendor_entries.iter().rev() {
+        if let Some(list) = vendor_config
+            .get_mut(&entry.list_key)
+            .and_then(Value::as_array_mut)
+        {
+            list.remove(entry.index);
+        }
+    }
+
+    let content = edited_json(Some(&text), &Value::Object(vendor_config))?;
+    operation.write(vendor_path, content.as_bytes())
+}
+
+fn restore_vendor_entries(
+    operation: &mut Operation,
+    vendor_entries: &[VendorListEntry],
+) -> Result<(), String> {
+    let Some(first_entry) = vendor_entries.first() else {
+        return Ok(());
+    };
+
+    let vendor_path = Path::new(&first_entry.vendor_path);
+    let (text, mut vendor_config) = load_object(vendor_path).map_err(|err| {
+        format!(
+            "The vendor config listing the file cannot be updated: {}",
+            err
+        )
+    })?;
+
+    for entry in vendor_entries {
+        let list = vendor_config
+            .entry(entry.list_key.clone())
+            .or_insert(Value::Array(Vec::new()));
+        let Some(list) = list.as_array_mut() else {
+            return Err(format!(
+                "'{}' of '{}' is not a list",
+                entry.list_key, entry.vendor_path
+            ));
+        };
+
+        let already_listed = list.iter().any(|item| {
+            item.get("sub_path").and_then(Value::as_str) == Some(entry.sub_path.as_str())
+        });
+        if !already_listed {
+            let mut item = Map::new();
+            item.insert("name".into(), Value::String(entry.name.clone()));
+            item.insert("sub_path".into(), Value::String(entry.sub_path.clone()));
+            list.insert(entry.index.min(list.len()), Value::Object(item));
+        }
+    }
+
+    let content = edited_json(Some(&text), &Value::Object(vendor_config))?;
+    operation.write(vendor_path, content.as_bytes())
+}
+
+impl FileStore {
+    fn trash_item_directory(&self, id: &str) -> Result<PathBuf, String> {
+        // ids come from the frontend, they must not leave the trash
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("'{}' is not a trash entry", id));
+        }
+
+        Ok(self.trash_directory.join(id))
+    }
+
+    /// Moves `path` into the trash as part of `operation`, together with the
+    /// vendor list entries pointing at it. On failure the operation is rolled
+    /// back, so that no vendor list loses an entry to a file that stays.
+    pub fn move_to_trash(
+        &self,
+        operation: &mut Operation,
+        path: &Path,
+        command: &str,
+    ) -> Result<TrashEntry, String> {
+        let path = &self.workspace.check_path(path)?;
+        let content = fs::read(read_path(path)).map_err(|err| io_error("read", path, err))?;
+
+        let mut deleted_at = unix_millis();
+        while self.trash_directory.join(deleted_at.to_string()).exists() {
+            deleted_at += 1;
+        }
+        let id = deleted_at.to_string();
+        let directory = self.trash_directory.join(&id);
+
+        let entry = TrashEntry {
+            id,
+            original_path: path.to_string_lossy().to_string(),
+            name: serde_json::from_slice::<Map<String, Value>>(&content)
+                .ok()
+                .and_then(|config| config.get("name")?.as_str().map(str::to_string)),
+            deleted_at,
+            operation: command.into(),
+            vendor_entries: find_vendor_entries(path),
+            size: content.len() as u64,
+        };
+
+        // the trash holds the file before anything is removed
+        let entry_json = serde_json::to_string_pretty(&entry)
+            .map_err(|err| format!("Failed to serialize the trash entry: {}", err))?;
+        let trash_res = fs::create_dir_all(&directory)
+            .map_err(|err| io_error("create", &directory, err))
+            .and_then(|_| atomic_write(&directory.join(CONTENT_FILE), &content))
+            .and_then(|_| atomic_write(&directory.join(ENTRY_FILE), entry_json.as_bytes()))
+            .and_then(|_| remove_vendor_entries(operation, &entry.vendor_entries))
+            .and_then(|_| operation.remove_file(path));
+
+        if let Err(err) = trash_res {
+            operation.rollback();
+            fs::remove_dir_all(&directory).ok();
+            return Err(err);
+        }
+
+        Ok(entry)
+    }
+
+    /// Everything in the trash, most recently deleted first.
+    pub fn list_trash(&self) -> Result<Vec<TrashEntry>, String> {
+        if !self.trash_directory.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let items = fs::read_dir(&self.trash_directory)
+            .map_err(|err| io_error("read", &self.trash_directory, err))?;
+
+        let mut entries: Vec<TrashEntry> = items
+            .filter_map(|item| {
+                let data = fs::read_to_string(item.ok()?.path().join(ENTRY_FILE)).ok()?;
+                serde_json::from_str(&data).ok()
+            })
+            .collect();
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+
+        Ok(entries)
+    }
+
+    /// Puts a deleted file back at its original path and its vendor list
+    /// entries back into the vendor config, as a journaled operation.
+    pub fn restore_from_trash(&self, id: &str) -> Result<TrashEntry, String> {
+        let directory = self.trash_item_directory(id)?;
+        let entry_path = directory.join(ENTRY_FILE);
+        let entry: TrashEntry = fs::read_to_string(&entry_path)
+            .map_err(|_| format!("There is no trash entry '{}'", id))
+            .and_then(|data| {
+                serde_json::from_str(&data)
+                    .map_err(|err| format!("Trash entry '{}' is damaged: {}", id, err))
+            })?;
+
+        let original_path = PathBuf::from(&entry.original_path);
+        if read_path(&original_path).exists() {
+            return Err(format!(
+                "'{}' exists again, move it away before restoring it",
+                entry.original_path
+            ));
+        }
+
+        let content_path = directory.join(CONTENT_FILE);
+        let content =
+            fs::read(&content_path).map_err(|err| io_error("read", &content_path, err))?;
+
+        self.record(
+            "restore_from_trash",
+            format!("Restore '{}' from the trash", entry.original_path),
+            |operation| {
+                let restore_res = operation
+                    .write(&original_path, &content)
+                    .and_then(|_| restore_vendor_entries(operation, &entry.vendor_entries));
+
+                if restore_res.is_err() {
+                    operation.rollback();
+                }
+                restore_res
+            },
+        )?;
+
+        fs::remove_dir_all(&directory).map_err(|err| io_error("delete", &directory, err))?;
+
+        Ok(entry)
+    }
+
+    /// Deletes the given trash entries for good, or all of them without ids.
+    pub fn purge_trash(&self, ids: Option<Vec<String>>) -> Result<(), String> {
+        let Some(ids) = ids else {
+            if self.trash_directory.is_dir() {
+                fs::remove_dir_all(&self.trash_directory)
+                    .map_err(|err| io_error("delete", &self.trash_directory, err))?;
+            }
+            return self.audit(AuditRecord::new("purge_trash", "Empty the trash".into()));
+        };
+
+        for id in &ids {
+            let directory = self.trash_item_directory(id)?;
+            if directory.is_dir() {
+                fs::remove_dir_all(&directory)
+                    .map_err(|err| io_error("delete", &directory, err))?;
+            }
+        }
+
+        self.audit(AuditRecord::new(
+            "purge_trash",
+            format!("Purge trash entries {}", ids.join(", ")),
+        ))
+    }
+}
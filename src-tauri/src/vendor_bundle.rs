@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use ts_rs::TS;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::commons::vendor_config_analysis;
+use crate::configuration_loader::{get_all_json_files, AnalysisMessageDetails};
+use crate::file_store::atomic_write;
+use crate::overlay::read_path;
+use crate::vendor_duplication::{config_lists, new_vendor_paths};
+use crate::vendor_merge::load_vendor;
+use crate::vendor_plan::{VendorPlan, VendorProblem};
+use crate::vendor_split::model_assets;
+
+// Files archivers add that are not part of a vendor
+const IGNORED_BUNDLE_FILES: [&str; 3] = ["__MACOSX", ".DS_Store", "Thumbs.db"];
+
+/// What importing a vendor bundle installed, with the findings of the
+/// analysis that did not stop it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BundleImport {
+    pub vendor: String,
+    pub plan: VendorPlan,
+    pub warnings: Vec<VendorProblem>,
+}
+
+/// A vendor bundle unpacked into a temporary directory, removed when dropped.
+pub struct StagedBundle {
+    pub vendor: String,
+    pub directory: PathBuf,
+    /// The unpacked files, relative to `directory`.
+    pub files: Vec<PathBuf>,
+}
+
+impl Drop for StagedBundle {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.directory).ok();
+    }
+}
+
+impl StagedBundle {
+    fn vendor_file(&self) -> PathBuf {
+        self.directory.join(format!("{}.json", self.vendor))
+    }
+}
+
+fn is_ignored(path: &Path) -> bool {
+    path.components().any(|component| {
+        IGNORED_BUNDLE_FILES
+            .iter()
+            .any(|ignored| component.as_os_str() == *ignored)
+    })
+}
+
+// The single `<Vendor>.json` at the root of the bundle
+fn bundle_vendor(zip_path: &Path, files: &[PathBuf]) -> Result<String, VendorProblem> {
+    let vendor_files: Vec<&PathBuf> = files
+        .iter()
+        .filter(|file| {
+            file.components().count() == 1
+                && file.extension().and_then(|extension| extension.to_str()) == Some("json")
+        })
+        .collect();
+
+    match vendor_files.as_slice() {
+        [vendor_file] => Ok(vendor_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()),
+        [] => Err(VendorProblem::new(
+            zip_path,
+            "The bundle contains no vendor config at its root".into(),
+        )),
+        _ => Err(VendorProblem::new(
+            zip_path,
+            "The bundle contains more than one vendor config at its root".into(),
+        )),
+    }
+}
+
+/// Unpacks the vendor bundle at `zip_path` into a temporary directory. The
+/// bundle has to hold one `<Vendor>.json` and a `<Vendor>/` directory only,
+/// entries leaving the bundle are refused.
+pub fn stage_bundle(zip_path: &Path) -> Result<StagedBundle, Vec<VendorProblem>> {
+    let problem = |message: String| vec![VendorProblem::new(zip_path, message)];
+
+    let file = File::open(zip_path)
+        .map_err(|err| problem(format!("Could not open the bundle: {}", err)))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|err| problem(format!("The bundle is not a valid zip archive: {}", err)))?;
+
+    let mut entries: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    let mut problems = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|err| problem(format!("Could not read the bundle: {}", err)))?;
+
+        if entry.is_dir() {
+            continue;
+        }
+
+        let Some(relative_path) = entry.enclosed_name() else {
+            problems.push(VendorProblem::new(
+                zip_path,
+                format!("'{}' points outside of the bundle", entry.name()),
+            ));
+            continue;
+        };
+        if is_ignored(&relative_path) {
+            continue;
+        }
+
+        let mut content = Vec::new();
+        if let Err(err) = entry.read_to_end(&mut content) {
+            problems.push(VendorProblem::new(
+                zip_path,
+                format!("Could not unpack '{}': {}", relative_path.display(), err),
+            ));
+            continue;
+        }
+        entries.push((relative_path, content));
+    }
+
+    let files: Vec<PathBuf> = entries.iter().map(|(path, _)| path.clone()).collect();
+    let vendor = bundle_vendor(zip_path, &files).map_err(|problem| vec![problem])?;
+    let vendor_file = PathBuf::from(format!("{}.json", vendor));
+
+    for file in &files {
+        let in_vendor_directory = matches!(
+            file.components().next(),
+            Some(Component::Normal(name)) if name == vendor.as_str()
+        );
+
+        if *file != vendor_file && !in_vendor_directory {
+            problems.push(VendorProblem::new(
+                zip_path,
+                format!("'{}' is not part of vendor '{}'", file.display(), vendor),
+            ));
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    let staged = StagedBundle {
+        directory: std::env::temp_dir().join(format!(
+            "vendor-bundle-{}-{}",
+            std::process::id(),
+            vendor
+        )),
+        vendor,
+        files,
+    };
+    fs::remove_dir_all(&staged.directory).ok();
+
+    for (relative_path, content) in entries {
+        let path = staged.directory.join(&relative_path);
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, content));
+
+        if let Err(err) = written {
+            return Err(problem(format!(
+                "Could not unpack '{}': {}",
+                relative_path.display(),
+                err
+            )));
+        }
+    }
+
+    Ok(staged)
+}
+
+// Analysis messages name the staged files, reports name where they are installed
+fn to_problems(
+    messages: HashMap<String, Vec<AnalysisMessageDetails>>,
+    staged: &StagedBundle,
+    profiles_directory: &Path,
+) -> Vec<VendorProblem> {
+    let staged_directory = staged.directory.to_string_lossy().to_string();
+    let installed_directory = profiles_directory.to_string_lossy().to_string();
+    let vendor_file = profiles_directory.join(format!("{}.json", staged.vendor));
+
+    let mut problems: Vec<VendorProblem> = messages
+        .into_iter()
+        .flat_map(|(key, details)| {
+            details
+                .into_iter()
+                .map(move |details| (key.clone(), details))
+        })
+        .map(|(key, details)| {
+            VendorProblem::new(
+                &vendor_file,
+                format!(
+                    "{}: {}",
+                    key,
+                    details
+                        .message
+                        .text
+                        .replace(&staged_directory, &installed_directory)
+                ),
+            )
+        })
+        .collect();
+
+    problems.sort_by(|left, right| left.message.cmp(&right.message));
+    problems
+}
+
+// Preset names listed by the vendors in `profiles_directory` other than
+// `vendor`, by config type
+fn installed_names(
+    profiles_directory: &Path,
+    vendor: &str,
+) -> HashMap<(&'static str, String), String> {
+    let mut names = HashMap::new();
+
+    for vendor_file in get_all_json_files(&profiles_directory.to_string_lossy()).unwrap_or_default()
+    {
+        let vendor_file = PathBuf::from(vendor_file);
+        let Some(installed_vendor) = vendor_file.file_stem().map(|stem| stem.to_string_lossy())
+        else {
+            continue;
+        };
+        let Ok((_, mut vendor_config)) = load_vendor(&vendor_file) else {
+            continue;
+        };
+        if installed_vendor == vendor {
+            continue;
+        }
+
+        for (config_type, list) in config_lists(&mut vendor_config) {
+            for entry in list.iter().flatten() {
+                names
+                    .entry((config_type, entry.name.clone()))
+                    .or_insert(installed_vendor.to_string());
+            }
+        }
+    }
+
+    names
+}
+
+// Presets that are not instantiated are only seen by their own vendor
+fn is_instantiated(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str::<Map<String, Value>>(&data).ok())
+        .and_then(|config| config.get("instantiation")?.as_str().map(String::from))
+        .is_none_or(|instantiation| instantiation != "false")
+}
+
+/// Plans installing the staged bundle into `profiles_directory`. The bundle
+/// is analysed like an installed vendor first: errors and presets named like
+/// the instantiated presets of another installed vendor stop the import,
+/// warnings are returned with the plan.
+pub fn plan_import_bundle(
+    staged: &StagedBundle,
+    profiles_directory: &Path,
+) -> Result<(VendorPlan, Vec<VendorProblem>), Vec<VendorProblem>> {
+    let staged_vendor_file = staged.vendor_file();
+    let (new_directory_path, new_file_path) = new_vendor_paths(
+        &profiles_directory.join(format!("{}.json", staged.vendor)),
+        &staged.vendor,
+    )
+    .map_err(|problem| vec![problem])?;
+
+    let (errors, warnings) = vendor_config_analysis(
+        staged_vendor_file.to_string_lossy().to_string(),
+        "installed".into(),
+        staged.vendor.clone(),
+    )
+    .map_err(|err| vec![VendorProblem::new(&new_file_path, err)])?;
+
+    let mut problems = to_problems(errors, staged, profiles_directory);
+    let mut warnings = to_problems(warnings, staged, profiles_directory);
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    let (_, mut vendor_config) =
+        load_vendor(&staged_vendor_file).map_err(|problem| vec![problem])?;
+    let staged_directory = staged.directory.join(&staged.vendor);
+
+    let mut config_types: HashMap<PathBuf, &str> = HashMap::new();
+    let installed_names = installed_names(profiles_directory, &staged.vendor);
+
+    for (config_type, list) in config_lists(&mut vendor_config) {
+        for entry in list.iter().flatten() {
+            let staged_path = staged_directory.join(&entry.sub_path);
+            config_types.insert(staged_path.clone(), config_type);
+
+            let Some(installed_vendor) = installed_names.get(&(config_type, entry.name.clone()))
+            else {
+                continue;
+            };
+            let collision = VendorProblem::new(
+                &new_directory_path.join(&entry.sub_path),
+                format!(
+                    "The {} preset '{}' is also installed by vendor '{}'",
+                    config_type, entry.name, installed_vendor
+                ),
+            );
+
+            if is_instantiated(&staged_path) {
+                problems.push(collision);
+            } else {
+                warnings.push(collision);
+            }
+        }
+    }
+
+    let mut plan = VendorPlan::new(
+        "import_vendor_bundle",
+        format!("Import vendor '{}'", staged.vendor),
+        profiles_directory,
+    );
+
+    for file in &staged.files {
+        let staged_path = staged.directory.join(file);
+        let config_type = if staged_path == staged_vendor_file {
+            Some("vendor")
+        } else {
+            config_types.get(&staged_path).copied()
+        };
+
+        if let Err(problem) = plan.create_file(
+            &profiles_directory.join(file),
+            Some(&staged_path),
+            None,
+            config_type,
+        ) {
+            problems.push(problem);
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    plan.diff = plan.render_diff();
+    Ok((plan, warnings))
+}
+
+/// The files of the vendor at `path` that make up its bundle, relative to the
+/// directory of the vendor config: the vendor config, every listed config and
+/// the covers, bed models, bed textures and hotend models of its printer
+/// models. A listed config or a named asset that is missing is a problem, a
+/// missing cover is left out.
+pub fn bundle_files(path: &Path) -> Result<Vec<PathBuf>, Vec<VendorProblem>> {
+    let (_, mut vendor_config) = load_vendor(path).map_err(|problem| vec![problem])?;
+    let vendor_directory = path.with_extension("");
+    let vendor_name = vendor_directory
+        .file_name()
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    let mut files = vec![PathBuf::from(path.file_name().unwrap_or_default())];
+    let mut problems = Vec::new();
+    let push_file = |files: &mut Vec<PathBuf>, sub_path: &str| {
+        let file = vendor_name.join(sub_path);
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    };
+
+    for (config_type, list) in config_lists(&mut vendor_config) {
+        for entry in list.iter().flatten() {
+            let config_path = vendor_directory.join(&entry.sub_path);
+
+            let Ok(data) = fs::read_to_string(read_path(&config_path)) else {
+                problems.push(VendorProblem::new(
+                    &config_path,
+                    format!("'{}' is listed but does not exist", entry.sub_path),
+                ));
+                continue;
+            };
+            push_file(&mut files, &entry.sub_path);
+
+            if config_type != "printer-model" {
+                continue;
+            }
+            let Ok(values) = serde_json::from_str::<Map<String, Value>>(&data) else {
+                continue;
+            };
+            let cover = format!("{}_cover.png", entry.name);
+
+            for asset in model_assets(&values) {
+                if read_path(&vendor_directory.join(&asset)).is_file() {
+                    push_file(&mut files, &asset);
+                } else if asset != cover {
+                    problems.push(VendorProblem::new(
+                        &config_path,
+                        format!("The asset '{}' does not exist", asset),
+                    ));
+                }
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(files)
+    } else {
+        Err(problems)
+    }
+}
+
+/// Writes the bundle of the vendor at `path` to `zip_path`, laid out like the
+/// profiles directory so that it can be unpacked into it as it is. Returns the
+/// archived files.
+pub fn write_bundle(path: &Path, zip_path: &Path) -> Result<Vec<String>, Vec<VendorProblem>> {
+    let files = bundle_files(path)?;
+    let profiles_directory = path.parent().unwrap_or(path);
+    let problem = |err: String| vec![VendorProblem::new(zip_path, err)];
+
+    // built in memory and written in one go, a failed export leaves no
+    // truncated archive behind
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    let entries: Vec<String> = files
+        .iter()
+        .map(|file| file.to_string_lossy().replace('\\', "/"))
+        .collect();
+
+    for (file, entry) in files.iter().zip(&entries) {
+        let content = fs::read(read_path(&profiles_directory.join(file)))
+            .map_err(|err| problem(format!("Could not read '{}': {}", entry, err)))?;
+
+        zip.start_file(entry.as_str(), options)
+            .map_err(|err| problem(format!("Zip start_file error: {err}")))?;
+        zip.write_all(&content)
+            .map_err(|err| problem(format!("Zip write error: {err}")))?;
+    }
+
+    let zip_content = zip
+        .finish()
+        .map_err(|err| problem(format!("Zip finish error: {err}")))?
+        .into_inner();
+    atomic_write(zip_path, &zip_content).map_err(problem)?;
+
+    Ok(entries)
+}
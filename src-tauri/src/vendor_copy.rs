@@ -0,0 +1,288 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commons::replace_name;
+use crate::configuration_loader::ConfigNameAndPath;
+use crate::overlay::read_path;
+use crate::vendor_duplication::{config_lists, new_sub_path, to_edited_json, Rewrite};
+use crate::vendor_merge::{directory_name, extend_in_load_order, load_vendor, parent_name};
+use crate::vendor_plan::{PresetRename, VendorPlan, VendorProblem};
+
+// A config of the source vendor that is copied, with its name in the target
+struct CopiedConfig {
+    config_type: &'static str,
+    entry: ConfigNameAndPath,
+    data: String,
+    new_name: String,
+}
+
+/// Plans copying the configs at `config_paths`, listed by the vendor at
+/// `source_path`, into the vendor at `target_path`, with every ancestor they
+/// inherit from that the target lacks. The copies are named the way the
+/// target vendor names its presets, and the names they reference follow
+/// them, or the counterparts the target has already. The target lists keep
+/// a valid load order. Nothing is written, every problem found is returned.
+pub fn plan_copy_configs_to_vendor(
+    source_path: &Path,
+    config_paths: &[PathBuf],
+    target_path: &Path,
+    orca_filament_library_filaments: &HashSet<String>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let source_directory = source_path.with_extension("");
+    let target_directory = target_path.with_extension("");
+
+    if source_directory == target_directory {
+        return Err(vec![VendorProblem::new(
+            source_path,
+            "Configs cannot be copied into their own vendor".into(),
+        )]);
+    }
+
+    let source_name = directory_name(source_path);
+    let target_name = directory_name(target_path);
+
+    let (_, mut source_config) = load_vendor(source_path).map_err(|problem| vec![problem])?;
+    let (target_data, mut target_config) =
+        load_vendor(target_path).map_err(|problem| vec![problem])?;
+
+    let source_entries: Vec<(&'static str, ConfigNameAndPath)> = config_lists(&mut source_config)
+        .into_iter()
+        .flat_map(|(config_type, list)| {
+            list.clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |entry| (config_type, entry))
+        })
+        .collect();
+    let target_entries: HashMap<&str, Vec<ConfigNameAndPath>> = config_lists(&mut target_config)
+        .into_iter()
+        .map(|(config_type, list)| (config_type, list.clone().unwrap_or_default()))
+        .collect();
+    let target_lists = |config_type: &str, name: &str| {
+        target_entries[config_type]
+            .iter()
+            .any(|entry| entry.name == name)
+    };
+    let target_name_of = |name: &str| {
+        replace_name(
+            name,
+            &source_name,
+            &target_name,
+            orca_filament_library_filaments,
+        )
+    };
+
+    let mut problems = Vec::new();
+    let mut queue: Vec<(&'static str, ConfigNameAndPath)> = Vec::new();
+
+    for path in config_paths {
+        let listed = source_entries
+            .iter()
+            .find(|(_, entry)| source_directory.join(&entry.sub_path) == *path);
+
+        match listed {
+            Some((config_type, _)) if *config_type == "printer-model" => {
+                problems.push(VendorProblem::new(
+                    path,
+                    "Printer models are moved with their vendor, by splitting or merging vendors"
+                        .into(),
+                ))
+            }
+            Some((config_type, entry)) => {
+                if target_lists(config_type, &target_name_of(&entry.name)) {
+                    problems.push(VendorProblem::new(
+                        path,
+                        format!(
+                            "Vendor '{}' already has the {} preset '{}'",
+                            target_name,
+                            config_type,
+                            target_name_of(&entry.name)
+                        ),
+                    ));
+                }
+                queue.push((config_type, entry.clone()));
+            }
+            None => problems.push(VendorProblem::new(
+                path,
+                format!("This is not a config of vendor '{}'", source_name),
+            )),
+        }
+    }
+
+    // the selected configs and the ancestors the target lacks
+    let mut copied: Vec<CopiedConfig> = Vec::new();
+    let mut next = 0;
+    while next < queue.len() {
+        let (config_type, entry) = queue[next].clone();
+        next += 1;
+
+        if copied
+            .iter()
+            .any(|config| config.config_type == config_type && config.entry.name == entry.name)
+        {
+            continue;
+        }
+
+        let source_file = source_directory.join(&entry.sub_path);
+        let data = match fs::read_to_string(read_path(&source_file)) {
+            Ok(data) => data,
+            Err(_) => {
+                problems.push(VendorProblem::new(
+                    &source_file,
+                    format!("Config '{}' could not be read", entry.name),
+                ));
+                continue;
+            }
+        };
+
+        // parents of other vendors, like the filament library, stay where they are
+        if let Some(parent) = parent_name(&data) {
+            let parent_entry = source_entries
+                .iter()
+                .find(|(other_type, other)| *other_type == config_type && other.name == parent);
+            if let Some((_, parent_entry)) = parent_entry {
+                if !target_lists(config_type, &target_name_of(&parent)) {
+                    queue.push((config_type, parent_entry.clone()));
+                }
+            }
+        }
+
+        copied.push(CopiedConfig {
+            config_type,
+            new_name: target_name_of(&entry.name),
+            entry,
+            data,
+        });
+    }
+
+    // the names of the copies, and of the presets the target has a copy of
+    let mut renames: HashMap<String, String> = HashMap::new();
+    for (config_type, entry) in &source_entries {
+        let new_name = target_name_of(&entry.name);
+        if target_lists(config_type, &new_name) {
+            renames.insert(entry.name.clone(), new_name);
+        }
+    }
+    for config in &copied {
+        renames.insert(config.entry.name.clone(), config.new_name.clone());
+    }
+    let rename = |name: &str| renames.get(name).cloned().unwrap_or(name.into());
+
+    let mut plan = VendorPlan::new(
+        "copy_configs_to_vendor",
+        format!(
+            "Copy {} configs of vendor '{}' into '{}'",
+            copied.len(),
+            source_name,
+            target_name
+        ),
+        target_path.parent().unwrap_or(target_path),
+    );
+    let mut copied_entries: HashMap<&str, Vec<ConfigNameAndPath>> = HashMap::new();
+    let mut parents: HashMap<&str, HashMap<String, String>> = HashMap::new();
+
+    for config in &copied {
+        let source_file = source_directory.join(&config.entry.sub_path);
+        let sub_path = new_sub_path(&config.entry.sub_path, &config.new_name);
+        let destination = target_directory.join(&sub_path);
+
+        let mut rewrite = Rewrite {
+            rename: &rename,
+            family: &target_name,
+            assets: &HashMap::new(),
+            path: destination.to_string_lossy().to_string(),
+            references: Vec::new(),
+        };
+        let content =
+            match rewrite.rewrite_config(config.config_type, &config.data, &config.new_name) {
+                Ok(content) => content,
+                Err(err) => {
+                    problems.push(VendorProblem::new(&source_file, err));
+                    continue;
+                }
+            };
+
+        if let Some(model) = serde_json::from_str::<serde_json::Value>(&content)
+            .ok()
+            .and_then(|values| values.get("printer_model")?.as_str().map(String::from))
+        {
+            if !target_lists("printer-model", &model) {
+                problems.push(VendorProblem::new(
+                    &source_file,
+                    format!(
+                        "Vendor '{}' has no printer model '{}' for '{}'",
+                        target_name, model, config.entry.name
+                    ),
+                ));
+            }
+        }
+
+        if let Some(parent) = parent_name(&content) {
+            parents
+                .entry(config.config_type)
+                .or_default()
+                .insert(config.new_name.clone(), parent);
+        }
+
+        let planned = if read_path(&destination).exists() {
+            Err(VendorProblem::new(
+                &source_file,
+                format!("'{}' already exists in vendor '{}'", sub_path, target_name),
+            ))
+        } else {
+            plan.create_file(
+                &destination,
+                Some(&source_file),
+                Some(content),
+                Some(config.config_type),
+            )
+        };
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+
+        plan.references.extend(rewrite.references);
+        plan.renames.push(PresetRename {
+            config_type: config.config_type.into(),
+            old_name: config.entry.name.clone(),
+            new_name: config.new_name.clone(),
+        });
+        copied_entries
+            .entry(config.config_type)
+            .or_default()
+            .push(ConfigNameAndPath {
+                name: config.new_name.clone(),
+                sub_path,
+            });
+    }
+
+    for (config_type, list) in config_lists(&mut target_config) {
+        let Some(new_entries) = copied_entries.remove(config_type) else {
+            continue;
+        };
+
+        *list = Some(extend_in_load_order(
+            &target_directory,
+            &target_entries[config_type],
+            new_entries,
+            parents.entry(config_type).or_default(),
+        ));
+    }
+
+    match to_edited_json(&target_data, &target_config) {
+        Ok(content) => {
+            if let Err(problem) = plan.modify_file(target_path, content, Some("vendor")) {
+                problems.push(problem);
+            }
+        }
+        Err(err) => problems.push(VendorProblem::new(target_path, err)),
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    plan.diff = plan.render_diff();
+    Ok(plan)
+}
@@ -0,0 +1,620 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commons::{remove_nulls, replace_name};
+use crate::configuration_loader::{
+    ConfigNameAndPath, FilamentJsonSchema, PrinterModelJsonSchema, PrinterVariantJsonSchema,
+    ProcessJsonSchema, VendorJsonSchema,
+};
+use crate::json_edit::edited_json;
+use crate::name_references::{name_fields, NameTarget};
+use crate::overlay::{merged_listing, read_path};
+use crate::vendor_plan::{PresetRename, ReferenceChange, VendorPlan, VendorProblem};
+use crate::vendor_split::{model_assets, MODEL_ASSET_KEYS};
+
+// The folders of the OrcaSlicer data directory holding the loaded system
+// vendors and the presets of each user account
+pub const SYSTEM_DIRECTORY: &str = "system";
+pub const USER_DIRECTORY: &str = "user";
+const USER_PRESET_DIRECTORIES: [(&str, &str); 3] = [
+    ("printer", "machine"),
+    ("filament", "filament"),
+    ("process", "process"),
+];
+
+struct Renamer<'a> {
+    old_dir_name: &'a str,
+    new_dir_name: &'a str,
+    orca_filament_library_filaments: &'a HashSet<String>,
+}
+
+/// Rewrites one config under a new name: the preset names it references are
+/// passed through `rename` and recorded when they change, printer models move
+/// to `family` and point at their assets by the names in `assets`.
+pub struct Rewrite<'a> {
+    pub rename: &'a dyn Fn(&str) -> String,
+    pub family: &'a str,
+    pub assets: &'a HashMap<String, String>,
+    pub path: String,
+    pub references: Vec<ReferenceChange>,
+}
+
+impl Renamer<'_> {
+    fn rename(&self, name: &str) -> String {
+        replace_name(
+            name,
+            self.old_dir_name,
+            self.new_dir_name,
+            self.orca_filament_library_filaments,
+        )
+    }
+}
+
+impl Rewrite<'_> {
+    fn rename_reference(&mut self, key: &str, name: &str) -> String {
+        let new_name = (self.rename)(name);
+
+        if new_name != name {
+            self.references.push(ReferenceChange {
+                path: self.path.clone(),
+                key: key.into(),
+                old_value: name.into(),
+                new_value: new_name.clone(),
+            });
+        }
+
+        new_name
+    }
+
+    fn rename_assets(&mut self, values: &mut Map<String, Value>) {
+        for key in MODEL_ASSET_KEYS {
+            let Some(Value::String(asset)) = values.get(key) else {
+                continue;
+            };
+            let Some(new_asset) = self.assets.get(asset).cloned() else {
+                continue;
+            };
+
+            if new_asset != *asset {
+                self.references.push(ReferenceChange {
+                    path: self.path.clone(),
+                    key: key.into(),
+                    old_value: asset.clone(),
+                    new_value: new_asset.clone(),
+                });
+            }
+            values.insert(key.into(), Value::String(new_asset));
+        }
+    }
+
+    pub fn rewrite_config(
+        &mut self,
+        config_type: &str,
+        data: &str,
+        new_name: &str,
+    ) -> Result<String, String> {
+        let parse_error = |err: serde_json::Error| format!("Malformed or invalid JSON: {}", err);
+
+        // the rewritten config has to load as its type, like the original
+        match config_type {
+            "printer" => serde_json::from_str::<PrinterVariantJsonSchema>(data).map(|_| ()),
+            "printer-model" => serde_json::from_str::<PrinterModelJsonSchema>(data).map(|_| ()),
+            "filament" => serde_json::from_str::<FilamentJsonSchema>(data).map(|_| ()),
+            _ => serde_json::from_str::<ProcessJsonSchema>(data).map(|_| ()),
+        }
+        .map_err(parse_error)?;
+
+        let mut values: Map<String, Value> = serde_json::from_str(data).map_err(parse_error)?;
+        values.insert("name".into(), Value::String(new_name.into()));
+
+        if config_type == "printer-model" {
+            values.insert("family".into(), Value::String(self.family.into()));
+            self.rename_assets(&mut values);
+        }
+
+        for field in name_fields(config_type) {
+            if field.target != NameTarget::Variant {
+                field.rename(&mut values, |name| self.rename_reference(field.key, name))?;
+            }
+        }
+
+        edited_json(Some(data), &Value::Object(values))
+    }
+}
+
+// the copy keeps the layout of the file it was made from
+pub fn to_edited_json<T: Serialize>(original: &str, config: &T) -> Result<String, String> {
+    let mut value = serde_json::to_value(config).map_err(|err| err.to_string())?;
+    remove_nulls(&mut value);
+    edited_json(Some(original), &value)
+}
+
+/// The four config lists of a vendor with the config type they list.
+pub fn config_lists(
+    vendor_config: &mut VendorJsonSchema,
+) -> [(&'static str, &mut Option<Vec<ConfigNameAndPath>>); 4] {
+    [
+        ("printer", &mut vendor_config.machine_list),
+        ("printer-model", &mut vendor_config.machine_model_list),
+        ("filament", &mut vendor_config.filament_list),
+        ("process", &mut vendor_config.process_list),
+    ]
+}
+
+/// The directory and vendor file a vendor duplicated from `path` is written to.
+pub fn duplicated_vendor_paths(
+    path: &Path,
+    new_dir_name: &str,
+) -> Result<(PathBuf, PathBuf), VendorProblem> {
+    let (Some(parent_directory), Some(path_extension)) = (path.parent(), path.extension()) else {
+        return Err(VendorProblem::new(
+            path,
+            "This is not the path of a vendor config file".into(),
+        ));
+    };
+
+    let new_directory_path = parent_directory.join(Path::new(new_dir_name));
+
+    let mut path_extension_combined = OsString::from(new_dir_name);
+    path_extension_combined.push(".");
+    path_extension_combined.push(path_extension);
+
+    let new_file_path = parent_directory.join(path_extension_combined);
+
+    Ok((new_directory_path, new_file_path))
+}
+
+/// The directory and vendor file of a new vendor `new_dir_name` next to the
+/// vendor at `path`, refused when the name is invalid or already taken.
+pub fn new_vendor_paths(
+    path: &Path,
+    new_dir_name: &str,
+) -> Result<(PathBuf, PathBuf), VendorProblem> {
+    if new_dir_name.trim().is_empty()
+        || new_dir_name.contains(['/', '\\'])
+        || new_dir_name.starts_with('.')
+    {
+        return Err(VendorProblem::new(
+            path,
+            format!("'{}' is not a valid vendor name", new_dir_name),
+        ));
+    }
+
+    let (new_directory_path, new_file_path) = duplicated_vendor_paths(path, new_dir_name)?;
+
+    if read_path(&new_directory_path).exists() || read_path(&new_file_path).exists() {
+        return Err(VendorProblem::new(
+            &new_file_path,
+            format!("A vendor named '{}' already exists", new_dir_name),
+        ));
+    }
+
+    Ok((new_directory_path, new_file_path))
+}
+
+/// `sub_path` with its file named after `new_name`.
+pub fn new_sub_path(sub_path: &str, new_name: &str) -> String {
+    match Path::new(sub_path).parent().and_then(Path::to_str) {
+        Some(parent) if !parent.is_empty() => format!("{}/{}.json", parent, new_name),
+        _ => format!("{}.json", new_name),
+    }
+}
+
+fn files_on_disk(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(files_on_disk(&path));
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Every file below `directory`, recursively, including the overlay.
+pub fn files_in_tree(directory: &Path) -> Vec<PathBuf> {
+    merged_listing(directory, files_on_disk)
+}
+
+// `asset` with its file name passed through `rename`
+fn renamed_asset(asset: &str, rename: &dyn Fn(&str) -> String) -> String {
+    let asset_path = Path::new(asset);
+    let (Some(stem), Some(extension)) = (
+        asset_path.file_stem().and_then(|stem| stem.to_str()),
+        asset_path
+            .extension()
+            .and_then(|extension| extension.to_str()),
+    ) else {
+        return asset.into();
+    };
+
+    match asset_path.parent().and_then(Path::to_str) {
+        Some(parent) if !parent.is_empty() => format!("{}/{}.{}", parent, rename(stem), extension),
+        _ => format!("{}.{}", rename(stem), extension),
+    }
+}
+
+/// The new names of the files the printer models of a vendor show, relative
+/// to the vendor directory. Covers follow the name of their model, the other
+/// assets are renamed like the presets.
+fn asset_renames(
+    vendor_directory: &Path,
+    machine_models: &[ConfigNameAndPath],
+    rename: &dyn Fn(&str) -> String,
+) -> HashMap<String, String> {
+    let mut renames = HashMap::new();
+
+    for entry in machine_models {
+        let Ok(data) = fs::read_to_string(read_path(&vendor_directory.join(&entry.sub_path)))
+        else {
+            continue;
+        };
+        let Ok(values) = serde_json::from_str::<Map<String, Value>>(&data) else {
+            continue;
+        };
+        let cover = format!("{}_cover.png", entry.name);
+
+        for asset in model_assets(&values) {
+            let new_asset = if asset == cover {
+                format!("{}_cover.png", rename(&entry.name))
+            } else {
+                renamed_asset(&asset, rename)
+            };
+            renames.entry(asset).or_insert(new_asset);
+        }
+    }
+
+    renames
+}
+
+/// Plans the duplication of the vendor config at `path` and its directory as
+/// `new_dir_name`: every copied and moved file, every renamed preset and every
+/// rewritten reference. Nothing is written, every problem found is returned.
+pub fn plan_duplicate_vendor(
+    path: &Path,
+    new_dir_name: &str,
+    orca_filament_library_filaments: &HashSet<String>,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let (new_directory_path, new_file_path) =
+        new_vendor_paths(path, new_dir_name).map_err(|problem| vec![problem])?;
+
+    let (Some(parent_directory), Some(old_dir_name)) = (
+        path.parent(),
+        path.file_stem().and_then(|stem| stem.to_str()),
+    ) else {
+        return Err(vec![VendorProblem::new(
+            path,
+            "This is not the path of a vendor config file".into(),
+        )]);
+    };
+    let old_directory_path = parent_directory.join(old_dir_name);
+
+    let renamer = Renamer {
+        old_dir_name,
+        new_dir_name,
+        orca_filament_library_filaments,
+    };
+    let rename = |name: &str| renamer.rename(name);
+
+    let vendor_data = fs::read_to_string(read_path(path)).map_err(|err| {
+        vec![VendorProblem::new(
+            path,
+            format!("Could not read the vendor config: {}", err),
+        )]
+    })?;
+    let mut vendor_config: VendorJsonSchema =
+        serde_json::from_str(&vendor_data).map_err(|err| {
+            vec![VendorProblem::new(
+                path,
+                format!("Malformed or invalid JSON: {}", err),
+            )]
+        })?;
+    vendor_config.name = new_dir_name.into();
+
+    let asset_renames = asset_renames(
+        &old_directory_path,
+        vendor_config
+            .machine_model_list
+            .as_deref()
+            .unwrap_or_default(),
+        &rename,
+    );
+
+    let mut plan = VendorPlan::new(
+        "duplicate_vendor",
+        format!("Duplicate vendor '{}' as '{}'", old_dir_name, new_dir_name),
+        parent_directory,
+    );
+    let mut problems = Vec::new();
+    let mut listed_files: HashSet<PathBuf> = HashSet::new();
+
+    for (config_type, list) in config_lists(&mut vendor_config) {
+        let mut new_names: HashMap<String, String> = HashMap::new();
+
+        for entry in list.iter_mut().flatten() {
+            let old_file_path = old_directory_path.join(&entry.sub_path);
+            let new_name = renamer.rename(&entry.name);
+            let new_sub_path = new_sub_path(&entry.sub_path, &new_name);
+            let new_config_path = new_directory_path.join(&new_sub_path);
+
+            if !listed_files.insert(old_file_path.clone()) {
+                problems.push(VendorProblem::new(
+                    &old_file_path,
+                    format!("'{}' is listed more than once", entry.sub_path),
+                ));
+            }
+
+            if let Some(other_name) = new_names.insert(new_name.clone(), entry.name.clone()) {
+                problems.push(VendorProblem::new(
+                    &old_file_path,
+                    format!(
+                        "'{}' and '{}' would both be renamed to '{}'",
+                        other_name, entry.name, new_name
+                    ),
+                ));
+            }
+
+            let mut rewrite = Rewrite {
+                rename: &rename,
+                family: new_dir_name,
+                assets: &asset_renames,
+                path: new_config_path.to_string_lossy().to_string(),
+                references: Vec::new(),
+            };
+
+            let rewrite_res = fs::read_to_string(read_path(&old_file_path))
+                .map_err(|_| format!("Config '{}' could not be read", entry.name))
+                .and_then(|data| rewrite.rewrite_config(config_type, &data, &new_name));
+
+            match rewrite_res {
+                Ok(content) => {
+                    if let Err(problem) = plan.create_file(
+                        &new_config_path,
+                        Some(&old_file_path),
+                        Some(content),
+                        Some(config_type),
+                    ) {
+                        problems.push(problem);
+                    }
+                }
+                Err(err) => problems.push(VendorProblem::new(&old_file_path, err)),
+            }
+
+            plan.references.extend(rewrite.references);
+            if new_name != entry.name {
+                plan.renames.push(PresetRename {
+                    config_type: config_type.into(),
+                    old_name: entry.name.clone(),
+                    new_name: new_name.clone(),
+                });
+            }
+
+            entry.name = new_name;
+            entry.sub_path = new_sub_path;
+        }
+    }
+
+    // the assets of the printer models get their new names, the other files
+    // are copied as they are
+    for file_path in files_in_tree(&old_directory_path) {
+        if listed_files.contains(&file_path) {
+            continue;
+        }
+
+        let Ok(relative_path) = file_path.strip_prefix(&old_directory_path) else {
+            continue;
+        };
+
+        let new_relative_path = asset_renames
+            .get(&relative_path.to_string_lossy().replace('\\', "/"))
+            .map(PathBuf::from)
+            .unwrap_or(relative_path.to_path_buf());
+
+        if let Err(problem) = plan.create_file(
+            &new_directory_path.join(new_relative_path),
+            Some(&file_path),
+            None,
+            None,
+        ) {
+            problems.push(problem);
+        }
+    }
+
+    match to_edited_json(&vendor_data, &vendor_config) {
+        Ok(content) => {
+            if let Err(problem) =
+                plan.create_file(&new_file_path, Some(path), Some(content), Some("vendor"))
+            {
+                problems.push(problem);
+            }
+        }
+        Err(err) => problems.push(VendorProblem::new(&new_file_path, err)),
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    plan.diff = plan.render_diff();
+    Ok(plan)
+}
+
+/// The user presets of every account below `<data>/user`, by config type.
+pub fn user_preset_files(data_directory: &Path) -> Vec<(&'static str, PathBuf)> {
+    let Ok(accounts) = fs::read_dir(read_path(&data_directory.join(USER_DIRECTORY))) else {
+        return Vec::new();
+    };
+
+    let mut files = Vec::new();
+    for account in accounts.flatten() {
+        let account_directory = data_directory
+            .join(USER_DIRECTORY)
+            .join(account.file_name());
+
+        for (config_type, sub_directory) in USER_PRESET_DIRECTORIES {
+            files.extend(
+                files_in_tree(&account_directory.join(sub_directory))
+                    .into_iter()
+                    .filter(|file| file.extension().and_then(|ext| ext.to_str()) == Some("json"))
+                    .map(|file| (config_type, file)),
+            );
+        }
+    }
+
+    files
+}
+
+// Points the name fields of user presets at the renamed system presets
+fn plan_user_preset_updates(
+    plan: &mut VendorPlan,
+    data_directory: &Path,
+    problems: &mut Vec<VendorProblem>,
+) {
+    let renames: HashMap<(String, String), String> = plan
+        .renames
+        .iter()
+        .map(|rename| {
+            (
+                (rename.config_type.clone(), rename.old_name.clone()),
+                rename.new_name.clone(),
+            )
+        })
+        .collect();
+
+    for (config_type, file_path) in user_preset_files(data_directory) {
+        let Ok(data) = fs::read_to_string(read_path(&file_path)) else {
+            continue;
+        };
+        // broken user presets are reported by the analysers, not here
+        let Ok(mut config) = serde_json::from_str::<Map<String, Value>>(&data) else {
+            continue;
+        };
+
+        let mut references = Vec::new();
+        for field in name_fields(config_type) {
+            let Some(target_type) = field.target_type(config_type) else {
+                continue;
+            };
+
+            // a field of the wrong kind is left as it is, like the rest of the preset
+            let _ = field.rename(&mut config, |name| {
+                let Some(new_name) = renames.get(&(target_type.to_string(), name.to_string()))
+                else {
+                    return name.to_string();
+                };
+
+                references.push(ReferenceChange {
+                    path: file_path.to_string_lossy().to_string(),
+                    key: field.key.into(),
+                    old_value: name.into(),
+                    new_value: new_name.clone(),
+                });
+                new_name.clone()
+            });
+        }
+
+        if references.is_empty() {
+            continue;
+        }
+        plan.references.extend(references);
+
+        let planned = edited_json(Some(&data), &Value::Object(config)).and_then(|content| {
+            plan.modify_file(&file_path, content, None)
+                .map_err(|problem| problem.message)
+        });
+        if let Err(err) = planned {
+            problems.push(VendorProblem::new(&file_path, err));
+        }
+    }
+}
+
+/// Plans renaming the vendor config at `path` and its directory to
+/// `new_dir_name`. The files of the vendor are moved to their names in the
+/// duplicate `plan_duplicate_vendor` would make, and only the configs whose
+/// content changes are rewritten. With the data directory the stale copy
+/// OrcaSlicer keeps in its `system` folder is removed as well, and user
+/// presets inheriting from the renamed presets can be pointed at their new
+/// names.
+pub fn plan_rename_vendor(
+    path: &Path,
+    new_dir_name: &str,
+    orca_filament_library_filaments: &HashSet<String>,
+    data_directory: Option<&Path>,
+    update_user_presets: bool,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let duplicate = plan_duplicate_vendor(path, new_dir_name, orca_filament_library_filaments)?;
+
+    let old_dir_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut plan = VendorPlan::new(
+        "rename_vendor",
+        format!("Rename vendor '{}' to '{}'", old_dir_name, new_dir_name),
+        Path::new(&duplicate.root),
+    );
+    plan.renames = duplicate.renames;
+    plan.references = duplicate.references;
+
+    let mut problems = Vec::new();
+
+    // every file of the duplicate is a copy of one of the vendor, it is moved
+    // and only rewritten when its content changes
+    for file in duplicate.files {
+        let Some(source) = file.source.as_deref().map(Path::new) else {
+            continue;
+        };
+        let content = file.content.filter(|content| {
+            fs::read_to_string(read_path(source)).map_or(true, |data| data != *content)
+        });
+
+        if let Err(problem) = plan.move_file(
+            Path::new(&file.path),
+            source,
+            content,
+            file.config_type.as_deref(),
+        ) {
+            problems.push(problem);
+        }
+    }
+
+    if let Some(data_directory) = data_directory {
+        let system_vendor_path = data_directory
+            .join(SYSTEM_DIRECTORY)
+            .join(format!("{}.json", old_dir_name));
+
+        let mut old_files = files_in_tree(&system_vendor_path.with_extension(""));
+        if read_path(&system_vendor_path).is_file() {
+            old_files.push(system_vendor_path);
+        }
+
+        problems.extend(
+            old_files
+                .iter()
+                .filter_map(|file_path| plan.delete_file(file_path).err()),
+        );
+    }
+
+    if let (Some(data_directory), true) = (data_directory, update_user_presets) {
+        plan_user_preset_updates(&mut plan, data_directory, &mut problems);
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    plan.diff = plan.render_diff();
+    Ok(plan)
+}
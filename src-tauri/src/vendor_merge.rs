@@ -0,0 +1,426 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::configuration_loader::{ConfigNameAndPath, VendorJsonSchema};
+use crate::file_store::file_hash;
+use crate::overlay::read_path;
+use crate::vendor_duplication::{
+    config_lists, files_in_tree, new_sub_path, to_edited_json, Rewrite,
+};
+use crate::vendor_plan::{PresetRename, VendorPlan, VendorProblem};
+
+/// What happens to a config of the merged vendor when the target vendor
+/// already has a config of the same type and name.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum CollisionStrategy {
+    /// The config is moved with the name of the merged vendor put in front.
+    Prefix,
+    /// The config of the target vendor is kept, the other one is dropped.
+    Skip,
+    /// The config of the target vendor is replaced.
+    Overwrite,
+}
+
+/// Reads a vendor config, with its text so that rewrites keep its layout.
+pub fn load_vendor(path: &Path) -> Result<(String, VendorJsonSchema), VendorProblem> {
+    let data = fs::read_to_string(read_path(path)).map_err(|err| {
+        VendorProblem::new(path, format!("Could not read the vendor config: {}", err))
+    })?;
+    let config = serde_json::from_str(&data)
+        .map_err(|err| VendorProblem::new(path, format!("Malformed or invalid JSON: {}", err)))?;
+
+    Ok((data, config))
+}
+
+/// The `inherits` of a config given as JSON text.
+pub fn parent_name(content: &str) -> Option<String> {
+    serde_json::from_str::<Map<String, Value>>(content)
+        .ok()?
+        .get("inherits")?
+        .as_str()
+        .map(String::from)
+}
+
+struct InheritanceOrder<'a> {
+    entries: HashMap<&'a str, &'a ConfigNameAndPath>,
+    parents: &'a HashMap<String, String>,
+    visited: HashSet<&'a str>,
+    sorted: Vec<ConfigNameAndPath>,
+}
+
+impl<'a> InheritanceOrder<'a> {
+    // the parent goes first, a cycle is broken where it closes
+    fn place(&mut self, name: &'a str) {
+        if !self.visited.insert(name) {
+            return;
+        }
+
+        if let Some(parent) = self.parents.get(name) {
+            if let Some((parent, _)) = self.entries.get_key_value(parent.as_str()) {
+                self.place(parent);
+            }
+        }
+
+        self.sorted.push(self.entries[name].clone());
+    }
+}
+
+/// Orders the entries of a vendor list so that every config follows the
+/// config it inherits from, as OrcaSlicer loads the lists in order. Entries
+/// already in a valid order keep it.
+pub fn sort_by_inheritance(
+    entries: &[ConfigNameAndPath],
+    parents: &HashMap<String, String>,
+) -> Vec<ConfigNameAndPath> {
+    let mut order = InheritanceOrder {
+        entries: entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry))
+            .collect(),
+        parents,
+        visited: HashSet::new(),
+        sorted: Vec::new(),
+    };
+
+    for entry in entries {
+        order.place(&entry.name);
+    }
+
+    order.sorted
+}
+
+/// The entries of a target vendor list followed by `added_entries`, in load
+/// order. The parents of target configs missing from `parents` are read from
+/// their files below `target_directory`.
+pub(crate) fn extend_in_load_order(
+    target_directory: &Path,
+    target_entries: &[ConfigNameAndPath],
+    added_entries: Vec<ConfigNameAndPath>,
+    parents: &mut HashMap<String, String>,
+) -> Vec<ConfigNameAndPath> {
+    for entry in target_entries {
+        if parents.contains_key(&entry.name) {
+            continue;
+        }
+        let content = fs::read_to_string(read_path(&target_directory.join(&entry.sub_path)));
+        if let Some(parent) = content.ok().as_deref().and_then(parent_name) {
+            parents.insert(entry.name.clone(), parent);
+        }
+    }
+
+    let mut entries = target_entries.to_vec();
+    entries.extend(added_entries);
+    sort_by_inheritance(&entries, parents)
+}
+
+pub(crate) fn directory_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Plans moving every config of the vendor at `source_path` into the vendor at
+/// `target_path`, then removing the emptied source vendor. Name collisions are
+/// resolved by `strategy`, references in the moved configs follow renamed
+/// presets and printer models join the family of the target. The merged lists
+/// are put in a valid load order. Nothing is written, every problem found is
+/// returned.
+pub fn plan_merge_vendors(
+    source_path: &Path,
+    target_path: &Path,
+    strategy: CollisionStrategy,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let source_directory = source_path.with_extension("");
+    let target_directory = target_path.with_extension("");
+
+    if source_directory == target_directory {
+        return Err(vec![VendorProblem::new(
+            source_path,
+            "A vendor cannot be merged into itself".into(),
+        )]);
+    }
+
+    let source_name = directory_name(source_path);
+    let target_name = directory_name(target_path);
+
+    let (_, mut source_config) = load_vendor(source_path).map_err(|problem| vec![problem])?;
+    let (target_data, mut target_config) =
+        load_vendor(target_path).map_err(|problem| vec![problem])?;
+
+    let mut plan = VendorPlan::new(
+        "merge_vendors",
+        format!("Merge vendor '{}' into '{}'", source_name, target_name),
+        target_path.parent().unwrap_or(target_path),
+    );
+    let mut problems = Vec::new();
+
+    // the configs of the target by type, with their sub_path
+    let target_entries: HashMap<&str, Vec<ConfigNameAndPath>> = config_lists(&mut target_config)
+        .into_iter()
+        .map(|(config_type, list)| (config_type, list.clone().unwrap_or_default()))
+        .collect();
+    let target_sub_paths = |config_type: &str, name: &str| {
+        target_entries[config_type]
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.sub_path.clone())
+    };
+
+    // prefixed names for the colliding configs
+    let mut renames: HashMap<String, String> = HashMap::new();
+    if strategy == CollisionStrategy::Prefix {
+        for (config_type, list) in config_lists(&mut source_config) {
+            for entry in list.iter().flatten() {
+                if target_sub_paths(config_type, &entry.name).is_none() {
+                    continue;
+                }
+
+                let new_name = format!("{} {}", source_name, entry.name);
+                if target_sub_paths(config_type, &new_name).is_some() {
+                    problems.push(VendorProblem::new(
+                        &source_directory.join(&entry.sub_path),
+                        format!(
+                            "'{}' exists in both vendors and so does '{}'",
+                            entry.name, new_name
+                        ),
+                    ));
+                }
+                renames.insert(entry.name.clone(), new_name);
+            }
+        }
+    }
+    let rename = |name: &str| renames.get(name).cloned().unwrap_or(name.into());
+
+    let mut listed_files: HashSet<PathBuf> = HashSet::new();
+    let mut moved_entries: HashMap<&str, Vec<ConfigNameAndPath>> = HashMap::new();
+    let mut parents: HashMap<&str, HashMap<String, String>> = HashMap::new();
+
+    for (config_type, list) in config_lists(&mut source_config) {
+        for entry in list.iter().flatten() {
+            let source_file = source_directory.join(&entry.sub_path);
+            listed_files.insert(source_file.clone());
+
+            let collision = target_sub_paths(config_type, &entry.name);
+            // an overwritten config keeps the place of the config it replaces
+            let overwrite = collision.is_some() && strategy == CollisionStrategy::Overwrite;
+            let (name, sub_path) = match (collision, strategy) {
+                (Some(_), CollisionStrategy::Skip) => continue,
+                (Some(target_sub_path), CollisionStrategy::Overwrite) => {
+                    (entry.name.clone(), target_sub_path)
+                }
+                _ => {
+                    let name = rename(&entry.name);
+                    let sub_path = if name == entry.name {
+                        entry.sub_path.clone()
+                    } else {
+                        new_sub_path(&entry.sub_path, &name)
+                    };
+                    (name, sub_path)
+                }
+            };
+            let destination = target_directory.join(&sub_path);
+
+            let mut rewrite = Rewrite {
+                rename: &rename,
+                family: &target_name,
+                assets: &HashMap::new(),
+                path: destination.to_string_lossy().to_string(),
+                references: Vec::new(),
+            };
+
+            let content = match fs::read_to_string(read_path(&source_file))
+                .map_err(|_| format!("Config '{}' could not be read", entry.name))
+                .and_then(|data| rewrite.rewrite_config(config_type, &data, &name))
+            {
+                Ok(content) => content,
+                Err(err) => {
+                    problems.push(VendorProblem::new(&source_file, err));
+                    continue;
+                }
+            };
+
+            if let Some(parent) = parent_name(&content) {
+                parents
+                    .entry(config_type)
+                    .or_default()
+                    .insert(name.clone(), parent);
+            }
+
+            let planned = if overwrite {
+                plan.modify_file(&destination, content, Some(config_type))
+            } else if read_path(&destination).exists() {
+                Err(VendorProblem::new(
+                    &source_file,
+                    format!("'{}' already exists in vendor '{}'", sub_path, target_name),
+                ))
+            } else {
+                plan.create_file(
+                    &destination,
+                    Some(&source_file),
+                    Some(content),
+                    Some(config_type),
+                )
+            };
+            if let Err(problem) = planned {
+                problems.push(problem);
+            }
+
+            plan.references.extend(rewrite.references);
+            if name != entry.name {
+                plan.renames.push(PresetRename {
+                    config_type: config_type.into(),
+                    old_name: entry.name.clone(),
+                    new_name: name.clone(),
+                });
+            }
+            if !overwrite {
+                moved_entries
+                    .entry(config_type)
+                    .or_default()
+                    .push(ConfigNameAndPath { name, sub_path });
+            }
+        }
+    }
+
+    for (config_type, list) in config_lists(&mut target_config) {
+        let entries = extend_in_load_order(
+            &target_directory,
+            &target_entries[config_type],
+            moved_entries.remove(config_type).unwrap_or_default(),
+            parents.entry(config_type).or_default(),
+        );
+        if !entries.is_empty() {
+            *list = Some(entries);
+        }
+    }
+
+    match to_edited_json(&target_data, &target_config) {
+        Ok(content) => {
+            if let Err(problem) = plan.modify_file(target_path, content, Some("vendor")) {
+                problems.push(problem);
+            }
+        }
+        Err(err) => problems.push(VendorProblem::new(target_path, err)),
+    }
+
+    // the files no config lists, like covers and bed models, move as they are
+    for file_path in files_in_tree(&source_directory) {
+        let Ok(relative_path) = file_path.strip_prefix(&source_directory) else {
+            continue;
+        };
+        let destination = target_directory.join(relative_path);
+
+        let planned = if listed_files.contains(&file_path) {
+            Ok(())
+        } else if !read_path(&destination).exists() {
+            plan.create_file(&destination, Some(&file_path), None, None)
+        } else if file_hash(&read_path(&destination)) == file_hash(&read_path(&file_path)) {
+            Ok(())
+        } else {
+            match strategy {
+                CollisionStrategy::Overwrite => plan.replace_file(&destination, &file_path),
+                CollisionStrategy::Skip => Ok(()),
+                CollisionStrategy::Prefix => Err(VendorProblem::new(
+                    &file_path,
+                    format!(
+                        "'{}' differs from the file of vendor '{}'",
+                        relative_path.display(),
+                        target_name
+                    ),
+                )),
+            }
+        };
+
+        if let Err(problem) = planned.and_then(|_| plan.delete_file(&file_path)) {
+            problems.push(problem);
+        }
+    }
+
+    if let Err(problem) = plan.delete_file(source_path) {
+        problems.push(problem);
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    plan.diff = plan.render_diff();
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(names: &[&str]) -> Vec<ConfigNameAndPath> {
+        names
+            .iter()
+            .map(|name| ConfigNameAndPath {
+                name: name.to_string(),
+                sub_path: format!("filament/{}.json", name),
+            })
+            .collect()
+    }
+
+    fn parents(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(child, parent)| (child.to_string(), parent.to_string()))
+            .collect()
+    }
+
+    fn sorted_names(names: &[&str], pairs: &[(&str, &str)]) -> Vec<String> {
+        sort_by_inheritance(&entries(names), &parents(pairs))
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect()
+    }
+
+    #[test]
+    fn valid_order_is_kept() {
+        assert_eq!(
+            sorted_names(&["base", "a", "b"], &[("a", "base"), ("b", "base")]),
+            vec!["base", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn parents_move_before_their_children() {
+        assert_eq!(
+            sorted_names(
+                &["child", "other", "middle", "base"],
+                &[("child", "middle"), ("middle", "base")]
+            ),
+            vec!["base", "middle", "child", "other"]
+        );
+    }
+
+    #[test]
+    fn parents_outside_the_list_are_ignored() {
+        assert_eq!(
+            sorted_names(&["b", "a"], &[("b", "fdm_filament_pla")]),
+            vec!["b", "a"]
+        );
+    }
+
+    #[test]
+    fn cycles_keep_every_entry_once() {
+        let sorted = sorted_names(&["a", "b", "c"], &[("a", "b"), ("b", "a")]);
+
+        assert_eq!(sorted, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn sub_paths_stay_with_their_entries() {
+        let sorted = sort_by_inheritance(&entries(&["a", "base"]), &parents(&[("a", "base")]));
+
+        assert_eq!(sorted[0].sub_path, "filament/base.json");
+        assert_eq!(sorted[1].sub_path, "filament/a.json");
+    }
+}
@@ -0,0 +1,821 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::configuration_loader::{
+    load_preset, ConfigNameAndPath, FilamentJsonSchema, PrinterModelJsonSchema,
+    PrinterVariantJsonSchema, ProcessJsonSchema, VendorJsonSchema,
+};
+use crate::file_store::file_hash;
+use crate::journal::Operation;
+use crate::overlay::{read_path, write_path};
+
+// Unchanged lines shown around each change of the diff
+const DIFF_CONTEXT_LINES: usize = 2;
+
+/// A problem that stopped a vendor operation, with the file it was found in.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VendorProblem {
+    pub path: String,
+    pub message: String,
+}
+
+impl VendorProblem {
+    pub fn new(path: &Path, message: String) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum PlannedAction {
+    Create,
+    Modify,
+    Delete,
+    Move,
+}
+
+/// One file written or removed by a plan. Copied and moved files name their
+/// `source`, rewritten files carry their new `content`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PlannedFile {
+    pub action: PlannedAction,
+    pub path: String,
+    pub source: Option<String>,
+    /// Hash of `source`, or of `path` for modified and deleted files, when the
+    /// plan was made. The plan is refused if it no longer matches.
+    pub expected_hash: Option<String>,
+    pub content: Option<String>,
+    /// The config type the written file is validated as, if it is a config.
+    pub config_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PresetRename {
+    pub config_type: String,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// A preset name referenced from another config that is rewritten.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReferenceChange {
+    pub path: String,
+    pub key: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+/// Everything a bulk operation will do, computed without touching the disk.
+/// Executing the plan applies exactly these changes.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VendorPlan {
+    pub command: String,
+    pub description: String,
+    /// The directory the plan works in, paths in the diff are relative to it.
+    pub root: String,
+    pub files: Vec<PlannedFile>,
+    pub renames: Vec<PresetRename>,
+    pub references: Vec<ReferenceChange>,
+    pub diff: String,
+}
+
+impl VendorPlan {
+    pub fn new(command: &str, description: String, root: &Path) -> Self {
+        Self {
+            command: command.into(),
+            description,
+            root: root.to_string_lossy().to_string(),
+            files: Vec::new(),
+            renames: Vec::new(),
+            references: Vec::new(),
+            diff: String::new(),
+        }
+    }
+
+    fn push_file(&mut self, file: PlannedFile) -> Result<(), VendorProblem> {
+        let path = Path::new(&file.path);
+
+        if self
+            .files
+            .iter()
+            .any(|other| Path::new(&other.path) == path)
+        {
+            return Err(VendorProblem::new(
+                path,
+                "The file would be written more than once".into(),
+            ));
+        }
+
+        self.files.push(file);
+        Ok(())
+    }
+
+    /// Plans a new file at `path`, a copy of `source` unless `content` is given.
+    pub fn create_file(
+        &mut self,
+        path: &Path,
+        source: Option<&Path>,
+        content: Option<String>,
+        config_type: Option<&str>,
+    ) -> Result<(), VendorProblem> {
+        let expected_hash = match source {
+            Some(source) => {
+                file_hash(&read_path(source)).map_err(|err| VendorProblem::new(source, err))?
+            }
+            None => None,
+        };
+
+        self.push_file(PlannedFile {
+            action: PlannedAction::Create,
+            path: path.to_string_lossy().to_string(),
+            source: source.map(|source| source.to_string_lossy().to_string()),
+            expected_hash,
+            content,
+            config_type: config_type.map(String::from),
+        })
+    }
+
+    /// Plans replacing the content of the existing file at `path`.
+    pub fn modify_file(
+        &mut self,
+        path: &Path,
+        content: String,
+        config_type: Option<&str>,
+    ) -> Result<(), VendorProblem> {
+        let expected_hash =
+            file_hash(&read_path(path)).map_err(|err| VendorProblem::new(path, err))?;
+
+        self.push_file(PlannedFile {
+            action: PlannedAction::Modify,
+            path: path.to_string_lossy().to_string(),
+            source: None,
+            expected_hash,
+            content: Some(content),
+            config_type: config_type.map(String::from),
+        })
+    }
+
+    /// Plans replacing the existing file at `path` with a copy of `source`.
+    pub fn replace_file(&mut self, path: &Path, source: &Path) -> Result<(), VendorProblem> {
+        let expected_hash =
+            file_hash(&read_path(path)).map_err(|err| VendorProblem::new(path, err))?;
+
+        self.push_file(PlannedFile {
+            action: PlannedAction::Modify,
+            path: path.to_string_lossy().to_string(),
+            source: Some(source.to_string_lossy().to_string()),
+            expected_hash,
+            content: None,
+            config_type: None,
+        })
+    }
+
+    /// Plans moving the file at `source` to `path`, with `content` written
+    /// over it when the moved file changes as well.
+    pub fn move_file(
+        &mut self,
+        path: &Path,
+        source: &Path,
+        content: Option<String>,
+        config_type: Option<&str>,
+    ) -> Result<(), VendorProblem> {
+        let expected_hash =
+            file_hash(&read_path(source)).map_err(|err| VendorProblem::new(source, err))?;
+
+        self.push_file(PlannedFile {
+            action: PlannedAction::Move,
+            path: path.to_string_lossy().to_string(),
+            source: Some(source.to_string_lossy().to_string()),
+            expected_hash,
+            content,
+            config_type: config_type.map(String::from),
+        })
+    }
+
+    /// Plans removing the file at `path`.
+    pub fn delete_file(&mut self, path: &Path) -> Result<(), VendorProblem> {
+        let expected_hash =
+            file_hash(&read_path(path)).map_err(|err| VendorProblem::new(path, err))?;
+
+        self.push_file(PlannedFile {
+            action: PlannedAction::Delete,
+            path: path.to_string_lossy().to_string(),
+            source: None,
+            expected_hash,
+            content: None,
+            config_type: None,
+        })
+    }
+
+    fn display_path(&self, path: &str) -> String {
+        Path::new(path)
+            .strip_prefix(&self.root)
+            .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+            .unwrap_or(path.to_string())
+    }
+
+    /// Renders the planned files as a unified diff for review.
+    pub fn render_diff(&self) -> String {
+        let mut diff = String::new();
+
+        for file in &self.files {
+            let path = self.display_path(&file.path);
+            let read =
+                |path: &str| fs::read_to_string(read_path(Path::new(path))).unwrap_or_default();
+
+            match (file.action, &file.source, &file.content) {
+                (PlannedAction::Create, Some(source), None) => {
+                    diff.push_str(&format!("copy {} -> {}\n", self.display_path(source), path));
+                }
+                (PlannedAction::Create, Some(source), Some(content)) => {
+                    diff.push_str(&format!(
+                        "--- {}\n+++ {}\n",
+                        self.display_path(source),
+                        path
+                    ));
+                    diff.push_str(&line_diff(&read(source), content));
+                }
+                (PlannedAction::Create, None, content) => {
+                    diff.push_str(&format!("--- /dev/null\n+++ {}\n", path));
+                    diff.push_str(&line_diff("", content.as_deref().unwrap_or_default()));
+                }
+                (PlannedAction::Modify, Some(source), None) => {
+                    diff.push_str(&format!(
+                        "replace {} <- {}\n",
+                        path,
+                        self.display_path(source)
+                    ));
+                }
+                (PlannedAction::Modify, _, content) => {
+                    diff.push_str(&format!("--- {}\n+++ {}\n", path, path));
+                    diff.push_str(&line_diff(
+                        &read(&file.path),
+                        content.as_deref().unwrap_or_default(),
+                    ));
+                }
+                (PlannedAction::Delete, _, _) => {
+                    diff.push_str(&format!("--- {}\n+++ /dev/null\n", path));
+                }
+                (PlannedAction::Move, Some(source), None) => {
+                    diff.push_str(&format!(
+                        "rename {} -> {}\n",
+                        self.display_path(source),
+                        path
+                    ));
+                }
+                (PlannedAction::Move, source, content) => {
+                    let source = source.as_deref().unwrap_or_default();
+                    diff.push_str(&format!(
+                        "--- {}\n+++ {}\n",
+                        self.display_path(source),
+                        path
+                    ));
+                    diff.push_str(&line_diff(
+                        &read(source),
+                        content.as_deref().unwrap_or_default(),
+                    ));
+                }
+            }
+        }
+
+        diff
+    }
+}
+
+/// A line based diff of `old` and `new` in unified format, without file headers.
+pub fn line_diff(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    // lengths of the longest common subsequences of the line suffixes
+    let mut common = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if old_lines[i] == new_lines[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    // (old index, new index, marker) of every line in order
+    let mut lines: Vec<(usize, usize, char)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_lines[i] == new_lines[j] {
+            lines.push((i, j, ' '));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || common[i + 1][j] >= common[i][j + 1]) {
+            lines.push((i, j, '-'));
+            i += 1;
+        } else {
+            lines.push((i, j, '+'));
+            j += 1;
+        }
+    }
+
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, _, marker))| *marker != ' ')
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut diff = String::new();
+    let mut index = 0;
+
+    while index < changed.len() {
+        let start = changed[index].saturating_sub(DIFF_CONTEXT_LINES);
+        let mut end = changed[index];
+
+        // changes close to each other share a hunk
+        while index < changed.len() && changed[index] <= end + 2 * DIFF_CONTEXT_LINES + 1 {
+            end = changed[index];
+            index += 1;
+        }
+        let end = (end + DIFF_CONTEXT_LINES + 1).min(lines.len());
+
+        let hunk = &lines[start..end];
+        let old_count = hunk.iter().filter(|line| line.2 != '+').count();
+        let new_count = hunk.iter().filter(|line| line.2 != '-').count();
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            hunk[0].0 + 1,
+            old_count,
+            hunk[0].1 + 1,
+            new_count
+        ));
+
+        for (old_index, new_index, marker) in hunk {
+            let text = match marker {
+                '+' => new_lines[*new_index],
+                _ => old_lines[*old_index],
+            };
+            diff.push_str(&format!("{}{}\n", marker, text));
+        }
+    }
+
+    diff
+}
+
+/// Loads the config at `path` as `config_type`, returning its name.
+fn load_config_name(config_type: &str, path: &str) -> Result<String, String> {
+    match config_type {
+        "printer" => load_preset::<PrinterVariantJsonSchema>(path).map(|config| config.name),
+        "printer-model" => load_preset::<PrinterModelJsonSchema>(path).map(|config| config.name),
+        "filament" => load_preset::<FilamentJsonSchema>(path).map(|config| config.name),
+        "process" => load_preset::<ProcessJsonSchema>(path).map(|config| config.name),
+        "vendor" => load_preset::<VendorJsonSchema>(path).map(|config| config.name),
+        _ => Err(format!("Unknown config type '{}'", config_type)),
+    }
+}
+
+// The files of the plan still have to be as they were when it was made
+fn verify_plan(plan: &VendorPlan) -> Vec<VendorProblem> {
+    let mut problems = Vec::new();
+
+    for file in &plan.files {
+        let path = Path::new(&file.path);
+
+        let check_res = match file.action {
+            PlannedAction::Create | PlannedAction::Move if read_path(path).exists() => {
+                Err("The file already exists".into())
+            }
+            PlannedAction::Create | PlannedAction::Move => match &file.source {
+                Some(source) => file_hash(&read_path(Path::new(source))).and_then(|hash| {
+                    if hash == file.expected_hash {
+                        Ok(())
+                    } else {
+                        Err(format!("'{}' was changed after the plan was made", source))
+                    }
+                }),
+                None => Ok(()),
+            },
+            PlannedAction::Modify | PlannedAction::Delete => {
+                file_hash(&read_path(path)).and_then(|hash| {
+                    if hash == file.expected_hash {
+                        Ok(())
+                    } else {
+                        Err("The file was changed after the plan was made".into())
+                    }
+                })
+            }
+        };
+
+        if let Err(err) = check_res {
+            problems.push(VendorProblem::new(path, err));
+        }
+    }
+
+    problems
+}
+
+/// The vendor files a plan creates together with their whole `<Vendor>/`
+/// directory. These vendors are staged as complete trees and moved into place
+/// with one rename each.
+fn new_vendor_files(plan: &VendorPlan) -> Vec<PathBuf> {
+    plan.files
+        .iter()
+        .filter(|file| {
+            file.action == PlannedAction::Create && file.config_type.as_deref() == Some("vendor")
+        })
+        .map(|file| PathBuf::from(&file.path))
+        .filter(|vendor_file_path| {
+            let vendor_directory_path = vendor_file_path.with_extension("");
+
+            !read_path(&vendor_directory_path).exists()
+                && plan.files.iter().all(|file| {
+                    file.action == PlannedAction::Create
+                        || !Path::new(&file.path).starts_with(&vendor_directory_path)
+                })
+        })
+        .collect()
+}
+
+/// The new vendor file `path` belongs to, if any.
+fn new_vendor_of<'a>(path: &Path, new_vendor_files: &'a [PathBuf]) -> Option<&'a PathBuf> {
+    new_vendor_files.iter().find(|vendor_file_path| {
+        path == vendor_file_path.as_path() || path.starts_with(vendor_file_path.with_extension(""))
+    })
+}
+
+// Writes the new content of every file into the staging directory. The files
+// of new vendors are laid out as their final tree below `vendors/`, files
+// moved as they are stay where they are until the commit
+fn stage_plan(
+    plan: &VendorPlan,
+    new_vendor_files: &[PathBuf],
+    staging_directory_path: &Path,
+) -> Result<Vec<Option<PathBuf>>, Vec<VendorProblem>> {
+    let mut problems = Vec::new();
+    let mut staged_paths = Vec::new();
+
+    for (index, file) in plan.files.iter().enumerate() {
+        let path = Path::new(&file.path);
+
+        if file.action == PlannedAction::Delete {
+            staged_paths.push(None);
+            continue;
+        }
+        if let (PlannedAction::Move, None, Some(source)) =
+            (file.action, &file.content, &file.source)
+        {
+            staged_paths.push(Some(read_path(Path::new(source))));
+            continue;
+        }
+
+        let staged_path = match new_vendor_of(path, new_vendor_files)
+            .and_then(|vendor_file_path| vendor_file_path.parent())
+            .and_then(|vendors_directory_path| path.strip_prefix(vendors_directory_path).ok())
+        {
+            Some(relative_path) => staged_vendor_path(staging_directory_path, relative_path),
+            None => {
+                let extension = path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .unwrap_or("bak");
+                staging_directory_path.join(format!("{}.{}", index, extension))
+            }
+        };
+
+        if let Some(parent) = staged_path.parent() {
+            if let Err(err) = fs::create_dir_all(parent) {
+                problems.push(VendorProblem::new(path, err.to_string()));
+                staged_paths.push(None);
+                continue;
+            }
+        }
+
+        let write_res = match (&file.content, &file.source) {
+            (Some(content), _) => fs::write(&staged_path, content),
+            (None, Some(source)) => {
+                fs::copy(read_path(Path::new(source)), &staged_path).map(|_| ())
+            }
+            (None, None) => fs::write(&staged_path, ""),
+        };
+
+        if let Err(err) = write_res {
+            problems.push(VendorProblem::new(path, err.to_string()));
+        }
+        staged_paths.push(Some(staged_path));
+    }
+
+    if problems.is_empty() {
+        Ok(staged_paths)
+    } else {
+        Err(problems)
+    }
+}
+
+fn staged_vendor_path(staging_directory_path: &Path, relative_path: &Path) -> PathBuf {
+    staging_directory_path.join("vendors").join(relative_path)
+}
+
+/// Checks that every staged config loads, and that the configs listed by a
+/// staged vendor exist after the plan and carry the name they are listed under.
+fn validate_staged_plan(plan: &VendorPlan, staged_paths: &[Option<PathBuf>]) -> Vec<VendorProblem> {
+    let mut problems = Vec::new();
+
+    let planned: HashMap<PathBuf, usize> = plan
+        .files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| (PathBuf::from(&file.path), index))
+        .collect();
+
+    for (file, staged_path) in plan.files.iter().zip(staged_paths) {
+        let (Some(config_type), Some(staged_path)) = (&file.config_type, staged_path) else {
+            continue;
+        };
+        let path = Path::new(&file.path);
+
+        if let Err(err) = load_config_name(config_type, &staged_path.to_string_lossy()) {
+            problems.push(VendorProblem::new(path, err));
+            continue;
+        }
+
+        if config_type != "vendor" {
+            continue;
+        }
+
+        let Ok(vendor_config) = load_preset::<VendorJsonSchema>(&staged_path.to_string_lossy())
+        else {
+            continue;
+        };
+        let vendor_directory_path = path.with_extension("");
+
+        let lists: [(&str, &Option<Vec<ConfigNameAndPath>>); 4] = [
+            ("printer", &vendor_config.machine_list),
+            ("printer-model", &vendor_config.machine_model_list),
+            ("filament", &vendor_config.filament_list),
+            ("process", &vendor_config.process_list),
+        ];
+
+        for (list_type, list) in lists {
+            for entry in list.iter().flatten() {
+                let config_path = vendor_directory_path.join(&entry.sub_path);
+
+                let readable_path = match planned.get(&config_path) {
+                    Some(index) => staged_paths[*index].clone(),
+                    None if read_path(&config_path).is_file() => Some(config_path.clone()),
+                    None => None,
+                };
+
+                let Some(readable_path) = readable_path else {
+                    problems.push(VendorProblem::new(
+                        path,
+                        format!("'{}' is listed but does not exist", entry.sub_path),
+                    ));
+                    continue;
+                };
+
+                match load_config_name(list_type, &readable_path.to_string_lossy()) {
+                    Ok(name) if name != entry.name => problems.push(VendorProblem::new(
+                        &config_path,
+                        format!(
+                            "The config is listed as '{}' but is named '{}'",
+                            entry.name, name
+                        ),
+                    )),
+                    Ok(_) => (),
+                    // configs of the plan are reported above already
+                    Err(_) if planned.contains_key(&config_path) => (),
+                    Err(err) => problems.push(VendorProblem::new(&config_path, err)),
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+// New vendors are moved into place whole: the staged `<Vendor>/` directory
+// and the vendor file with one rename each, so they either appear complete or
+// not at all. The files of existing vendors are then written one at a time, a
+// failure part way is put back by the caller rolling the operation back.
+fn commit_plan(
+    plan: &VendorPlan,
+    new_vendor_files: &[PathBuf],
+    staged_paths: &[Option<PathBuf>],
+    staging_directory_path: &Path,
+    operation: &mut Operation,
+) -> Result<(), String> {
+    let mut moves = Vec::new();
+    for vendor_file_path in new_vendor_files {
+        let Some(vendors_directory_path) = vendor_file_path.parent() else {
+            continue;
+        };
+        let vendor_directory_path = vendor_file_path.with_extension("");
+
+        for path in [&vendor_directory_path, vendor_file_path] {
+            let staged_path = path
+                .strip_prefix(vendors_directory_path)
+                .map(|relative_path| staged_vendor_path(staging_directory_path, relative_path))
+                .map_err(|err| err.to_string())?;
+
+            // a vendor without configs has no directory to move
+            if staged_path.exists() {
+                moves.push((staged_path, path.clone()));
+            }
+        }
+    }
+    operation.move_into_place(&moves)?;
+
+    for (file, staged_path) in plan.files.iter().zip(staged_paths) {
+        let path = Path::new(&file.path);
+        if new_vendor_of(path, new_vendor_files).is_some() {
+            continue;
+        }
+
+        if let (PlannedAction::Move, Some(source)) = (file.action, &file.source) {
+            operation.rename(Path::new(source), path)?;
+            if file.content.is_none() {
+                continue;
+            }
+        }
+
+        match staged_path {
+            Some(staged_path) => {
+                let content = fs::read(staged_path).map_err(|err| {
+                    format!("Could not read '{}': {}", staged_path.display(), err)
+                })?;
+                operation.write(path, &content)?;
+            }
+            None => operation.remove_file(path)?,
+        }
+    }
+
+    Ok(())
+}
+
+// The directories of deleted and moved vendors go once they are empty, undo
+// creates them again with their files
+fn remove_emptied_directories(plan: &VendorPlan) {
+    let deleted_files: Vec<&Path> = plan
+        .files
+        .iter()
+        .filter_map(|file| match file.action {
+            PlannedAction::Delete => Some(file.path.as_str()),
+            PlannedAction::Move => file.source.as_deref(),
+            _ => None,
+        })
+        .map(Path::new)
+        .collect();
+
+    let vendor_directories: Vec<PathBuf> = deleted_files
+        .iter()
+        .map(|path| path.with_extension(""))
+        .collect();
+
+    for path in deleted_files {
+        for directory in path.ancestors().skip(1) {
+            let in_vendor_directory = vendor_directories
+                .iter()
+                .any(|vendor_directory| directory.starts_with(vendor_directory));
+
+            if !in_vendor_directory || fs::remove_dir(directory).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Applies `plan` as part of `operation`. The plan is checked against the
+/// disk, and every file is staged and validated before anything is changed.
+/// New vendors are then renamed into place as a whole, and a failure while
+/// writing the other files puts back everything written so far.
+pub fn execute_plan(
+    plan: &VendorPlan,
+    operation: &mut Operation,
+) -> Result<(), Vec<VendorProblem>> {
+    // plans come from the frontend, every path is checked before anything is
+    // staged or removed
+    let checked_paths = std::iter::once(&plan.root)
+        .chain(plan.files.iter().map(|file| &file.path))
+        .chain(plan.files.iter().filter_map(|file| file.source.as_ref()))
+        .try_for_each(|path| operation.check_path(Path::new(path)).map(|_| ()));
+    if let Err(err) = checked_paths {
+        return Err(vec![VendorProblem::new(Path::new(&plan.root), err)]);
+    }
+
+    let problems = verify_plan(plan);
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    let root = Path::new(&plan.root);
+
+    // next to the target files, so that staging fails early on a read-only
+    // drive. The command names the directory, so it is reduced to a plain name
+    let command: String = plan
+        .command
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    let staging_directory_path =
+        write_path(root).join(format!(".{}.staging-{}", command, std::process::id()));
+    fs::remove_dir_all(&staging_directory_path).ok();
+    fs::create_dir_all(&staging_directory_path)
+        .map_err(|err| vec![VendorProblem::new(&staging_directory_path, err.to_string())])?;
+
+    let new_vendor_files = new_vendor_files(plan);
+
+    let execute_res = stage_plan(plan, &new_vendor_files, &staging_directory_path)
+        .and_then(|staged_paths| {
+            let problems = validate_staged_plan(plan, &staged_paths);
+            if problems.is_empty() {
+                Ok(staged_paths)
+            } else {
+                Err(problems)
+            }
+        })
+        .and_then(|staged_paths| {
+            commit_plan(
+                plan,
+                &new_vendor_files,
+                &staged_paths,
+                &staging_directory_path,
+                operation,
+            )
+            .map_err(|err| {
+                operation.rollback();
+                vec![VendorProblem::new(root, err)]
+            })
+        });
+
+    fs::remove_dir_all(&staging_directory_path).ok();
+
+    if execute_res.is_ok() {
+        remove_emptied_directories(plan);
+    }
+
+    execute_res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_texts_have_no_diff() {
+        assert_eq!(line_diff("a\nb\n", "a\nb\n"), "");
+        assert_eq!(line_diff("", ""), "");
+    }
+
+    #[test]
+    fn changed_line_is_shown_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n";
+        let new = "1\n2\n3\nfour\n5\n6\n7\n";
+
+        assert_eq!(
+            line_diff(old, new),
+            "@@ -2,5 +2,5 @@\n 2\n 3\n-4\n+four\n 5\n 6\n"
+        );
+    }
+
+    #[test]
+    fn new_and_removed_files() {
+        assert_eq!(line_diff("", "a\nb"), "@@ -1,0 +1,2 @@\n+a\n+b\n");
+        assert_eq!(line_diff("a\nb", ""), "@@ -1,2 +1,0 @@\n-a\n-b\n");
+    }
+
+    #[test]
+    fn distant_changes_get_their_own_hunks() {
+        let old: String = (1..=20).map(|line| format!("{}\n", line)).collect();
+        let new: String = (1..=20)
+            .map(|line| match line {
+                2 => "two\n".to_string(),
+                19 => "nineteen\n".to_string(),
+                _ => format!("{}\n", line),
+            })
+            .collect();
+        let diff = line_diff(&old, &new);
+
+        assert_eq!(diff.matches("@@ -").count(), 2, "{}", diff);
+        assert!(diff.contains("-2\n+two\n"));
+        assert!(diff.contains("-19\n+nineteen\n"));
+    }
+
+    #[test]
+    fn close_changes_share_a_hunk() {
+        let old = "1\n2\n3\n4\n5\n6\n";
+        let new = "one\n2\n3\n4\nfive\n6\n";
+
+        assert_eq!(line_diff(old, new).matches("@@ -").count(), 1);
+    }
+}
@@ -0,0 +1,494 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::config_inheritance::detach_from_parent;
+use crate::configuration_loader::{ConfigNameAndPath, VendorJsonSchema};
+use crate::json_edit::edited_json;
+use crate::name_references::{name_field, name_fields};
+use crate::overlay::read_path;
+use crate::vendor_duplication::{config_lists, new_vendor_paths, to_edited_json};
+use crate::vendor_merge::{load_vendor, sort_by_inheritance};
+use crate::vendor_plan::{VendorPlan, VendorProblem};
+
+// Keys of a printer model naming the files it shows, relative to the vendor
+// directory. The cover image is named after the model instead.
+pub const MODEL_ASSET_KEYS: [&str; 3] = ["bed_model", "bed_texture", "hotend_model"];
+
+// Name fields of printers and printer models picking the presets they start with
+const DEFAULT_PRESET_KEYS: [&str; 3] = [
+    "default_print_profile",
+    "default_filament_profile",
+    "default_materials",
+];
+
+/// What happens to a base preset that the split out printers and the printers
+/// staying behind both inherit from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum SharedBaseStrategy {
+    /// Both vendors get a copy of the base.
+    Duplicate,
+    /// The base stays in the old vendor only, its values are folded into the
+    /// presets of the new vendor that inherited them.
+    Keep,
+}
+
+struct VendorConfig {
+    config_type: &'static str,
+    entry: ConfigNameAndPath,
+    data: String,
+    values: Map<String, Value>,
+}
+
+impl VendorConfig {
+    fn names(&self, key: &str) -> Vec<String> {
+        name_field(self.config_type, key)
+            .and_then(|field| field.names(&self.values).ok())
+            .unwrap_or_default()
+    }
+
+    fn parent(&self) -> Option<&str> {
+        self.values
+            .get("inherits")
+            .and_then(Value::as_str)
+            .filter(|parent| !parent.is_empty())
+    }
+
+    // the presets a printer or printer model picks by default
+    fn defaults(&self) -> Vec<(&'static str, String)> {
+        name_fields(self.config_type)
+            .filter(|field| DEFAULT_PRESET_KEYS.contains(&field.key))
+            .flat_map(|field| {
+                let target_type = field.target_type(self.config_type);
+                self.names(field.key)
+                    .into_iter()
+                    .filter_map(move |name| Some((target_type?, name)))
+            })
+            .collect()
+    }
+}
+
+struct VendorConfigs {
+    configs: Vec<VendorConfig>,
+    index: HashMap<(&'static str, String), usize>,
+}
+
+impl VendorConfigs {
+    fn find(&self, config_type: &'static str, name: &str) -> Option<usize> {
+        self.index.get(&(config_type, name.to_string())).copied()
+    }
+
+    // the bases of a config within the vendor, closest first
+    fn ancestors(&self, config: usize) -> Vec<usize> {
+        let mut ancestors = Vec::new();
+        let mut current = &self.configs[config];
+
+        while let Some(parent) = current
+            .parent()
+            .and_then(|parent| self.find(current.config_type, parent))
+        {
+            if parent == config || ancestors.contains(&parent) {
+                break;
+            }
+            ancestors.push(parent);
+            current = &self.configs[parent];
+        }
+
+        ancestors
+    }
+
+    // what the given configs need to load: their bases and default presets
+    fn needed_by(&self, configs: impl Iterator<Item = usize>) -> HashSet<usize> {
+        configs
+            .flat_map(|config| {
+                let defaults = self.configs[config]
+                    .defaults()
+                    .into_iter()
+                    .filter_map(|(config_type, name)| self.find(config_type, &name));
+                let mut needed = self.ancestors(config);
+                needed.extend(defaults);
+                needed
+            })
+            .collect()
+    }
+}
+
+fn load_configs(
+    vendor_config: &mut VendorJsonSchema,
+    directory: &Path,
+) -> Result<VendorConfigs, Vec<VendorProblem>> {
+    let mut configs = Vec::new();
+    let mut problems = Vec::new();
+
+    for (config_type, list) in config_lists(vendor_config) {
+        for entry in list.iter().flatten() {
+            let path = directory.join(&entry.sub_path);
+            let loaded = fs::read_to_string(read_path(&path))
+                .map_err(|_| format!("Config '{}' could not be read", entry.name))
+                .and_then(|data| {
+                    serde_json::from_str(&data)
+                        .map(|values| (data, values))
+                        .map_err(|err| format!("Malformed or invalid JSON: {}", err))
+                });
+
+            match loaded {
+                Ok((data, values)) => configs.push(VendorConfig {
+                    config_type,
+                    entry: entry.clone(),
+                    data,
+                    values,
+                }),
+                Err(err) => problems.push(VendorProblem::new(&path, err)),
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    let index = configs
+        .iter()
+        .enumerate()
+        .map(|(position, config)| ((config.config_type, config.entry.name.clone()), position))
+        .collect();
+
+    Ok(VendorConfigs { configs, index })
+}
+
+// `compatible_printers` of `values` limited to, or without, `printers`
+fn filter_compatible_printers(
+    values: &mut Map<String, Value>,
+    printers: &HashSet<String>,
+    keep_listed: bool,
+) {
+    if let Some(Value::Array(names)) = values.get_mut("compatible_printers") {
+        names.retain(|name| {
+            name.as_str()
+                .is_some_and(|name| printers.contains(name) == keep_listed)
+        });
+    }
+}
+
+/// The files a printer model shows, relative to the vendor directory.
+pub fn model_assets(values: &Map<String, Value>) -> Vec<String> {
+    let cover = values
+        .get("name")
+        .and_then(Value::as_str)
+        .map(|name| format!("{}_cover.png", name));
+
+    MODEL_ASSET_KEYS
+        .iter()
+        .filter_map(|key| values.get(*key)?.as_str())
+        .filter(|asset| !asset.is_empty())
+        .map(String::from)
+        .chain(cover)
+        .collect()
+}
+
+/// Plans moving the printer models `machine_models` of the vendor at `path`
+/// into a new vendor `new_dir_name`, with their printers and the filament and
+/// process presets compatible with those printers or picked by them as
+/// defaults, together with every base preset they inherit from. Presets also
+/// used by the printers staying behind are copied instead of moved, shared
+/// bases are handled by `shared_bases`. Presets without `compatible_printers`
+/// suit every printer, they are copied when the printers pick them only.
+pub fn plan_split_vendor(
+    path: &Path,
+    machine_models: &[String],
+    new_dir_name: &str,
+    shared_bases: SharedBaseStrategy,
+) -> Result<VendorPlan, Vec<VendorProblem>> {
+    let (new_directory_path, new_file_path) =
+        new_vendor_paths(path, new_dir_name).map_err(|problem| vec![problem])?;
+
+    let source_directory = path.with_extension("");
+    let source_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let (vendor_data, mut vendor_config) = load_vendor(path).map_err(|problem| vec![problem])?;
+    let vendor = load_configs(&mut vendor_config, &source_directory)?;
+    let configs = &vendor.configs;
+
+    let mut problems = Vec::new();
+    let mut taken: BTreeSet<usize> = BTreeSet::new();
+
+    for model in machine_models {
+        match vendor.find("printer-model", model) {
+            Some(config) => {
+                taken.insert(config);
+            }
+            None => problems.push(VendorProblem::new(
+                path,
+                format!("'{}' is not a printer model of the vendor", model),
+            )),
+        }
+    }
+    if taken.is_empty() && problems.is_empty() {
+        problems.push(VendorProblem::new(
+            path,
+            "Select at least one printer model".into(),
+        ));
+    }
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    let printers: HashSet<String> = configs
+        .iter()
+        .enumerate()
+        .filter(|(_, config)| {
+            config.config_type == "printer"
+                && config
+                    .values
+                    .get("printer_model")
+                    .and_then(Value::as_str)
+                    .is_some_and(|model| machine_models.iter().any(|name| name == model))
+        })
+        .map(|(position, config)| {
+            taken.insert(position);
+            config.entry.name.clone()
+        })
+        .collect();
+
+    // presets of other printers too are copied, the others move
+    let mut shared: HashSet<usize> = HashSet::new();
+    for (position, config) in configs.iter().enumerate() {
+        let compatible_printers = config.names("compatible_printers");
+        if compatible_printers
+            .iter()
+            .any(|name| printers.contains(name))
+        {
+            taken.insert(position);
+            if compatible_printers
+                .iter()
+                .any(|name| !printers.contains(name))
+            {
+                shared.insert(position);
+            }
+        }
+    }
+    // with everything they need, presets that suit any printer are copied
+    loop {
+        let needed = vendor.needed_by(taken.iter().copied());
+        let before = taken.len();
+        taken.extend(needed);
+        if taken.len() == before {
+            break;
+        }
+    }
+    shared.extend(taken.iter().copied().filter(|config| {
+        let config = &configs[*config];
+        matches!(config.config_type, "filament" | "process")
+            && !config.values.contains_key("compatible_printers")
+            && config.values.get("instantiation").and_then(Value::as_str) != Some("false")
+    }));
+
+    // whatever the staying configs need stays as well
+    let mut moved: BTreeSet<usize> = taken
+        .iter()
+        .copied()
+        .filter(|config| !shared.contains(config))
+        .collect();
+    loop {
+        let needed = vendor.needed_by((0..configs.len()).filter(|config| !moved.contains(config)));
+        let before = moved.len();
+        moved.retain(|config| !needed.contains(config));
+        if moved.len() == before {
+            break;
+        }
+    }
+
+    let omitted: HashSet<usize> = match shared_bases {
+        SharedBaseStrategy::Keep => taken
+            .iter()
+            .copied()
+            .filter(|config| {
+                !moved.contains(config)
+                    && configs[*config]
+                        .values
+                        .get("instantiation")
+                        .and_then(Value::as_str)
+                        == Some("false")
+            })
+            .collect(),
+        SharedBaseStrategy::Duplicate => HashSet::new(),
+    };
+
+    let mut plan = VendorPlan::new(
+        "split_vendor",
+        format!(
+            "Split {} out of vendor '{}' into '{}'",
+            machine_models.join(", "),
+            source_name,
+            new_dir_name
+        ),
+        path.parent().unwrap_or(path),
+    );
+
+    let mut new_lists: HashMap<&str, Vec<ConfigNameAndPath>> = HashMap::new();
+    let mut parents: HashMap<&str, HashMap<String, String>> = HashMap::new();
+
+    for &position in taken.iter().filter(|config| !omitted.contains(config)) {
+        let config = &configs[position];
+        let mut values = config.values.clone();
+
+        if !moved.contains(&position) {
+            filter_compatible_printers(&mut values, &printers, true);
+        }
+        if config.config_type == "printer-model" {
+            values.insert("family".into(), Value::String(new_dir_name.into()));
+        }
+        while let Some(parent) = values
+            .get("inherits")
+            .and_then(Value::as_str)
+            .and_then(|parent| vendor.find(config.config_type, parent))
+            .filter(|parent| omitted.contains(parent))
+        {
+            detach_from_parent(&mut values, &configs[parent].values);
+        }
+
+        if let Some(parent) = values.get("inherits").and_then(Value::as_str) {
+            parents
+                .entry(config.config_type)
+                .or_default()
+                .insert(config.entry.name.clone(), parent.into());
+        }
+
+        let source_path = source_directory.join(&config.entry.sub_path);
+        let planned = edited_json(Some(&config.data), &Value::Object(values))
+            .map_err(|err| VendorProblem::new(&source_path, err))
+            .and_then(|content| {
+                plan.create_file(
+                    &new_directory_path.join(&config.entry.sub_path),
+                    Some(&source_path),
+                    Some(content),
+                    Some(config.config_type),
+                )
+            });
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+
+        new_lists
+            .entry(config.config_type)
+            .or_default()
+            .push(config.entry.clone());
+    }
+
+    // the old vendor loses the moved presets and the split printers
+    for &position in &taken {
+        let config = &configs[position];
+        let source_path = source_directory.join(&config.entry.sub_path);
+
+        let planned = if moved.contains(&position) {
+            plan.delete_file(&source_path)
+        } else if config
+            .names("compatible_printers")
+            .iter()
+            .any(|name| printers.contains(name))
+        {
+            let mut values = config.values.clone();
+            filter_compatible_printers(&mut values, &printers, false);
+            edited_json(Some(&config.data), &Value::Object(values))
+                .map_err(|err| VendorProblem::new(&source_path, err))
+                .and_then(|content| {
+                    plan.modify_file(&source_path, content, Some(config.config_type))
+                })
+        } else {
+            Ok(())
+        };
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+    }
+
+    // the files shown by the models, moved unless a staying model shows them too
+    let staying_assets: HashSet<String> = configs
+        .iter()
+        .enumerate()
+        .filter(|(position, config)| {
+            config.config_type == "printer-model" && !moved.contains(position)
+        })
+        .flat_map(|(_, config)| model_assets(&config.values))
+        .collect();
+    let moved_assets: BTreeSet<String> = taken
+        .iter()
+        .filter(|config| configs[**config].config_type == "printer-model")
+        .flat_map(|config| model_assets(&configs[*config].values))
+        .collect();
+
+    for asset in moved_assets {
+        let asset_path = source_directory.join(&asset);
+        if !read_path(&asset_path).is_file() {
+            continue;
+        }
+
+        let mut planned = plan.create_file(
+            &new_directory_path.join(&asset),
+            Some(&asset_path),
+            None,
+            None,
+        );
+        if !staying_assets.contains(&asset) {
+            planned = planned.and_then(|_| plan.delete_file(&asset_path));
+        }
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+    }
+
+    let mut new_vendor_config = vendor_config.clone();
+    new_vendor_config.name = new_dir_name.into();
+
+    for (config_type, list) in config_lists(&mut vendor_config) {
+        if let Some(entries) = list {
+            entries.retain(|entry| {
+                vendor
+                    .find(config_type, &entry.name)
+                    .is_none_or(|config| !moved.contains(&config))
+            });
+        }
+    }
+    for (config_type, list) in config_lists(&mut new_vendor_config) {
+        let entries = new_lists.remove(config_type).unwrap_or_default();
+        let list_parents = parents.remove(config_type).unwrap_or_default();
+        *list = Some(sort_by_inheritance(&entries, &list_parents));
+    }
+
+    for (vendor_path, content, planned_as_new) in [
+        (path, to_edited_json(&vendor_data, &vendor_config), false),
+        (
+            new_file_path.as_path(),
+            to_edited_json(&vendor_data, &new_vendor_config),
+            true,
+        ),
+    ] {
+        let planned = content
+            .map_err(|err| VendorProblem::new(vendor_path, err))
+            .and_then(|content| {
+                if planned_as_new {
+                    plan.create_file(vendor_path, Some(path), Some(content), Some("vendor"))
+                } else {
+                    plan.modify_file(vendor_path, content, Some("vendor"))
+                }
+            });
+        if let Err(problem) = planned {
+            problems.push(problem);
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    plan.diff = plan.render_diff();
+    Ok(plan)
+}
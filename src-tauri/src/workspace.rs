@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::overlay::overlay_directory;
+use crate::vendor_duplication::{SYSTEM_DIRECTORY, USER_DIRECTORY};
+
+// Where an installation keeps its profiles, on macOS inside of the app bundle
+const INSTALLED_PROFILES_DIRECTORIES: [&str; 2] =
+    ["resources/profiles", "Contents/Resources/profiles"];
+
+/// The directories commands may write to: the configured OrcaSlicer
+/// installation and data directories, the export folders picked by the user
+/// and the overlay directory. Every path coming from the frontend is resolved and checked against
+/// them before anything is changed on disk.
+#[derive(Default)]
+pub struct WorkspaceRoots {
+    configured_roots: RwLock<Vec<PathBuf>>,
+    export_roots: RwLock<Vec<PathBuf>>,
+}
+
+// Resolves symlinks and `.` in the part of `path` that exists. The rest does
+// not exist yet, so it cannot be a link.
+pub fn resolve_path(path: &Path) -> Result<PathBuf, String> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+
+    while !existing.exists() {
+        let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+            return Err(format!("'{}' cannot be resolved", path.display()));
+        };
+        missing.push(name);
+        existing = parent;
+    }
+
+    let mut resolved = fs::canonicalize(existing)
+        .map_err(|err| format!("'{}' cannot be resolved: {}", path.display(), err))?;
+    resolved.extend(missing.into_iter().rev());
+
+    Ok(resolved)
+}
+
+fn resolve_root(root: &str) -> Result<PathBuf, String> {
+    let root_path = Path::new(root);
+
+    if !root_path.is_dir() {
+        return Err(format!("Directory '{}' does not exist", root));
+    }
+
+    fs::canonicalize(root_path).map_err(|err| format!("'{}' cannot be resolved: {}", root, err))
+}
+
+// A configured directory has to be an OrcaSlicer one, so that a wrong setting
+// cannot open up the whole disk
+fn resolve_orca_directory(
+    directory: &str,
+    kind: &str,
+    subdirectories: &[&str],
+) -> Result<PathBuf, String> {
+    let resolved_directory = resolve_root(directory)?;
+
+    if resolved_directory.parent().is_none() {
+        return Err(format!(
+            "'{}' is the root of a filesystem, not an OrcaSlicer {}",
+            directory, kind
+        ));
+    }
+
+    if !subdirectories
+        .iter()
+        .any(|subdirectory| resolved_directory.join(subdirectory).is_dir())
+    {
+        return Err(format!(
+            "'{}' is not an OrcaSlicer {}, it has no '{}' directory",
+            directory,
+            kind,
+            subdirectories.join("' or '")
+        ));
+    }
+
+    Ok(resolved_directory)
+}
+
+impl WorkspaceRoots {
+    /// Replaces the configured OrcaSlicer installation and data directories.
+    /// Directories that do not exist or are not laid out like OrcaSlicer ones
+    /// are left out and reported.
+    pub fn set_roots(
+        &self,
+        installation_directory: Option<String>,
+        data_directory: Option<String>,
+    ) -> Result<(), String> {
+        let mut resolved_roots = Vec::new();
+        let mut problems = Vec::new();
+
+        let installation_root = installation_directory.map(|directory| {
+            resolve_orca_directory(&directory, "installation", &INSTALLED_PROFILES_DIRECTORIES)
+        });
+        let data_root = data_directory.map(|directory| {
+            resolve_orca_directory(
+                &directory,
+                "data directory",
+                &[SYSTEM_DIRECTORY, USER_DIRECTORY],
+            )
+        });
+
+        for root in installation_root.into_iter().chain(data_root) {
+            match root {
+                Ok(resolved_root) => resolved_roots.push(resolved_root),
+                Err(err) => problems.push(err),
+            }
+        }
+
+        *self.configured_roots.write().unwrap() = resolved_roots;
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("\n"))
+        }
+    }
+
+    /// The configured directories, which make up the workspace.
+    pub fn roots(&self) -> Vec<String> {
+        self.configured_roots
+            .read()
+            .unwrap()
+            .iter()
+            .map(|root| root.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Allows writing to a folder the user picked for an export.
+    pub fn add_export_root(&self, root: &Path) -> Result<(), String> {
+        let resolved_root = resolve_root(&root.to_string_lossy())?;
+
+        let mut export_roots = self.export_roots.write().unwrap();
+        if !export_roots.contains(&resolved_root) {
+            export_roots.push(resolved_root);
+        }
+
+        Ok(())
+    }
+
+    /// Resolves `path` and checks that it lies inside one of the directories.
+    /// Relative paths, `..` and symlinks leading elsewhere are rejected.
+    pub fn check_path(&self, path: &Path) -> Result<PathBuf, String> {
+        if !path.is_absolute() {
+            return Err(format!("'{}' is not an absolute path", path.display()));
+        }
+
+        if path
+            .components()
+            .any(|component| component == Component::ParentDir)
+        {
+            return Err(format!("'{}' must not contain '..'", path.display()));
+        }
+
+        let configured_roots = self.configured_roots.read().unwrap();
+        let export_roots = self.export_roots.read().unwrap();
+        let overlay_root = overlay_directory();
+
+        if configured_roots.is_empty() && export_roots.is_empty() {
+            return Err(
+                "No directories are configured yet, set the OrcaSlicer installation or data directory first"
+                    .into(),
+            );
+        }
+
+        let resolved = resolve_path(path)?;
+
+        if configured_roots
+            .iter()
+            .chain(export_roots.iter())
+            .chain(overlay_root.iter())
+            .any(|root| resolved.starts_with(root))
+        {
+            Ok(resolved)
+        } else {
+            Err(format!(
+                "'{}' is outside of the OrcaSlicer installation and data directories",
+                path.display()
+            ))
+        }
+    }
+}
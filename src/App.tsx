@@ -1,9 +1,10 @@
 import { useHookstate } from "@hookstate/core";
 import { platform } from "@tauri-apps/api/os";
+import { invoke } from "@tauri-apps/api/tauri";
 import { Tooltip } from "radix-ui";
 import { useEffect } from "react";
 import { Route, HashRouter as Router, Routes } from "react-router-dom";
-import { ToastContainer } from "react-toastify";
+import { toast, ToastContainer } from "react-toastify";
 import "./App.css";
 import BackgroundPlate from "./components/background-plate";
 import EditConfig from "./edit-config";
@@ -12,6 +13,7 @@ import {
   dataFilamentConfigLoader,
   dataPrinterConfigLoader,
   dataProcessConfigLoader,
+  getDirectoryFromTypeAndLocation,
   installedFilamentConfigLoader,
   installedModelConfigLoader,
   installedPrinterConfigLoader,
@@ -28,6 +30,7 @@ function App() {
   const {
     orcaInstallationPath,
     orcaDataDirectory,
+    orcaOverlayDirectory,
     installedVendorConfigs: vendorConfigs,
     os,
   } = configState;
@@ -41,12 +44,42 @@ function App() {
     });
   }, []);
 
+  // The backend only writes inside these directories
   useEffect(() => {
-    installedVendorConfigLoader();
+    invoke("set_workspace_roots", {
+      installationDirectory: orcaInstallationPath.get(),
+      dataDirectory: orcaDataDirectory.get(),
+    }).catch((err: any) => {
+      toast(err.toString(), { type: "error" });
+    });
+  }, [orcaInstallationPath, orcaDataDirectory]);
 
-    selectedConfigs.set(new Set());
-    selectedConfigType.set(undefined);
-  }, [orcaInstallationPath]);
+  // With an overlay directory the installed profiles are only read, changes
+  // go there
+  useEffect(() => {
+    const overlayDirectory = orcaOverlayDirectory.get();
+    const settings =
+      orcaInstallationPath.get() && overlayDirectory
+        ? {
+            base_directory: getDirectoryFromTypeAndLocation(
+              "vendor",
+              "installed"
+            ),
+            overlay_directory: overlayDirectory,
+          }
+        : null;
+
+    invoke("set_overlay_directory", { settings })
+      .catch((err: any) => {
+        toast(err.toString(), { type: "error" });
+      })
+      .finally(() => {
+        installedVendorConfigLoader();
+
+        selectedConfigs.set(new Set());
+        selectedConfigType.set(undefined);
+      });
+  }, [orcaInstallationPath, orcaOverlayDirectory]);
 
   useEffect(() => {
     loadedSystemModelConfigLoader();
@@ -97,6 +97,7 @@ export default function ConfigItem({
     selectedConfigs,
     selectedConfigType,
     propertyCopyPopover,
+    vendorCopyPopover,
   } = useHookstate(appState);
 
   const [isSelected, setIsSelected] = useState(
@@ -150,6 +151,26 @@ export default function ConfigItem({
     optionsMenuItems.push(duplicationMenuItem);
   }
 
+  if (
+    allowDuplication &&
+    configLocation === "installed" &&
+    family &&
+    type !== "vendor" &&
+    type !== "printer-model"
+  ) {
+    const onClickVendorCopyItem = async () => {
+      vendorCopyPopover.arguments.set({ name, type, family });
+      vendorCopyPopover.newFamily.set(undefined);
+      vendorCopyPopover.visible.set(true);
+    };
+
+    optionsMenuItems.push({
+      icon: HiOutlineDocumentDuplicate,
+      onClick: onClickVendorCopyItem,
+      text: "Copy to vendor with ancestors",
+    });
+  }
+
   if (
     allowSelection &&
     selectedConfigs.get().size > 0 &&
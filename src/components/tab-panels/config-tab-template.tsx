@@ -3,8 +3,10 @@ import { VscNewFile } from "react-icons/vsc";
 import { useNavigate } from "react-router-dom";
 import { ConfigType, newFile } from "../../lib/commons";
 import TopButton from "./config-list/config-item-components/top-button";
+import DeletionPopover from "./input-components/deletion-popover";
 import DuplicationPopover from "./input-components/duplication-popover";
 import PropertyCopyPopover from "./input-components/property-copy-popover";
+import VendorCopyPopover from "./input-components/vendor-copy-popover";
 
 export default function ConfigTabTemplate({
   installedConfigs,
@@ -23,6 +25,8 @@ export default function ConfigTabTemplate({
     <div className="flex min-h-0 h-full">
       <DuplicationPopover />
       <PropertyCopyPopover />
+      <DeletionPopover />
+      <VendorCopyPopover />
       <div className="flex flex-col min-h-0 w-[50%] h-full mr-2">
         <div className="font-semibold text-text-primary text-2xl mb-3 pl-3">
           Installation Directory
@@ -1,5 +1,7 @@
 import { State, useHookstate } from "@hookstate/core";
 import { open } from "@tauri-apps/api/dialog";
+import { invoke } from "@tauri-apps/api/tauri";
+import { toast } from "react-toastify";
 import { globalState } from "../../lib/state-store";
 import FieldButton from "./field-button";
 import InputComponent from "./input-component";
@@ -8,6 +10,7 @@ export default function FileLoader() {
   const {
     orcaInstallationPath,
     orcaDataDirectory,
+    orcaOverlayDirectory,
     errLoadingInstallationPath,
     errLoadingDataPath,
     os,
@@ -31,6 +34,19 @@ export default function FileLoader() {
     stateObj.set(undefined);
   };
 
+  // writes the installation with the overlay applied as a new profiles tree
+  const materializeOverlay = async () => {
+    try {
+      const folder = await invoke<string>("pick_folder", {});
+      const target = folder + "/profiles";
+
+      await invoke("materialize_overlay", { target });
+      toast(`Wrote the merged profiles to ${target}`, { type: "success" });
+    } catch (err: any) {
+      toast(err.toString(), { type: "error" });
+    }
+  };
+
   return (
     <>
       <InputComponent
@@ -63,6 +79,27 @@ export default function FileLoader() {
         err={errLoadingDataPath.get()}
         allowEdit
       />
+      <InputComponent
+        label="Overlay Directory (for read-only installations)"
+        type="button"
+        onClick={() => handleClick(orcaOverlayDirectory)}
+        value={orcaOverlayDirectory.get()}
+        placeholder="Pick a directory/folder"
+        rightChild={
+          <div className="flex gap-2">
+            <FieldButton
+              text="Materialize"
+              disabled={orcaOverlayDirectory.get() === undefined}
+              onClick={materializeOverlay}
+            />
+            <FieldButton
+              text="Clear"
+              onClick={() => clearPath(orcaOverlayDirectory)}
+            />
+          </div>
+        }
+        allowEdit
+      />
     </>
   );
 }
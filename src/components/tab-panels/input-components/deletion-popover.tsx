@@ -0,0 +1,55 @@
+import { useHookstate } from "@hookstate/core";
+import { InputPopover } from "./input-popover";
+import { appState } from "../../../lib/state-store";
+import InputComponent from "../input-component";
+import { deletePresetWithStrategy } from "../../../lib/commons";
+import { DeleteStrategy } from "../../../lib/bindings/DeleteStrategy";
+
+export default function DeletionPopover() {
+  const { deletionPopover } = useHookstate(appState);
+  const { name, dependents } = deletionPopover.arguments.get();
+
+  return (
+    <InputPopover
+      popoverVisible={deletionPopover.visible.get()}
+      setPopOverVisible={deletionPopover.visible.set}
+      label={`'${name}' is used by other configs`}
+      description="Block keeps it, cascade also deletes every config depending on it, reparent lets them inherit its values and parent instead"
+      inputChildren={[
+        <div key={0} className="mt-4 text-text-secondary text-sm">
+          {dependents.map((dependent, idx) => (
+            <div key={idx}>
+              {dependent.name} · {dependent.keys.join(", ")}
+            </div>
+          ))}
+        </div>,
+        <InputComponent
+          key={1}
+          type="dropdown"
+          value={deletionPopover.strategy.get()}
+          enumValues={[
+            ["block", "block"],
+            ["cascade", "cascade"],
+            ["reparent", "reparent"],
+          ]}
+          className="mt-4 w-full rounded px-3 py-2"
+          onChange={(e) => deletionPopover.strategy.set(e as DeleteStrategy)}
+          allowEdit
+        />,
+      ]}
+      onSubmit={() => {
+        const { name, type, location, family, path } =
+          deletionPopover.arguments.get({ stealth: true });
+
+        deletePresetWithStrategy(
+          name,
+          type,
+          location,
+          path,
+          deletionPopover.strategy.get({ stealth: true }),
+          family
+        );
+      }}
+    />
+  );
+}
@@ -0,0 +1,43 @@
+import { useHookstate } from "@hookstate/core";
+import { InputPopover } from "./input-popover";
+import { appState, globalState } from "../../../lib/state-store";
+import InputComponent from "../input-component";
+import { copyConfigToVendor } from "../../../lib/commons";
+
+export default function VendorCopyPopover() {
+  const { vendorCopyPopover } = useHookstate(appState);
+  const { installedVendorConfigs } = useHookstate(globalState);
+
+  const family = vendorCopyPopover.arguments.family.get();
+  const otherFamilies = (installedVendorConfigs.keys as string[]).filter(
+    (vendor) => vendor !== family
+  );
+
+  return (
+    <InputPopover
+      popoverVisible={vendorCopyPopover.visible.get()}
+      setPopOverVisible={vendorCopyPopover.visible.set}
+      label="Please select the vendor to copy to"
+      description="The config is copied with the configs it inherits from that the vendor lacks"
+      inputChildren={[
+        <InputComponent
+          key={0}
+          type="dropdown"
+          value={vendorCopyPopover.newFamily.get()}
+          enumValues={otherFamilies.map((vendor) => [vendor, vendor])}
+          className="mt-4 w-full rounded px-3 py-2"
+          onChange={(e) => vendorCopyPopover.newFamily.set(e as string)}
+          allowEdit
+        />,
+      ]}
+      onSubmit={() => {
+        const { name, type, family } = vendorCopyPopover.arguments.get({
+          stealth: true,
+        });
+        const newFamily = vendorCopyPopover.newFamily.get({ stealth: true });
+
+        if (newFamily) copyConfigToVendor(name, type, family, newFamily);
+      }}
+    />
+  );
+}
@@ -1,11 +1,16 @@
 import { useHookstate } from "@hookstate/core";
 import { invoke } from "@tauri-apps/api/tauri";
 import { useState } from "react";
-import { HiOutlineDocumentDuplicate } from "react-icons/hi2";
+import {
+  HiOutlineArchiveBox,
+  HiOutlineDocumentDuplicate,
+  HiOutlinePencilSquare,
+} from "react-icons/hi2";
 import { toast } from "react-toastify";
 import { v4 as uuidv4 } from "uuid";
 import {
   flattenConfig,
+  formatVendorProblems,
   getFilamentLibraryFilaments,
   matchesQuery,
   newFile,
@@ -24,6 +29,7 @@ export default function VendorConfigTab() {
     installedPrinterConfigs,
     installedProcessConfigs,
     installedFilamentConfigs,
+    orcaDataDirectory,
   } = useHookstate(globalState);
 
   const { searchQuery } = useHookstate(appState);
@@ -33,6 +39,59 @@ export default function VendorConfigTab() {
   const [popoverVisible, setPopOverVisible] = useState(false);
   const [originalVendorFileName, setOriginalVendorFileName] = useState("");
   const [newVendorName, setNewVendorName] = useState("");
+  const [vendorAction, setVendorAction] = useState<"duplicate" | "rename">(
+    "duplicate"
+  );
+
+  // renaming also removes the stale system copy and updates the user presets
+  const submitVendorAction = () => {
+    const args = {
+      path: originalVendorFileName,
+      newDirName: newVendorName,
+      orcaFilamentLibraryFilaments: getFilamentLibraryFilaments(),
+    };
+    const request =
+      vendorAction === "rename"
+        ? invoke("rename_vendor", {
+            ...args,
+            dataDirectory: orcaDataDirectory.get() ?? null,
+            updateUserPresets: true,
+          })
+        : invoke("duplicate_vendor", args);
+
+    return request
+      .then(() => {
+        toast(
+          vendorAction === "rename"
+            ? "Vendor successfully renamed"
+            : "Vendor successfully copied",
+          { type: "success" }
+        );
+        refreshConfigs("vendor", "installed");
+      })
+      .catch((err: any) => {
+        toast(formatVendorProblems(err), { type: "error" });
+      });
+  };
+
+  // the bundle keeps the on-disk layout, with the assets of the printer models
+  const bundleExportFunction = async (
+    vendorFileName: string,
+    vendorName: string
+  ) => {
+    try {
+      const folderName = await invoke<string>("pick_folder", {});
+
+      await invoke("export_vendor_bundle", {
+        path: vendorFileName,
+        zipPath: `${folderName}/${vendorName}.zip`,
+      });
+
+      toast("Vendor bundle successfully exported", { type: "success" });
+    } catch (err: any) {
+      toast(formatVendorProblems(err), { type: "error" });
+    }
+  };
 
   const flatExportFunction = async (vendorName: string) => {
     try {
@@ -139,20 +198,7 @@ export default function VendorConfigTab() {
             onChange={(e) => setNewVendorName(e.target.value)}
           />,
         ]}
-        onSubmit={() =>
-          invoke("duplicate_vendor", {
-            path: originalVendorFileName,
-            newDirName: newVendorName,
-            orcaFilamentLibraryFilaments: getFilamentLibraryFilaments(),
-          })
-            .then(() => {
-              toast("Vendor successfully copied", { type: "success" });
-              refreshConfigs("vendor", "installed");
-            })
-            .catch((err: any) => {
-              toast(err.toString(), { type: "error" });
-            })
-        }
+        onSubmit={submitVendorAction}
       />
 
       <TopButton
@@ -179,6 +225,13 @@ export default function VendorConfigTab() {
 
         const onClickDuplicationItem = async () => {
           setOriginalVendorFileName(config.fileName);
+          setVendorAction("duplicate");
+          setPopOverVisible(true);
+        };
+
+        const onClickRenameItem = async () => {
+          setOriginalVendorFileName(config.fileName);
+          setVendorAction("rename");
           setPopOverVisible(true);
         };
 
@@ -188,6 +241,18 @@ export default function VendorConfigTab() {
           text: "Duplicate vendor config",
         };
 
+        const renameMenuItem = {
+          icon: HiOutlinePencilSquare,
+          onClick: onClickRenameItem,
+          text: "Rename vendor",
+        };
+
+        const bundleExportMenuItem = {
+          icon: HiOutlineArchiveBox,
+          onClick: () => bundleExportFunction(config.fileName, key),
+          text: "Export vendor bundle",
+        };
+
         if (!matchesQuery(searchQuery.get(), [config.name]))
           return <div key={config.name}></div>;
 
@@ -207,7 +272,11 @@ export default function VendorConfigTab() {
             configLocation="installed"
             allowEdit
             flatExportFunction={flatExportFunction}
-            extraOptionsMenuItems={[duplicationMenuItem]}
+            extraOptionsMenuItems={[
+              duplicationMenuItem,
+              renameMenuItem,
+              bundleExportMenuItem,
+            ]}
             onClick={() =>
               invoke("analyse_vendor_config", {
                 path: config.fileName,
@@ -138,7 +138,8 @@ export default function TabbedWindow() {
                     parsedEl.type,
                     parsedEl.location,
                     parsedEl.family,
-                    true
+                    true,
+                    false
                   );
                 }
                 refreshConfigs(
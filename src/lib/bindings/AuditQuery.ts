@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Filters for the audit log, records have to match all that are given.
+ * `path` matches the record when it is one of the changed files or a
+ * directory above one.
+ */
+export type AuditQuery = { path: string | null, since: number | null, until: number | null, command: string | null, limit: number | null, };
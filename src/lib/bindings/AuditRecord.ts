@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileChange } from "./FileChange";
+
+/**
+ * One mutating command as it was run, appended to the audit log of the
+ * workspace it ran in. `changes` hold the content hashes before and after.
+ */
+export type AuditRecord = { timestamp: number, command: string, description: string, workspace: Array<string>, changes: Array<FileChange>, created_directories: Array<string>, error: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A previous version of a file, kept by the `FileStore`.
+ */
+export type BackupEntry = { id: string, path: string, backup_path: string, created_at: number, size: number, };
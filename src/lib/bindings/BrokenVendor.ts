@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonDiagnostic } from "./JsonDiagnostic";
+
+/**
+ * A vendor file that could not be loaded, with the problems found in it.
+ */
+export type BrokenVendor = { path: string, error: string, diagnostics: Array<JsonDiagnostic>, };
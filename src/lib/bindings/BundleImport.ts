@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VendorPlan } from "./VendorPlan";
+import type { VendorProblem } from "./VendorProblem";
+
+/**
+ * What importing a vendor bundle installed, with the findings of the
+ * analysis that did not stop it.
+ */
+export type BundleImport = { vendor: string, plan: VendorPlan, warnings: Array<VendorProblem>, };
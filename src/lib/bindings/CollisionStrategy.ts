@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What happens to a config of the merged vendor when the target vendor
+ * already has a config of the same type and name.
+ */
+export type CollisionStrategy = "prefix" | "skip" | "overwrite";
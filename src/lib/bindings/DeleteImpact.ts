@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PresetDependent } from "./PresetDependent";
+
+/**
+ * Everything that depends on a preset, and on the presets a cascading
+ * delete would remove with it.
+ */
+export type DeleteImpact = { name: string, config_type: string, 
+/**
+ * The files of the preset in every copy of its vendor.
+ */
+paths: Array<string>, dependents: Array<PresetDependent>, };
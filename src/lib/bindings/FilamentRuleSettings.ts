@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Tunable limits of the built-in filament rules. Anything left out by the caller
+ * falls back to the defaults.
+ */
+export type FilamentRuleSettings = { allowed_filament_diameters: Array<number>, max_bed_temperature: number, };
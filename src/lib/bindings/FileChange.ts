@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One file touched by an operation. `before` and `after` are content hashes of
+ * the stored versions, `None` when the file did not exist.
+ */
+export type FileChange = { path: string, before: string | null, after: string | null, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileChange } from "./FileChange";
+
+/**
+ * A mutating command as a whole, undone and redone in one step.
+ */
+export type JournalEntry = { id: number, command: string, description: string, created_at: number, changes: Array<FileChange>, created_directories: Array<string>, 
+/**
+ * Why the operation, or the undo or redo returning the entry, is missing
+ * from the audit log.
+ */
+warning: string | null, };
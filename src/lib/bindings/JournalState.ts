@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JournalEntry } from "./JournalEntry";
+
+/**
+ * The recorded operations. Entries before `position` can be undone, the
+ * ones after it redone.
+ */
+export type JournalState = { entries: Array<JournalEntry>, position: number, };
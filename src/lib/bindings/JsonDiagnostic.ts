@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrType } from "./ErrType";
+
+/**
+ * A problem found in a JSON file, with a 1-based line/column span.
+ */
+export type JsonDiagnostic = { message: string, line: number, column: number, end_line: number, end_column: number, type: ErrType, 
+/**
+ * Whether the problem is removed when the file is repaired.
+ */
+fixable: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A read-only installation directory and the writable directory that takes
+ * the changes made to it.
+ */
+export type OverlaySettings = { base_directory: string, overlay_directory: string, };
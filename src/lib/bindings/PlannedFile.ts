@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PlannedAction } from "./PlannedAction";
+
+/**
+ * One file written or removed by a plan. Copied and moved files name their
+ * `source`, rewritten files carry their new `content`.
+ */
+export type PlannedFile = { action: PlannedAction, path: string, source: string | null, 
+/**
+ * Hash of `source`, or of `path` for modified and deleted files, when the
+ * plan was made. The plan is refused if it no longer matches.
+ */
+expected_hash: string | null, content: string | null, 
+/**
+ * The config type the written file is validated as, if it is a config.
+ */
+config_type: string | null, };
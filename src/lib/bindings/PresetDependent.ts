@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A vendor list entry or preset naming a preset that is deleted.
+ */
+export type PresetDependent = { path: string, name: string, 
+/**
+ * The config type of the dependent, `vendor` for a vendor list entry.
+ */
+config_type: string, 
+/**
+ * The vendor directory listing the dependent, `None` for user presets.
+ */
+vendor: string | null, 
+/**
+ * The keys naming the preset, or the vendor list holding its entry.
+ */
+keys: Array<string>, 
+/**
+ * The preset named: the deleted one, or a preset inheriting from it.
+ */
+references: string, };
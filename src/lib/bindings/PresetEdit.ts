@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The edited content a preset is saved with when it is renamed, with the
+ * token of the file it was loaded from.
+ */
+export type PresetEdit = { content: string, expected_token: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A preset name referenced from another config that is rewritten.
+ */
+export type ReferenceChange = { path: string, key: string, old_value: string, new_value: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VendorPlan } from "./VendorPlan";
+
+/**
+ * A renamed preset: where it is now and everything the rename changed.
+ */
+export type RenamedPreset = { path: string, plan: VendorPlan, };
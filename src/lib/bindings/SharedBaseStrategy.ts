@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What happens to a base preset that the split out printers and the printers
+ * staying behind both inherit from.
+ */
+export type SharedBaseStrategy = "duplicate" | "keep";
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BrokenVendor } from "./BrokenVendor";
+import type { VendorJsonSchema } from "./VendorJsonSchema";
+
+/**
+ * The vendors of a profiles directory by file name, and the vendor files that
+ * could not be loaded.
+ */
+export type SystemVendorProfiles = { vendors: { [key in string]?: VendorJsonSchema }, broken: Array<BrokenVendor>, };
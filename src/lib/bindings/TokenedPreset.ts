@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GenericJsonSchema } from "./GenericJsonSchema";
+
+/**
+ * A config together with the version token of the file it was read from.
+ */
+export type TokenedPreset = { config: GenericJsonSchema, token: string, };
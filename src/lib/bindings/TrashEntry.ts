@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VendorListEntry } from "./VendorListEntry";
+
+/**
+ * A deleted file kept in the trash of the app.
+ */
+export type TrashEntry = { id: string, original_path: string, name: string | null, deleted_at: number, operation: string, vendor_entries: Array<VendorListEntry>, size: number, };
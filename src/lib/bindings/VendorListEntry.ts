@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A vendor list entry that pointed at a deleted file, with its position so
+ * that a restore puts it back where it was.
+ */
+export type VendorListEntry = { vendor_path: string, list_key: string, index: number, name: string, sub_path: string, };
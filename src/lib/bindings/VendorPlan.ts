@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PlannedFile } from "./PlannedFile";
+import type { PresetRename } from "./PresetRename";
+import type { ReferenceChange } from "./ReferenceChange";
+
+/**
+ * Everything a bulk operation will do, computed without touching the disk.
+ * Executing the plan applies exactly these changes.
+ */
+export type VendorPlan = { command: string, description: string, 
+/**
+ * The directory the plan works in, paths in the diff are relative to it.
+ */
+root: string, files: Array<PlannedFile>, renames: Array<PresetRename>, references: Array<ReferenceChange>, diff: string, };
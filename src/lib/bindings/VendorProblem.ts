@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A problem that stopped a vendor operation, with the file it was found in.
+ */
+export type VendorProblem = { path: string, message: string, };
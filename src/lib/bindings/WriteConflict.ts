@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A write refused because the file changed after it was read, with what is on
+ * disk now so that the changes can be merged.
+ */
+export type WriteConflict = { path: string, message: string, token: string | null, current_content: string | null, };
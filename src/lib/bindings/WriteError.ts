@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WriteConflict } from "./WriteConflict";
+
+/**
+ * The error of a command that checks version tokens. Plain errors stay strings.
+ */
+export type WriteError = string | WriteConflict;
@@ -10,7 +10,10 @@ import {
   process_properties_map,
 } from "./all-configuration-options";
 import { AnalysisMessageDetails } from "./bindings/AnalysisMessageDetails";
+import { BrokenVendor } from "./bindings/BrokenVendor";
 import { ConfigNameAndPath } from "./bindings/ConfigNameAndPath";
+import { DeleteImpact } from "./bindings/DeleteImpact";
+import { DeleteStrategy } from "./bindings/DeleteStrategy";
 import { FilamentJsonSchema } from "./bindings/FilamentJsonSchema";
 import { GenericJsonSchema } from "./bindings/GenericJsonSchema";
 import { MinFilamentJsonSchema } from "./bindings/MinFilamentJsonSchema";
@@ -19,8 +22,15 @@ import { MinPrinterVariantJsonSchema } from "./bindings/MinPrinterVariantJsonSch
 import { MinProcessJsonSchema } from "./bindings/MinProcessJsonSchema";
 import { PrinterModelJsonSchema } from "./bindings/PrinterModelJsonSchema";
 import { PrinterVariantJsonSchema } from "./bindings/PrinterVariantJsonSchema";
+import { PresetDependent } from "./bindings/PresetDependent";
+import { PresetEdit } from "./bindings/PresetEdit";
 import { ProcessJsonSchema } from "./bindings/ProcessJsonSchema";
+import { RenamedPreset } from "./bindings/RenamedPreset";
+import { SystemVendorProfiles } from "./bindings/SystemVendorProfiles";
 import { VendorJsonSchema } from "./bindings/VendorJsonSchema";
+import { TokenedPreset } from "./bindings/TokenedPreset";
+import { VendorProblem } from "./bindings/VendorProblem";
+import { WriteConflict } from "./bindings/WriteConflict";
 import {
   directoryDefaults,
   FILAMENT_SUBDIRECTORY,
@@ -40,6 +50,7 @@ import {
   printer_properties_map,
 } from "./printer-configuration-options";
 import {
+  appState,
   ErrWan,
   familyProperty,
   fileProperty,
@@ -214,10 +225,17 @@ export const installedVendorConfigLoader = async () => {
         orcaInstallationPath.get({ stealth: true }) +
         get_installed_system_profiles_subdirectory_directory(os.get());
 
-      const vendorConfigsRead: Record<string, VendorJsonSchema & fileProperty> =
-        await invoke("load_all_system_vendor_profiles", {
+      const profiles: SystemVendorProfiles = await invoke(
+        "load_all_system_vendor_profiles",
+        {
           path: vendorConfigsPath,
-        });
+        }
+      );
+      const vendorConfigsRead = profiles.vendors as Record<
+        string,
+        VendorJsonSchema & fileProperty
+      >;
+      reportBrokenVendors(profiles.broken);
 
       Object.entries(vendorConfigsRead).forEach(([key, value]) => {
         value.fileName = vendorConfigsPath + "/" + key + ".json";
@@ -237,6 +255,13 @@ export const installedVendorConfigLoader = async () => {
   }
 };
 
+// broken vendor files are left out of the lists, the user is told which
+const reportBrokenVendors = (broken: BrokenVendor[]) => {
+  broken.forEach((vendor) => {
+    toast(vendor.error, { type: "warning" });
+  });
+};
+
 export const loadedSystemVendorConfigLoader = async () => {
   const orcaDataDirectory = globalState.orcaDataDirectory;
   const relevantConfigs = getRelevantConfigsFromTypeVM("vendor");
@@ -252,12 +277,17 @@ export const loadedSystemVendorConfigLoader = async () => {
       orcaDataDirectory.get({ stealth: true }) &&
       !errLoadingDataPath.get({ stealth: true })
     ) {
-      const vendorConfigsRead: NamedConfigStateType<VendorJsonSchema> =
-        await invoke("load_all_system_vendor_profiles", {
+      const profiles: SystemVendorProfiles = await invoke(
+        "load_all_system_vendor_profiles",
+        {
           path:
             orcaDataDirectory.get({ stealth: true }) +
             LOADED_SYSTEM_PROFILES_SUBDIRECTORY,
-        });
+        }
+      );
+      const vendorConfigsRead =
+        profiles.vendors as NamedConfigStateType<VendorJsonSchema>;
+      reportBrokenVendors(profiles.broken);
 
       Object.entries(vendorConfigsRead).forEach(([key, value]) => {
         value.fileName =
@@ -297,7 +327,7 @@ export const loadedSystemModelConfigLoader = async () => {
       orcaDataDirectory.get({ stealth: true }) &&
       !errLoadingDataPath.get({ stealth: true })
     ) {
-      const vendorConfigsRead: Record<string, VendorJsonSchema> = await invoke(
+      const profiles: SystemVendorProfiles = await invoke(
         "load_all_system_vendor_profiles",
         {
           path:
@@ -305,6 +335,10 @@ export const loadedSystemModelConfigLoader = async () => {
             LOADED_SYSTEM_PROFILES_SUBDIRECTORY,
         }
       );
+      const vendorConfigsRead = profiles.vendors as Record<
+        string,
+        VendorJsonSchema
+      >;
 
       Object.keys(vendorConfigsRead).map(async (key) => {
         const vendorConfig = vendorConfigsRead[key];
@@ -434,7 +468,7 @@ export async function dataConfigLoader<
       orcaDataDirectory.get({ stealth: true }) &&
       !errLoadingDataPath.get({ stealth: true })
     ) {
-      const vendorConfigsRead: Record<string, VendorJsonSchema> = await invoke(
+      const profiles: SystemVendorProfiles = await invoke(
         "load_all_system_vendor_profiles",
         {
           path:
@@ -442,6 +476,10 @@ export async function dataConfigLoader<
             LOADED_SYSTEM_PROFILES_SUBDIRECTORY,
         }
       );
+      const vendorConfigsRead = profiles.vendors as Record<
+        string,
+        VendorJsonSchema
+      >;
 
       Object.keys(vendorConfigsRead).map(async (key) => {
         const vendorConfig = vendorConfigsRead[key];
@@ -1257,6 +1295,43 @@ export function newFile(
   navigate(url, { replace: replace });
 }
 
+// the backend copies the config with the ancestors the other vendor lacks,
+// named and listed the way that vendor expects
+export async function copyConfigToVendor(
+  name: string,
+  type: ConfigType,
+  family: string,
+  newFamily: string
+) {
+  const { installedVendorConfigs } = globalState;
+  const vendor = installedVendorConfigs[family].get({ stealth: true });
+  const newVendor = installedVendorConfigs[newFamily].get({ stealth: true });
+  const config = findConfig(name, type, "installed", family);
+
+  if (!newVendor) {
+    toast(`Vendor: ${newFamily} is not installed`, { type: "error" });
+    return;
+  }
+  if (!vendor || !config) {
+    toast(`Could not find config to copy: ${name}`, { type: "error" });
+    return;
+  }
+
+  await invoke("copy_configs_to_vendor", {
+    sourcePath: vendor.fileName,
+    configPaths: [config.fileName],
+    targetPath: newVendor.fileName,
+    orcaFilamentLibraryFilaments: getFilamentLibraryFilaments(),
+  })
+    .then(() => {
+      toast(`Config: ${name} copied to ${newFamily}`, { type: "success" });
+      refreshConfigs("vendor", "installed");
+    })
+    .catch((err: any) => {
+      toast(formatVendorProblems(err), { type: "error" });
+    });
+}
+
 export function duplicateFile(
   type: ConfigType,
   location: ConfigLocationType,
@@ -1437,46 +1512,6 @@ const getVendorConfigSubDirName = (type: ConfigType) => {
   }
 };
 
-export async function renameVendorConfigEntry(
-  family: string,
-  type: ConfigType,
-  oldName: string,
-  newName: string
-) {
-  const vendorConfig = globalState.installedVendorConfigs[family].get({
-    stealth: true,
-  });
-
-  const vendorConfigKeyName = getVendorConfigKeyName(type);
-
-  const relevantConfigList = vendorConfig[vendorConfigKeyName];
-
-  const updatedConfigs = relevantConfigList!.map((el) => {
-    if (el.name !== oldName) return el;
-    else {
-      const subDirectory = el.sub_path.split("/")[0];
-      return {
-        name: newName,
-        sub_path: subDirectory + "/" + newName + ".json",
-      };
-    }
-  });
-
-  const path = vendorConfig.fileName;
-
-  const readVendorConfig = await invoke<GenericJsonSchema>(
-    "load_generic_preset",
-    { path }
-  );
-
-  readVendorConfig[vendorConfigKeyName] = updatedConfigs;
-
-  await invoke("write_to_file", {
-    path,
-    content: JSON.stringify(readVendorConfig, null, 2),
-  });
-}
-
 export async function createVendorConfigEntry(
   family: string,
   type: ConfigType,
@@ -1497,37 +1532,8 @@ export async function createVendorConfigEntry(
 
   const path = vendorConfig.fileName;
 
-  const readVendorConfig = await invoke<GenericJsonSchema>(
-    "load_generic_preset",
-    { path }
-  );
-
-  readVendorConfig[vendorConfigKeyName] = newConfigList;
-
-  await invoke("write_to_file", {
-    path,
-    content: JSON.stringify(readVendorConfig, null, 2),
-  });
-}
-
-export async function deleteVendorConfigEntry(
-  family: string,
-  type: ConfigType,
-  name: string
-) {
-  const vendorConfig = globalState.installedVendorConfigs[family].get({
-    stealth: true,
-  });
-
-  const vendorConfigKeyName = getVendorConfigKeyName(type);
-
-  const relevantConfigList = vendorConfig[vendorConfigKeyName];
-  const newConfigList = relevantConfigList!.filter((el) => el.name !== name);
-
-  const path = vendorConfig.fileName;
-
-  const readVendorConfig = await invoke<GenericJsonSchema>(
-    "load_generic_preset",
+  const { config: readVendorConfig, token } = await invoke<TokenedPreset>(
+    "load_generic_preset_with_token",
     { path }
   );
 
@@ -1536,38 +1542,34 @@ export async function deleteVendorConfigEntry(
   await invoke("write_to_file", {
     path,
     content: JSON.stringify(readVendorConfig, null, 2),
+    expectedToken: token,
   });
 }
 
+// the backend renames the preset together with its vendor list entry and
+// every preset naming it, in the installed, loaded system and user folders
 export async function renameConfig(
   oldName: string,
   newName: string,
   type: ConfigType,
   location: ConfigLocationType,
-  family?: string
+  family?: string,
+  edit?: PresetEdit
 ) {
   const path = findConfig(oldName, type, location, family)!.fileName;
 
-  try {
-    const props = await invoke<{ name: string } & fileProperty>(
-      "load_generic_preset",
-      {
-        path,
-      }
-    );
-
-    props.name = newName;
-
-    await invoke("write_to_file", {
-      path,
-      content: JSON.stringify(props, null, 2),
-    });
-    const newPath = await invoke<string>("rename_config", { path, newName });
+  const renamed = await invoke<RenamedPreset>("rename_preset", {
+    path,
+    configType: type,
+    newName,
+    edit: edit ?? null,
+    profilesDirectory: getDirectoryFromTypeAndLocation("vendor", "installed"),
+    dataDirectory: globalState.orcaDataDirectory.get({ stealth: true }) ?? null,
+  }).catch((err: unknown) => {
+    throw formatVendorProblems(err);
+  });
 
-    return newPath;
-  } catch (error: any) {
-    throw error;
-  }
+  return renamed.path;
 }
 
 export function getPropMapFromType(type: ConfigType) {
@@ -1679,13 +1681,88 @@ export function getDirectoryFromTypeAndLocation(
   return directoryPathList.join("") + "/";
 }
 
+// the presets inheriting from or naming a preset; its own vendor list entries
+// go with it. A failed check is thrown, so nothing is deleted unchecked
+async function findPresetDependents(path: string, type: ConfigType) {
+  const impact = await invoke<DeleteImpact>("preset_delete_impact", {
+    path,
+    configType: type,
+    profilesDirectory: getDirectoryFromTypeAndLocation("vendor", "installed"),
+    dataDirectory: globalState.orcaDataDirectory.get({ stealth: true }) ?? null,
+  });
+
+  return impact.dependents.filter(
+    (dependent) => dependent.config_type !== "vendor"
+  );
+}
+
+function describeDependents(name: string, dependents: PresetDependent[]) {
+  return (
+    `Config: ${name} is used by ` +
+    dependents
+      .map((dependent) => `${dependent.name} (${dependent.keys.join(", ")})`)
+      .join(", ")
+  );
+}
+
+// the printer model a deleted printer is listed by stops offering its variant
+async function removeInstalledPrinterVariant(
+  name: string,
+  family: string,
+  printer: any
+) {
+  await removeVariantFromModel(
+    family,
+    printer["printer_model"] as string,
+    printer["printer_variant"] as string
+  );
+  toast(`Config: ${name} successfully removed from printer model`, {
+    type: "success",
+  });
+}
+
+// deletes a preset other presets depend on the way `strategy` says, in every
+// copy of its vendor and together with the user presets naming it
+export async function deletePresetWithStrategy(
+  name: string,
+  type: ConfigType,
+  location: ConfigLocationType,
+  path: string,
+  strategy: DeleteStrategy,
+  family?: string
+) {
+  try {
+    const printer: any =
+      location === "installed" && type === "printer"
+        ? await invoke("load_generic_preset", { path })
+        : undefined;
+
+    await invoke("delete_preset", {
+      path,
+      configType: type,
+      strategy,
+      profilesDirectory: getDirectoryFromTypeAndLocation("vendor", "installed"),
+      dataDirectory:
+        globalState.orcaDataDirectory.get({ stealth: true }) ?? null,
+    });
+    toast(`Config: ${name} deleted`, { type: "success" });
+
+    if (printer) await removeInstalledPrinterVariant(name, family!, printer);
+
+    await refreshConfigs(type, location);
+    if (location === "installed") await refreshConfigs("vendor", "installed");
+  } catch (error: any) {
+    toast(formatVendorProblems(error), { type: "error" });
+  }
+}
+
 export async function deleteUserConfig(name: string, type: ConfigType) {
   const config = findConfig(name, type, "user");
 
   if (config) {
     invoke("delete_file", { path: config.fileName })
       .then(() => {
-        toast(`Config: ${name} successfully deleted`, { type: "success" });
+        toast(`Config: ${name} moved to the trash`, { type: "success" });
         refreshConfigs(type, "user");
       })
       .catch((error: any) => toast(error.toString(), { type: "error" }));
@@ -1699,11 +1776,10 @@ export async function deleteInstalledConfig(
 ) {
   const config = findConfig(name, type, "installed", family);
   if (config) {
-    invoke("delete_file", { path: config.fileName })
+    // the backend removes the vendor list entry together with the file
+    await invoke("delete_file", { path: config.fileName })
       .then(() => {
-        deleteVendorConfigEntry(family, type, name).then(() => {
-          toast(`Config: ${name} successfully deleted`, { type: "success" });
-        });
+        toast(`Config: ${name} moved to the trash`, { type: "success" });
       })
       .catch((error: any) => toast(error.toString(), { type: "error" }));
   }
@@ -1714,38 +1790,52 @@ export async function deleteConfig(
   type: ConfigType,
   location: ConfigLocationType,
   family?: string,
-  doRefresh: boolean = true
+  doRefresh: boolean = true,
+  chooseStrategy: boolean = true
 ) {
   try {
+    const config = findConfig(name, type, location, family);
+    if (!config) {
+      toast(`Could not find config to delete: ${name}`, { type: "error" });
+      return;
+    }
+
+    const dependents = await findPresetDependents(config.fileName, type);
+    if (dependents.length > 0) {
+      if (!chooseStrategy) {
+        toast(describeDependents(name, dependents), { type: "error" });
+        return;
+      }
+
+      const { deletionPopover } = appState;
+      deletionPopover.arguments.set({
+        name,
+        type,
+        location,
+        family,
+        path: config.fileName,
+        dependents,
+      });
+      deletionPopover.strategy.set("block");
+      deletionPopover.visible.set(true);
+      return;
+    }
+
     if (location === "user") await deleteUserConfig(name, type);
     else if (location === "installed") {
       if (type === "printer") {
-        const printerConfig = findConfigPFP(name, type, "installed", family);
-        if (printerConfig) {
-          const res: any = await invoke("load_generic_preset", {
-            path: printerConfig.fileName,
-          });
-
-          await removeVariantFromModel(
-            family!,
-            res["printer_model"] as string,
-            res["printer_variant"] as string
-          );
-          toast(`Config: ${name} successfully removed from printer model`, {
-            type: "success",
-          });
-        } else {
-          toast(`Could not find config to delete: ${name}`, { type: "error" });
-        }
+        const printer: any = await invoke("load_generic_preset", {
+          path: config.fileName,
+        });
+        await removeInstalledPrinterVariant(name, family!, printer);
       }
 
-      await deleteVendorConfigEntry(family!, type, name);
       await deleteInstalledConfig(name, type, family!);
 
       if (doRefresh) await refreshConfigs(type, "installed");
     }
   } catch (error: any) {
-    toast(error.toString(), { type: "error" });
+    toast(formatVendorProblems(error), { type: "error" });
   }
 }
 
@@ -1880,18 +1970,33 @@ export async function analyseVendorConfigs() {
     analysisWarnings,
   } = globalState;
 
-  return Promise.all(
-    vendorConfigs.keys.map(async (vendorName) => {
-      const vendorConfig = vendorConfigs[vendorName].get();
-      return invoke("analyse_vendor_config", {
-        path: vendorConfig.fileName,
-        configLocation: "installed",
-        name: vendorName,
-      }).then((analysisMessages) => {
-        return [vendorConfig.fileName, analysisMessages];
-      });
-    })
-  )
+  const vendorConfigsPath =
+    globalState.orcaInstallationPath.get({ stealth: true }) +
+    get_installed_system_profiles_subdirectory_directory(globalState.os.get());
+
+  return Promise.all([
+    Promise.all(
+      vendorConfigs.keys.map(async (vendorName) => {
+        const vendorConfig = vendorConfigs[vendorName].get();
+        return invoke("analyse_vendor_config", {
+          path: vendorConfig.fileName,
+          configLocation: "installed",
+          name: vendorName,
+        }).then((analysisMessages) => {
+          return [vendorConfig.fileName, analysisMessages];
+        });
+      })
+    ),
+    // vendor files that failed to load are missing from vendorConfigs
+    invoke<any[]>("analyse_vendor_directory", {
+      path: vendorConfigsPath,
+      configLocation: "installed",
+    }),
+  ])
+    .then(([vendorMessages, brokenVendorMessages]) => [
+      ...vendorMessages,
+      ...brokenVendorMessages,
+    ])
     .then((analysisMessages) => {
       analysisMessages.forEach((analysisMessage) => {
         const analysisMessageCasted = analysisMessage as [
@@ -2246,3 +2351,25 @@ export async function analyseConfigs() {
     analyseProcessConfigs(),
   ]);
 }
+
+// Vendor operations reject with the list of every problem they found
+/**
+ * Error text of a command. Commands that check version tokens fail with a
+ * `WriteConflict` object instead of a string when the file changed on disk.
+ */
+export function formatCommandError(err: unknown): string {
+  if (err && typeof err === "object" && "current_content" in err) {
+    const conflict = err as WriteConflict;
+    return `${conflict.message}. Reload it to see the changes before saving again.`;
+  }
+
+  return String(err);
+}
+
+export function formatVendorProblems(err: unknown): string {
+  if (!Array.isArray(err)) return String(err);
+
+  return (err as VendorProblem[])
+    .map((problem) => `${problem.path}: ${problem.message}`)
+    .join("\n");
+}
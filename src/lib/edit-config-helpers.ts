@@ -11,6 +11,7 @@ import {
   deinherit_config_by_type,
   editConfigFile,
   findConfig,
+  formatCommandError,
   getArrayFromDelimitedString,
   getDelimitedStringFromArray,
   getDirectoryFromTypeAndLocation,
@@ -18,7 +19,6 @@ import {
   getRelevantConfigsFromTypePFP,
   refreshConfigs,
   renameConfig,
-  renameVendorConfigEntry,
 } from "./commons";
 import {
   appState,
@@ -28,6 +28,7 @@ import {
 } from "./state-store";
 import { ConfigNameAndPath } from "./bindings/ConfigNameAndPath";
 import { MinPrinterModelJsonSchema } from "./bindings/MinPrinterModelJsonSchema";
+import { TokenedPreset } from "./bindings/TokenedPreset";
 
 function refreshAndReload(
   name: string,
@@ -49,18 +50,27 @@ async function writeToFile(
 ) {
   const changedProps = editWindowState[editWindowStateKey].changedProps;
 
-  await invoke("write_to_file", {
+  // only the file that was loaded into the window has a token to check
+  const expectedToken =
+    path === editWindowStateKey
+      ? editWindowState[editWindowStateKey].fileToken.get({ stealth: true })
+      : undefined;
+
+  await invoke<string>("write_to_file", {
     path,
     content: JSON.stringify(props, null, 2),
+    expectedToken,
   })
-    .then(() => {
+    .then((token) => {
       toast("Wrote new configuration to file", { type: "success" });
       changedProps.set({});
+      if (path === editWindowStateKey)
+        editWindowState[editWindowStateKey].fileToken.set(token);
       editWindowState[editWindowStateKey].initialLoadCompleted.set(false);
       editWindowState[editWindowStateKey].deleteKeys.set([]);
     })
     .catch((error: any) => {
-      toast(error.toString(), { type: "error" });
+      toast(formatCommandError(error), { type: "error" });
     });
 }
 
@@ -144,7 +154,7 @@ export async function saveFile(
       refreshAndReload(newName, newFileName, type, location, navigate, family);
     }
   } catch (error: any) {
-    toast(error.toString(), { type: "error" });
+    toast(formatCommandError(error), { type: "error" });
   }
 }
 
@@ -195,6 +205,8 @@ async function saveNewFile(
   }
 }
 
+// the edited properties are saved by the rename itself, so that both are one
+// change to undo and the preset is still found under its old name
 async function saveAndRenameFile(
   name: string,
   newName: string,
@@ -204,38 +216,36 @@ async function saveAndRenameFile(
   editWindowState: State<typeof globalStateObject.editWindowState, {}>,
   family?: string
 ) {
-  if (location === "user") {
-    const newProps = getKeysValuesToSave(fileName, editWindowState);
-    await writeToFile(fileName, newProps, fileName, editWindowState);
-    const newFileName = await renameConfig(
-      name,
-      newName,
-      type,
-      location,
-      family
-    );
+  if (location !== "user" && location !== "installed") {
+    toast("Could not save file", { type: "error" });
+    throw "Could not save file";
+  }
 
-    return newFileName;
-  } else if (location === "installed") {
-    const newProps = getKeysValuesToSave(fileName, editWindowState);
-    await writeToFile(fileName, newProps, fileName, editWindowState);
-    const newFileName = await renameConfig(
-      name,
-      newName,
-      type,
-      location,
-      family
-    );
+  const newProps = getKeysValuesToSave(fileName, editWindowState);
+  const newFileName = await renameConfig(
+    name,
+    newName,
+    type,
+    location,
+    family,
+    {
+      content: JSON.stringify(newProps, null, 2),
+      expected_token:
+        editWindowState[fileName].fileToken.get({ stealth: true }) ?? null,
+    }
+  );
 
-    await renameVendorConfigEntry(family!, type, name, newName);
-    await refreshConfigs("vendor", "installed");
+  toast("Wrote new configuration to file", { type: "success" });
+  editWindowState[fileName].changedProps.set({});
+  editWindowState[fileName].initialLoadCompleted.set(false);
+  editWindowState[fileName].deleteKeys.set([]);
 
+  if (location === "installed") {
+    await refreshConfigs("vendor", "installed");
     toast("reloaded vendor configs", { type: "success" });
-    return newFileName;
-  } else {
-    toast("Could not save file", { type: "error" });
-    throw "Could not save file";
   }
+
+  return newFileName;
 }
 
 export function getFilesToSearch(type: ConfigType) {
@@ -399,9 +409,13 @@ export async function addNewVariantToModel(
   );
 
   if (modelConfig) {
-    const res: MinPrinterModelJsonSchema = await invoke("load_generic_preset", {
-      path: modelConfig.fileName,
-    });
+    const { config, token } = await invoke<TokenedPreset>(
+      "load_generic_preset_with_token",
+      {
+        path: modelConfig.fileName,
+      }
+    );
+    const res = config as unknown as MinPrinterModelJsonSchema;
 
     const variantArray = res.nozzle_diameter
       .split(";")
@@ -415,6 +429,7 @@ export async function addNewVariantToModel(
     await invoke("write_to_file", {
       path: modelConfig.fileName,
       content: JSON.stringify(res, null, 2),
+      expectedToken: token,
     });
   }
 }
@@ -432,9 +447,13 @@ export async function removeVariantFromModel(
   );
 
   if (modelConfig) {
-    const res: MinPrinterModelJsonSchema = await invoke("load_generic_preset", {
-      path: modelConfig.fileName,
-    });
+    const { config, token } = await invoke<TokenedPreset>(
+      "load_generic_preset_with_token",
+      {
+        path: modelConfig.fileName,
+      }
+    );
+    const res = config as unknown as MinPrinterModelJsonSchema;
 
     const variantArray = res.nozzle_diameter
       .split(";")
@@ -447,6 +466,7 @@ export async function removeVariantFromModel(
     await invoke("write_to_file", {
       path: modelConfig.fileName,
       content: JSON.stringify(res, null, 2),
+      expectedToken: token,
     });
 
     await refreshConfigs("printer-model", "installed");
@@ -491,7 +511,12 @@ export function loadExistingConfigProps(
   const propMap = getPropMapFromType(type);
 
   const { editWindowState } = globalState;
-  deinherit_and_load_all_props(name, type, location, family)
+  // the token is taken first, so that changes made while loading are caught
+  invoke<string | null>("get_file_token", { path: fileName })
+    .then((token) => {
+      editWindowState[fileName].fileToken.set(token);
+      return deinherit_and_load_all_props(name, type, location, family);
+    })
     .then((res: any) => {
       const allKeysInRes = Object.keys(res.res);
 
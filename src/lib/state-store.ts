@@ -1,9 +1,11 @@
 import { hookstate } from "@hookstate/core";
 import { AnalysisMessageDetails } from "./bindings/AnalysisMessageDetails";
+import { DeleteStrategy } from "./bindings/DeleteStrategy";
 import { MinFilamentJsonSchema } from "./bindings/MinFilamentJsonSchema";
 import { MinPrinterModelJsonSchema } from "./bindings/MinPrinterModelJsonSchema";
 import { MinPrinterVariantJsonSchema } from "./bindings/MinPrinterVariantJsonSchema";
 import { MinProcessJsonSchema } from "./bindings/MinProcessJsonSchema";
+import { PresetDependent } from "./bindings/PresetDependent";
 import { VendorJsonSchema } from "./bindings/VendorJsonSchema";
 import { ConfigLocationType, ConfigType } from "./commons";
 
@@ -36,6 +38,7 @@ export type NamedConfigStateType<T> = Record<string, T & fileProperty>;
 export const globalStateObject = {
   orcaInstallationPath: undefined as string | undefined,
   orcaDataDirectory: undefined as string | undefined,
+  orcaOverlayDirectory: undefined as string | undefined,
   errLoadingInstallationPath: undefined as string | undefined,
   errLoadingDataPath: undefined as string | undefined,
   os: "none",
@@ -79,6 +82,7 @@ export const globalStateObject = {
       };
       changedProps: Record<string, unknown>;
       deleteKeys: string[];
+      fileToken?: string | null;
       knownKeys: Set<string>;
       unknownKeys: Set<string>;
     }
@@ -118,6 +122,27 @@ export const appStateObject = {
       newFamily: undefined as string | undefined,
     },
   },
+  vendorCopyPopover: {
+    visible: false,
+    arguments: {
+      name: "",
+      type: "printer" as ConfigType,
+      family: "",
+    },
+    newFamily: undefined as string | undefined,
+  },
+  deletionPopover: {
+    visible: false,
+    arguments: {
+      name: "",
+      type: "printer" as ConfigType,
+      location: "installed" as ConfigLocationType,
+      family: undefined as string | undefined,
+      path: "",
+      dependents: [] as PresetDependent[],
+    },
+    strategy: "block" as DeleteStrategy,
+  },
   propertyCopyPopover: {
     visible: false,
     arguments: {